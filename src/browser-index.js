@@ -5,13 +5,29 @@ await init();
 export const diffChars = diffNative.diffChars;
 export const diffWordsWithSpace = diffNative.diffWordsWithSpace;
 export const diffWords = diffNative.diffWords;
+export const diffCodeWords = diffNative.diffCodeWords;
 export const diffLines = diffNative.diffLines;
 export const diffTrimmedLines = diffNative.diffTrimmedLines;
 export const diffSentences = diffNative.diffSentences;
 export const diffCss = diffNative.diffCss;
+export const diffCode = diffNative.diffCode;
+export const diffWithPattern = diffNative.diffWithPattern;
 export const diffJson = diffNative.diffJson;
+export const diffArrays = diffNative.diffArrays;
+export const createDiff = diffNative.createDiff;
+
+export const diffCharsAsync = diffNative.diffCharsAsync;
+export const diffWordsWithSpaceAsync = diffNative.diffWordsWithSpaceAsync;
+export const diffWordsAsync = diffNative.diffWordsAsync;
+export const diffLinesAsync = diffNative.diffLinesAsync;
+export const diffTrimmedLinesAsync = diffNative.diffTrimmedLinesAsync;
+export const diffSentencesAsync = diffNative.diffSentencesAsync;
+export const diffCssAsync = diffNative.diffCssAsync;
+export const diffCodeAsync = diffNative.diffCodeAsync;
 
 export const convertChangesToXML = diffNative.convertChangesToXML;
+export const cleanupChanges = diffNative.cleanupChanges;
+export const similarity = diffNative.similarity;
 
 export const parsePatch = diffNative.parsePatch;
 export const createPatch = diffNative.createPatch;
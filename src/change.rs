@@ -1,9 +1,142 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+use crate::diff::token::Token;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Change {
     pub value: String,
+    pub count: u32,
     pub added: bool,
     pub removed: bool,
-    pub count: u32,
+    /// Where the change starts, when the diff was asked for `positions`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+}
+
+/// Start of a change in the old and in the new input. A change missing from
+/// one side (an insertion in the old input, a deletion in the new one)
+/// starts where it would be inserted there.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Position {
+    pub old: Location,
+    pub new: Location,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    /// Index of the first token.
+    pub token: u32,
+    /// Offset in UTF-8 bytes.
+    pub byte: usize,
+    /// Offset in UTF-16 code units, as used to index JS strings.
+    pub utf16: usize,
+    /// Zero-based line.
+    pub line: u32,
+    /// Zero-based column in UTF-16 code units.
+    pub column: u32,
+}
+
+impl Location {
+    fn advance(&mut self, change: &Change) {
+        self.token += change.count;
+        self.advance_over(&change.value);
+    }
+
+    fn advance_over(&mut self, text: &str) {
+        self.byte += text.len();
+        for c in text.chars() {
+            let units = c.len_utf16();
+            self.utf16 += units;
+            if c == '\n' {
+                self.line += 1;
+                self.column = 0;
+            } else {
+                self.column += units as u32;
+            }
+        }
+    }
+}
+
+/// Sets the `position` of each change, walking the old input through the
+/// removed and unchanged values and the new input through the added and
+/// unchanged ones.
+pub fn locate(changes: &mut [Change]) {
+    let (mut old, mut new) = (Location::default(), Location::default());
+    for change in changes {
+        change.position = Some(Position { old, new });
+        if !change.added {
+            old.advance(change);
+        }
+        if !change.removed {
+            new.advance(change);
+        }
+    }
+}
+
+/// `locate` for a diff of `old_toks` against `new_toks`, which borrow from
+/// `old` and `new`. Where an input does not continue with a change's value,
+/// as for unchanged tokens that match without being identical
+/// (`ignore_case`, `ignore_whitespace`, a comparator, ...), that side moves
+/// to the end of the change's tokens instead.
+pub(crate) fn locate_tokens(
+    changes: &mut [Change],
+    (old, old_toks): (&str, &[Token]),
+    (new, new_toks): (&str, &[Token]),
+) {
+    let (mut old, mut new) = (Cursor::new(old, old_toks), Cursor::new(new, new_toks));
+    for change in changes {
+        change.position = Some(Position {
+            old: old.at,
+            new: new.at,
+        });
+        if !change.added {
+            old.take(change);
+        }
+        if !change.removed {
+            new.take(change);
+        }
+    }
+}
+
+/// Location in one input, moved forward change by change.
+struct Cursor<'s, 't> {
+    text: &'s str,
+    toks: &'t [Token<'s>],
+    at: Location,
+}
+
+impl<'s, 't> Cursor<'s, 't> {
+    fn new(text: &'s str, toks: &'t [Token<'s>]) -> Self {
+        Self {
+            text,
+            toks,
+            at: Location::default(),
+        }
+    }
+
+    /// Moves past `change`: over its value when the input continues with it,
+    /// else to the end of its tokens.
+    fn take(&mut self, change: &Change) {
+        if self.text[self.at.byte..].starts_with(change.value.as_str()) {
+            self.at.advance(change);
+            return;
+        }
+        let first = self.at.token as usize;
+        self.at.token += change.count;
+        let Some(last) = self
+            .toks
+            .get(first..self.at.token as usize)
+            .and_then(<[_]>::last)
+        else {
+            return;
+        };
+        // Tokens borrow from the input, so their address gives the offset.
+        let end = last.text.as_ptr() as usize - self.text.as_ptr() as usize + last.text.len();
+        // Word tokens can share whitespace with their neighbours.
+        if end > self.at.byte {
+            let skipped = &self.text[self.at.byte..end];
+            self.at.advance_over(skipped);
+        }
+    }
 }
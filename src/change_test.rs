@@ -0,0 +1,148 @@
+use crate::{
+    diff_chars, diff_lines, diff_words, Change, DiffLinesOptions, DiffOptions, DiffWordsOptions,
+    Location,
+};
+
+fn at(token: u32, byte: usize, utf16: usize, line: u32, column: u32) -> Location {
+    Location {
+        token,
+        byte,
+        utf16,
+        line,
+        column,
+    }
+}
+
+#[test]
+fn test_positions_count_bytes_utf16_and_lines() {
+    let opts = DiffOptions {
+        positions: true,
+        ..Default::default()
+    };
+    let changes = diff_chars("a😀\nbc", "a😀\nxc", &opts);
+    let removed = changes.iter().find(|c| c.removed).unwrap();
+    let position = removed.position.unwrap();
+    assert_eq!(removed.value, "b");
+    assert_eq!(position.old, at(3, 6, 4, 1, 0));
+    assert_eq!(position.new, at(3, 6, 4, 1, 0));
+
+    let last = changes.last().unwrap().position.unwrap();
+    assert_eq!(last.old, at(4, 7, 5, 1, 1));
+    assert_eq!(last.new, at(4, 7, 5, 1, 1));
+}
+
+#[test]
+fn test_positions_are_opt_in() {
+    let changes = diff_chars("abc", "abd", &DiffOptions::default());
+    assert!(changes.iter().all(|c| c.position.is_none()));
+    let json = serde_json::to_value(&changes[0]).unwrap();
+    assert!(json.get("position").is_none());
+}
+
+#[test]
+fn test_positions_index_both_inputs() {
+    fn check(changes: &[Change], old: &str, new: &str) {
+        let mut tokens = (0, 0);
+        for c in changes {
+            let p = c.position.unwrap();
+            assert_eq!((p.old.token, p.new.token), tokens);
+            if !c.added {
+                assert_eq!(&old[p.old.byte..p.old.byte + c.value.len()], c.value);
+                assert_eq!(old[..p.old.byte].matches('\n').count() as u32, p.old.line);
+                tokens.0 += c.count;
+            }
+            if !c.removed {
+                assert_eq!(&new[p.new.byte..p.new.byte + c.value.len()], c.value);
+                assert_eq!(new[..p.new.byte].encode_utf16().count(), p.new.utf16);
+                tokens.1 += c.count;
+            }
+        }
+    }
+
+    let old = "one two\nthree ünïcode four\nfive\n";
+    let new = "one 2\nthree ünïcode 4\nfive\nsix\n";
+    let lines = DiffLinesOptions {
+        positions: true,
+        ..Default::default()
+    };
+    check(&diff_lines(old, new, &lines), old, new);
+    let lines = DiffLinesOptions {
+        indent_heuristic: Some(true),
+        ..lines
+    };
+    check(&diff_lines(old, new, &lines), old, new);
+
+    let words = DiffWordsOptions {
+        positions: true,
+        ..Default::default()
+    };
+    check(&diff_words(old, new, &words), old, new);
+    check(&diff_words(old, old, &words), old, old);
+}
+
+#[test]
+fn test_positions_follow_each_side_under_loose_equality() {
+    let (old, new) = ("    a\nb\n", "a\nc\n");
+    let lines = DiffLinesOptions {
+        ignore_whitespace: true,
+        positions: true,
+        ..Default::default()
+    };
+    for opts in [
+        lines.clone(),
+        DiffLinesOptions {
+            indent_heuristic: Some(true),
+            ..lines.clone()
+        },
+    ] {
+        let changes = diff_lines(old, new, &opts);
+        let removed = changes.iter().find(|c| c.removed).unwrap();
+        assert_eq!(removed.value, "b\n");
+        let position = removed.position.unwrap();
+        assert_eq!(position.old, at(1, 6, 6, 1, 0));
+        assert_eq!(position.new, at(1, 2, 2, 1, 0));
+    }
+
+    let chars = DiffOptions {
+        ignore_case: true,
+        positions: true,
+        ..Default::default()
+    };
+    let changes = diff_chars("\u{17f}x", "sy", &chars);
+    let removed = changes.iter().find(|c| c.removed).unwrap();
+    assert_eq!(removed.value, "x");
+    assert_eq!(removed.position.unwrap().old.byte, 2);
+}
+
+#[test]
+fn test_word_positions_with_unequal_whitespace() {
+    let words = DiffWordsOptions {
+        positions: true,
+        ..Default::default()
+    };
+    let starts = |old: &str, new: &str| -> Vec<(String, usize, usize)> {
+        diff_words(old, new, &words)
+            .into_iter()
+            .map(|c| {
+                let p = c.position.unwrap();
+                (c.value, p.old.byte, p.new.byte)
+            })
+            .collect()
+    };
+    let owned = |v: &[(&str, usize, usize)]| -> Vec<(String, usize, usize)> {
+        v.iter().map(|&(s, o, n)| (s.to_string(), o, n)).collect()
+    };
+    assert_eq!(
+        starts("foo bar baz", "foo  qux baz"),
+        owned(&[
+            ("foo ", 0, 0),
+            ("bar", 4, 4),
+            (" qux", 7, 4),
+            (" baz", 7, 8)
+        ])
+    );
+    assert_eq!(
+        starts("a  b c d", "a b x d"),
+        owned(&[("a b ", 0, 0), ("c", 5, 4), ("x", 6, 4), (" d", 6, 5)])
+    );
+}
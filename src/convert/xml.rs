@@ -1,5 +1,4 @@
 use crate::change::Change;
-use wasm_bindgen::prelude::*;
 
 fn escape_html(s: &str) -> String {
     s.replace('&', "&amp;")
@@ -8,11 +7,7 @@ fn escape_html(s: &str) -> String {
         .replace('"', "&quot;")
 }
 
-#[wasm_bindgen(js_name = convertChangesToXML)]
-pub fn convert_changes_to_xml(changes_js: JsValue) -> Result<String, JsValue> {
-    let changes: Vec<Change> = serde_wasm_bindgen::from_value(changes_js)
-        .map_err(|e| JsValue::from_str(&format!("Failed to deserialize changes: {}", e)))?;
-
+pub fn convert_changes_to_xml(changes: &[Change]) -> String {
     let mut ret = String::new();
     for change in changes {
         if change.added {
@@ -29,5 +24,5 @@ pub fn convert_changes_to_xml(changes_js: JsValue) -> Result<String, JsValue> {
             ret.push_str("</del>");
         }
     }
-    Ok(ret)
+    ret
 }
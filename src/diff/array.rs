@@ -0,0 +1,79 @@
+use serde::Serialize;
+
+use crate::diff::{base::Options, engine::Engine};
+use crate::options::DiffOptions;
+
+/// Change over a sequence of arbitrary elements; `value` holds the elements
+/// of the run, taken from the new sequence unless the run was removed.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ArrayChange<T> {
+    pub value: Vec<T>,
+    pub count: u32,
+    pub added: bool,
+    pub removed: bool,
+}
+
+/// Myers diff of `old_len` against `new_len` elements compared with
+/// `eq(old_index, new_index)`, as run-length `(count, added, removed)` spans.
+/// Returns `None` when `max_edit_length` is exceeded.
+pub fn diff_spans<E>(
+    old_len: usize,
+    new_len: usize,
+    eq: E,
+    opts: &Options,
+) -> Option<Vec<(u32, bool, bool)>>
+where
+    E: Fn(usize, usize) -> bool,
+{
+    let mut engine = Engine::new(opts.clone());
+    engine.reset_deadline();
+    let tail = engine.myers(old_len, new_len, &eq)?;
+    Some(engine.spans(tail))
+}
+
+pub fn diff_arrays<T: PartialEq + Clone>(
+    old: &[T],
+    new: &[T],
+    opts: &DiffOptions,
+) -> Vec<ArrayChange<T>> {
+    diff_arrays_by(old, new, T::eq, opts)
+}
+
+pub fn diff_arrays_by<T, F>(old: &[T], new: &[T], eq: F, opts: &DiffOptions) -> Vec<ArrayChange<T>>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> bool,
+{
+    let Some(spans) = diff_spans(
+        old.len(),
+        new.len(),
+        |i, j| eq(&old[i], &new[j]),
+        &opts.into(),
+    ) else {
+        return Vec::new();
+    };
+
+    let (mut old_pos, mut new_pos) = (0usize, 0usize);
+    spans
+        .into_iter()
+        .map(|(count, added, removed)| {
+            let n = count as usize;
+            let value = if removed {
+                old_pos += n;
+                old[old_pos - n..old_pos].to_vec()
+            } else {
+                new_pos += n;
+                if !added {
+                    old_pos += n;
+                }
+                new[new_pos - n..new_pos].to_vec()
+            };
+            ArrayChange {
+                value,
+                count,
+                added,
+                removed,
+            }
+        })
+        .collect()
+}
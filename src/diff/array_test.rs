@@ -0,0 +1,124 @@
+use super::array::{diff_arrays, diff_arrays_by, ArrayChange};
+use crate::options::DiffOptions;
+
+fn change<T>(value: Vec<T>, added: bool, removed: bool) -> ArrayChange<T> {
+    ArrayChange {
+        count: value.len() as u32,
+        value,
+        added,
+        removed,
+    }
+}
+
+#[test]
+fn test_numbers() {
+    let changes = diff_arrays(&[1, 2, 3], &[1, 4, 3], &DiffOptions::default());
+    assert_eq!(
+        changes,
+        vec![
+            change(vec![1], false, false),
+            change(vec![2], false, true),
+            change(vec![4], true, false),
+            change(vec![3], false, false),
+        ]
+    );
+}
+
+#[test]
+fn test_strings() {
+    let old = ["a", "b", "c", "d"];
+    let new = ["b", "c", "e"];
+    let changes = diff_arrays(&old, &new, &DiffOptions::default());
+    assert_eq!(
+        changes,
+        vec![
+            change(vec!["a"], false, true),
+            change(vec!["b", "c"], false, false),
+            change(vec!["d"], false, true),
+            change(vec!["e"], true, false),
+        ]
+    );
+}
+
+#[test]
+fn test_empty_sides() {
+    let opts = DiffOptions::default();
+    assert!(diff_arrays::<u8>(&[], &[], &opts).is_empty());
+    assert_eq!(
+        diff_arrays(&[], &[1, 2], &opts),
+        vec![change(vec![1, 2], true, false)]
+    );
+    assert_eq!(
+        diff_arrays(&[1, 2], &[], &opts),
+        vec![change(vec![1, 2], false, true)]
+    );
+}
+
+#[test]
+fn test_comparator_keeps_new_values_for_common_runs() {
+    let old = [(1, "old"), (2, "x")];
+    let new = [(1, "new"), (3, "y")];
+    let changes = diff_arrays_by(&old, &new, |l, r| l.0 == r.0, &DiffOptions::default());
+    assert_eq!(changes[0], change(vec![(1, "new")], false, false));
+    assert_eq!(changes[1], change(vec![(2, "x")], false, true));
+    assert_eq!(changes[2], change(vec![(3, "y")], true, false));
+}
+
+#[test]
+fn test_one_change_per_token() {
+    let opts = DiffOptions {
+        one_change_per_token: true,
+        ..Default::default()
+    };
+    let changes = diff_arrays(&[1, 2], &[1, 2, 3], &opts);
+    assert_eq!(changes.len(), 3);
+    assert!(changes.iter().all(|c| c.count == 1));
+}
+
+#[test]
+fn test_max_edit_length() {
+    let opts = DiffOptions {
+        max_edit_length: Some(1),
+        ..Default::default()
+    };
+    assert!(diff_arrays(&[1, 2, 3], &[4, 5, 6], &opts).is_empty());
+}
+
+#[test]
+fn test_linear_space_matches_greedy() {
+    let mut rng = fastrand::Rng::with_seed(11);
+    let old: Vec<u8> = (0..300).map(|_| rng.u8(0..4)).collect();
+    let new: Vec<u8> = (0..300).map(|_| rng.u8(0..4)).collect();
+
+    let greedy = diff_arrays(&old, &new, &DiffOptions::default());
+    let linear = diff_arrays(
+        &old,
+        &new,
+        &DiffOptions {
+            linear_space_threshold: Some(0),
+            ..Default::default()
+        },
+    );
+    let edits = |c: &[ArrayChange<u8>]| -> u32 {
+        c.iter()
+            .filter(|c| c.added || c.removed)
+            .map(|c| c.count)
+            .sum()
+    };
+    assert_eq!(edits(&greedy), edits(&linear));
+
+    for changes in [greedy, linear] {
+        let rebuilt_old: Vec<u8> = changes
+            .iter()
+            .filter(|c| !c.added)
+            .flat_map(|c| c.value.clone())
+            .collect();
+        let rebuilt_new: Vec<u8> = changes
+            .iter()
+            .filter(|c| !c.removed)
+            .flat_map(|c| c.value.clone())
+            .collect();
+        assert_eq!(rebuilt_old, old);
+        assert_eq!(rebuilt_new, new);
+    }
+}
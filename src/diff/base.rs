@@ -1,25 +1,116 @@
 use core::marker::PhantomData;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+use std::rc::Rc;
 
+use super::case::{eq_ignore_case, fold_case, CaseLocale};
+use super::cleanup::Cleanup;
+use super::normalize::Normalizer;
 use super::token::Token;
+pub use crate::change::Change;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Algorithm {
+    #[default]
+    Myers,
+    /// Anchors on tokens that occur exactly once on both sides and falls back
+    /// to Myers between anchors.
+    Patience,
+    /// Git's histogram diff: like patience, but anchors on the rarest common
+    /// run rather than only on unique tokens.
+    Histogram,
+}
+
+/// Caller-supplied token equality, replacing `Tokeniser::equals` (and with
+/// it `ignore_case`). Receives the old and the new token text.
+#[derive(Clone)]
+pub struct Comparator(Rc<CompareToken>);
+
+type CompareToken = dyn Fn(&str, &str) -> bool;
+
+impl Comparator {
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(&str, &str) -> bool + 'static,
+    {
+        Self(Rc::new(f))
+    }
+
+    #[inline(always)]
+    pub fn call(&self, l: &str, r: &str) -> bool {
+        (self.0)(l, r)
+    }
+}
+
+impl fmt::Debug for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Comparator(..)")
+    }
+}
+
+/// Options that accept a `Comparator`.
+pub trait WithComparator: Sized {
+    fn comparator_mut(&mut self) -> &mut Option<Comparator>;
+
+    /// Compares tokens with `comparator` instead of the built-in equality.
+    #[inline]
+    fn with_comparator<F>(mut self, comparator: F) -> Self
+    where
+        F: Fn(&str, &str) -> bool + 'static,
+    {
+        *self.comparator_mut() = Some(Comparator::new(comparator));
+        self
+    }
+}
 
 #[derive(Default, Debug, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Options {
     #[serde(default)]
     pub ignore_case: bool,
+    /// Locale tailoring of `ignore_case`, parsed from the option structs'
+    /// `locale`: a BCP 47 language tag, e.g. `"tr"` for Turkish dotted and
+    /// dotless i.
+    #[serde(skip)]
+    pub case_locale: CaseLocale,
     #[serde(default)]
     pub one_change_per_token: bool,
     #[serde(default)]
     pub max_edit_length: Option<usize>,
+    #[serde(default)]
+    pub algorithm: Algorithm,
+    /// Combined token count above which Myers runs in linear space; defaults
+    /// to `linear::LINEAR_SPACE_THRESHOLD`.
+    #[serde(default)]
+    pub linear_space_threshold: Option<usize>,
+    /// Milliseconds after which the search stops and the remaining input is
+    /// diffed coarsely. The result is still a valid diff, just not minimal.
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    /// Cleanup pass run by the character and word tokenizers. Skipped under
+    /// `ignore_case`, normalizers or a comparator.
+    #[serde(default)]
+    pub cleanup: Cleanup,
+    /// Fill in `Change::position`.
+    #[serde(default)]
+    pub positions: bool,
+    /// Overrides token equality. Patience and histogram need hashable tokens,
+    /// so setting this makes them fall back to Myers.
+    #[serde(skip)]
+    pub comparator: Option<Comparator>,
+    /// Applied to tokens before they are compared; the output keeps the
+    /// original text. Ignored when `comparator` is set.
+    #[serde(skip)]
+    pub normalizers: Vec<Normalizer>,
 }
 
-#[derive(Serialize, Debug, Clone)]
-pub struct Change {
-    pub value: String,
-    pub count: u32,
-    pub added: bool,
-    pub removed: bool,
+impl WithComparator for Options {
+    #[inline]
+    fn comparator_mut(&mut self) -> &mut Option<Comparator> {
+        &mut self.comparator
+    }
 }
 
 pub trait Tokeniser<'a> {
@@ -56,22 +147,35 @@ pub trait Tokeniser<'a> {
             }
         }
         if opts.ignore_case {
-            ascii_eq_ignore_case(l.text.as_bytes(), r.text.as_bytes())
+            eq_ignore_case(l.text, r.text, opts.case_locale)
         } else {
             l.text == r.text
         }
     }
 
+    /// Normalised form of a token: two tokens must have equal keys exactly
+    /// when `equals` considers them equal. Used to hash tokens.
+    #[inline]
+    fn comparison_key(&self, tok: &Token<'a>, opts: &Options) -> Cow<'a, str> {
+        if opts.ignore_case {
+            fold_case(tok.text, opts.case_locale)
+        } else {
+            Cow::Borrowed(tok.text)
+        }
+    }
+
+    /// Whether tokens are interned into integer IDs (via `comparison_key`)
+    /// before diffing. Worth it when `equals` normalises or allocates.
+    #[inline(always)]
+    fn interned(&self, _opts: &Options) -> bool {
+        false
+    }
+
     fn post_process(&self, changes: Vec<Change>, _opts: &Options) -> Vec<Change> {
         changes
     }
 }
 
-#[inline(always)]
-fn ascii_eq_ignore_case(a: &[u8], b: &[u8]) -> bool {
-    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.eq_ignore_ascii_case(y))
-}
-
 #[derive(Clone, Copy, Debug)]
 struct Component {
     count: u32,
@@ -161,6 +265,7 @@ impl<'a, T: Tokeniser<'a>> Diff<'a, T> {
                     count: b_len as u32,
                     added: true,
                     removed: false,
+                    position: None,
                 }],
                 &self.opts,
             );
@@ -172,6 +277,7 @@ impl<'a, T: Tokeniser<'a>> Diff<'a, T> {
                     count: a_len as u32,
                     added: false,
                     removed: true,
+                    position: None,
                 }],
                 &self.opts,
             );
@@ -276,6 +382,7 @@ impl<'a, T: Tokeniser<'a>> Diff<'a, T> {
                 count: c.count,
                 added: c.added,
                 removed: c.removed,
+                position: None,
             });
         }
 
@@ -0,0 +1,65 @@
+use caseless::Caseless;
+use std::borrow::Cow;
+
+/// Locale tailoring of `ignore_case`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseLocale {
+    /// Unicode default full case folding.
+    #[default]
+    Root,
+    /// Turkish and Azerbaijani: `I` pairs with dotless `ı` and `İ` with `i`.
+    Turkic,
+}
+
+impl CaseLocale {
+    /// Locale for a BCP 47 tag such as `"tr"` or `"az-Latn"`; only the
+    /// language subtag matters.
+    pub fn from_tag(tag: &str) -> Self {
+        let language = tag.split(['-', '_']).next().unwrap_or_default();
+        if language.eq_ignore_ascii_case("tr") || language.eq_ignore_ascii_case("az") {
+            CaseLocale::Turkic
+        } else {
+            CaseLocale::Root
+        }
+    }
+
+    pub(crate) fn from_option(tag: Option<&str>) -> Self {
+        tag.map_or(CaseLocale::Root, CaseLocale::from_tag)
+    }
+
+    /// Whether ASCII text folds like `to_ascii_lowercase`.
+    #[inline(always)]
+    fn ascii_folds(self, text: &str) -> bool {
+        text.is_ascii() && (self == CaseLocale::Root || !text.contains('I'))
+    }
+}
+
+/// Whether `a` and `b` are equal under full Unicode case folding.
+#[inline]
+pub fn eq_ignore_case(a: &str, b: &str, locale: CaseLocale) -> bool {
+    if locale.ascii_folds(a) && locale.ascii_folds(b) {
+        return a.eq_ignore_ascii_case(b);
+    }
+    fold(a, locale).eq(fold(b, locale))
+}
+
+/// Case-folded `text`, borrowed when folding leaves it unchanged.
+pub fn fold_case(text: &str, locale: CaseLocale) -> Cow<'_, str> {
+    if locale.ascii_folds(text) {
+        if text.bytes().any(|b| b.is_ascii_uppercase()) {
+            return Cow::Owned(text.to_ascii_lowercase());
+        }
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(fold(text, locale).collect())
+}
+
+fn fold(text: &str, locale: CaseLocale) -> impl Iterator<Item = char> + '_ {
+    text.chars()
+        .map(move |c| match (locale, c) {
+            (CaseLocale::Turkic, 'I') => 'ı',
+            (CaseLocale::Turkic, 'İ') => 'i',
+            _ => c,
+        })
+        .default_case_fold()
+}
@@ -0,0 +1,62 @@
+use super::case::{eq_ignore_case, fold_case, CaseLocale};
+use crate::{diff_chars, diff_lines, diff_words, DiffLinesOptions, DiffOptions, DiffWordsOptions};
+
+#[test]
+fn test_full_case_folding() {
+    let root = CaseLocale::Root;
+    assert!(eq_ignore_case("Straße", "STRASSE", root));
+    assert!(eq_ignore_case("Ωμέγα", "ΩΜΈΓΑ", root));
+    assert!(eq_ignore_case("Hello", "hELLO", root));
+    assert!(eq_ignore_case("\u{212A}elvin", "kelvin", root));
+    assert!(!eq_ignore_case("Straße", "STRASE", root));
+
+    assert_eq!(fold_case("abc", root), "abc");
+    assert_eq!(fold_case("ABC", root), "abc");
+    assert_eq!(fold_case("Straße", root), "strasse");
+}
+
+#[test]
+fn test_turkic_locale() {
+    let tr = CaseLocale::from_tag("tr-TR");
+    assert_eq!(tr, CaseLocale::Turkic);
+    assert_eq!(CaseLocale::from_tag("az"), CaseLocale::Turkic);
+    assert_eq!(CaseLocale::from_tag("en"), CaseLocale::Root);
+
+    assert!(eq_ignore_case("ISPARTA", "ısparta", tr));
+    assert!(eq_ignore_case("İSTANBUL", "istanbul", tr));
+    assert!(!eq_ignore_case("ISTANBUL", "istanbul", tr));
+    assert!(eq_ignore_case("ISTANBUL", "istanbul", CaseLocale::Root));
+    assert_eq!(fold_case("DIŞ", tr), "dış");
+}
+
+#[test]
+fn test_diffs_ignore_unicode_case() {
+    let words = DiffWordsOptions {
+        ignore_case: true,
+        ..Default::default()
+    };
+    let changes = diff_words("Die Straße ist lang", "DIE STRASSE IST LANG", &words);
+    assert_eq!(changes.len(), 1);
+    assert!(!changes[0].added && !changes[0].removed);
+
+    let changes = diff_words("Ωμέγα one", "ΩΜΈΓΑ two", &words);
+    assert!(!changes[0].added && !changes[0].removed);
+    assert_eq!(changes[0].value, "ΩΜΈΓΑ ");
+
+    let lines = DiffLinesOptions {
+        ignore_case: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        diff_lines("Straße\nÉté\n", "STRASSE\néTÉ\n", &lines).len(),
+        1
+    );
+
+    let turkish = DiffOptions {
+        ignore_case: true,
+        locale: Some("tr".into()),
+        ..Default::default()
+    };
+    assert_eq!(diff_chars("Iİ", "ıi", &turkish).len(), 1);
+    assert_eq!(diff_chars("I", "i", &turkish).len(), 2);
+}
@@ -1,15 +1,33 @@
-use wasm_bindgen::prelude::*;
-
 use crate::diff::{
-    base::{Options as BaseOptions, Tokeniser},
+    base::{Change, Options, Tokeniser},
+    cleanup::{self, cleanup},
     memory_pool::PooledDiff,
     token::Token,
 };
 use crate::options::DiffOptions;
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// What `diff_chars` counts as one character.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    /// Unicode scalar values, like jsdiff.
+    #[default]
+    Codepoint,
+    /// Extended grapheme clusters, so emoji sequences, flags and combining
+    /// accents stay whole. `cleanup` is not run.
+    Grapheme,
+}
 
 #[derive(Clone, Copy)]
 pub struct CharTokenizer;
 
+/// `CharTokenizer` over extended grapheme clusters. Runs no `cleanup`, which
+/// moves edit boundaries by code point and could split a cluster again.
+#[derive(Clone, Copy)]
+pub struct GraphemeTokenizer;
+
 impl<'a> Tokeniser<'a> for CharTokenizer {
     fn tokenize<'b>(&self, input: &'a str, arena: &'b mut Vec<Token<'a>>) -> &'b [Token<'a>] {
         arena.clear();
@@ -40,18 +58,36 @@ impl<'a> Tokeniser<'a> for CharTokenizer {
         }
         out
     }
+
+    fn post_process(&self, changes: Vec<Change>, opts: &Options) -> Vec<Change> {
+        if opts.one_change_per_token {
+            return changes;
+        }
+        cleanup(changes, cleanup::mode(opts, false), |s| {
+            s.chars().count() as u32
+        })
+    }
 }
 
-#[wasm_bindgen(js_name = diffChars)]
-pub fn diff_chars(old_str: &str, new_str: &str, options: JsValue) -> JsValue {
-    let opts: DiffOptions = serde_wasm_bindgen::from_value(options).unwrap_or_default();
-    let base_opts = BaseOptions {
-        ignore_case: opts.ignore_case.unwrap_or(false),
-        one_change_per_token: opts.one_change_per_token.unwrap_or(false),
-        max_edit_length: None,
-    };
-
-    let mut diff = PooledDiff::new(CharTokenizer, base_opts);
-    let result = diff.diff(old_str, new_str);
-    serde_wasm_bindgen::to_value(&result).unwrap()
+impl<'a> Tokeniser<'a> for GraphemeTokenizer {
+    fn tokenize<'b>(&self, input: &'a str, arena: &'b mut Vec<Token<'a>>) -> &'b [Token<'a>] {
+        arena.clear();
+        arena.extend(input.graphemes(true).map(|text| Token { text }));
+        &arena[..]
+    }
+
+    fn join(&self, toks: &[Token<'a>]) -> String {
+        CharTokenizer.join(toks)
+    }
+}
+
+pub fn diff_chars(old_str: &str, new_str: &str, opts: &DiffOptions) -> Vec<Change> {
+    match opts.granularity {
+        Granularity::Codepoint => {
+            PooledDiff::new(CharTokenizer, opts.into()).diff(old_str, new_str)
+        }
+        Granularity::Grapheme => {
+            PooledDiff::new(GraphemeTokenizer, opts.into()).diff(old_str, new_str)
+        }
+    }
 }
@@ -208,6 +208,7 @@ fn test_case_sensitive_equals() {
         ignore_case: false,
         one_change_per_token: false,
         max_edit_length: None,
+        ..Default::default()
     };
 
     let a1 = Token { text: "a" };
@@ -230,6 +231,7 @@ fn test_case_insensitive_equals() {
         ignore_case: true,
         one_change_per_token: false,
         max_edit_length: None,
+        ..Default::default()
     };
 
     let a1 = Token { text: "a" };
@@ -277,3 +279,90 @@ fn test_character_diff_cases() {
         assert_eq!(tokens3.len(), 4);
     }
 }
+
+#[test]
+fn test_diff_chars_native_api() {
+    use crate::{diff_chars, Change, DiffOptions};
+
+    let changes = diff_chars("abc", "axc", &DiffOptions::default());
+    assert_eq!(
+        changes,
+        vec![
+            Change {
+                value: "a".into(),
+                count: 1,
+                added: false,
+                removed: false,
+                position: None,
+            },
+            Change {
+                value: "b".into(),
+                count: 1,
+                added: false,
+                removed: true,
+                position: None,
+            },
+            Change {
+                value: "x".into(),
+                count: 1,
+                added: true,
+                removed: false,
+                position: None,
+            },
+            Change {
+                value: "c".into(),
+                count: 1,
+                added: false,
+                removed: false,
+                position: None,
+            },
+        ]
+    );
+
+    let opts = DiffOptions {
+        ignore_case: true,
+        ..Default::default()
+    };
+    let changes = diff_chars("ABC", "abc", &opts);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].value, "abc");
+    assert_eq!(changes[0].count, 3);
+}
+
+#[test]
+fn test_grapheme_granularity() {
+    use crate::{diff_chars, Cleanup, DiffOptions, Granularity};
+
+    let family = "👨\u{200D}👩\u{200D}👧";
+    let (old, new) = (format!("a{family}e\u{301}"), "a👨e\u{301}🇫🇷");
+    let opts = DiffOptions {
+        granularity: Granularity::Grapheme,
+        ..Default::default()
+    };
+    let changes = diff_chars(&old, new, &opts);
+    let summary: Vec<(&str, u32, bool, bool)> = changes
+        .iter()
+        .map(|c| (c.value.as_str(), c.count, c.added, c.removed))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("a", 1, false, false),
+            (family, 1, false, true),
+            ("👨", 1, true, false),
+            ("e\u{301}", 1, false, false),
+            ("🇫🇷", 1, true, false),
+        ]
+    );
+
+    // Cleanup would move edit boundaries by code point, so it is not run.
+    let cleaned = DiffOptions {
+        cleanup: Cleanup::Semantic,
+        ..opts.clone()
+    };
+    assert_eq!(diff_chars(&old, new, &cleaned), changes);
+
+    // By code point the shared leading emoji is kept and the cluster split.
+    let changes = diff_chars(&old, new, &DiffOptions::default());
+    assert_eq!(changes[0].value, "a👨");
+}
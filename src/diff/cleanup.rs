@@ -0,0 +1,535 @@
+use serde::{Deserialize, Serialize};
+
+use super::base::{Change, Options};
+
+/// Post-processing of character and word diffs, after diff-match-patch's
+/// `diff_cleanupSemantic` and `diff_cleanupEfficiency`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Cleanup {
+    #[default]
+    None,
+    /// Folds short coincidental equalities into the edits around them and
+    /// shifts edits to word and line boundaries, for human readers.
+    Semantic,
+    /// Folds equalities that cost more to show than to re-type.
+    Efficiency,
+}
+
+/// Cost in characters of an edit when weighing equalities for
+/// `Cleanup::Efficiency`.
+const EDIT_COST: usize = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Delete,
+    Equal,
+    Insert,
+}
+
+/// Runs `mode` over `changes`. Changes whose text is rewritten are
+/// recounted with `count`; the others keep their count.
+pub fn cleanup<F>(changes: Vec<Change>, mode: Cleanup, count: F) -> Vec<Change>
+where
+    F: Fn(&str) -> u32,
+{
+    if mode == Cleanup::None || changes.len() <= 1 {
+        return changes;
+    }
+
+    // A count of 0 marks a change to be recounted at the end.
+    let mut diffs = changes;
+    match mode {
+        Cleanup::None => {}
+        Cleanup::Semantic => semantic(&mut diffs),
+        Cleanup::Efficiency => efficiency(&mut diffs),
+    }
+    for change in &mut diffs {
+        if change.count == 0 {
+            change.count = count(&change.value);
+        }
+    }
+    diffs
+}
+
+/// `cleanup` for changes of any origin; rewritten changes are counted in
+/// characters.
+pub fn cleanup_changes(changes: Vec<Change>, mode: Cleanup) -> Vec<Change> {
+    cleanup(changes, mode, |s| s.chars().count() as u32)
+}
+
+/// The pass to run for `opts`. Skipped when tokens can match without being
+/// identical (`ignore_case`, normalizers, a comparator, or a tokenizer whose
+/// tokens compare with whitespace trimmed, as `trims` says): an equal change
+/// only carries the new text, so folding it into an edit would report text
+/// the old input never had.
+pub(crate) fn mode(opts: &Options, trims: bool) -> Cleanup {
+    if trims || opts.ignore_case || !opts.normalizers.is_empty() || opts.comparator.is_some() {
+        Cleanup::None
+    } else {
+        opts.cleanup
+    }
+}
+
+#[inline]
+fn op(c: &Change) -> Op {
+    if c.added {
+        Op::Insert
+    } else if c.removed {
+        Op::Delete
+    } else {
+        Op::Equal
+    }
+}
+
+fn edit(op: Op, value: String) -> Change {
+    Change {
+        value,
+        count: 0,
+        added: op == Op::Insert,
+        removed: op == Op::Delete,
+        position: None,
+    }
+}
+
+#[inline]
+fn chars(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// Turns the equality at `at` into a deletion followed by an insertion of
+/// the same text, and the edit after it into an insertion.
+fn split_equality(diffs: &mut Vec<Change>, at: usize) {
+    let text = std::mem::take(&mut diffs[at].value);
+    diffs[at] = edit(Op::Delete, text.clone());
+    diffs.insert(at + 1, edit(Op::Insert, text));
+}
+
+fn semantic(diffs: &mut Vec<Change>) {
+    let mut changed = false;
+    let mut equalities: Vec<usize> = Vec::new();
+    let mut last_equality: Option<usize> = None;
+    // Edit lengths before and after the last equality.
+    let (mut ins_before, mut del_before) = (0, 0);
+    let (mut ins_after, mut del_after) = (0, 0);
+
+    let mut pointer = 0isize;
+    while (pointer as usize) < diffs.len() {
+        let p = pointer as usize;
+        match op(&diffs[p]) {
+            Op::Equal => {
+                equalities.push(p);
+                ins_before = ins_after;
+                del_before = del_after;
+                ins_after = 0;
+                del_after = 0;
+                last_equality = Some(chars(&diffs[p].value));
+            }
+            edit_op => {
+                if edit_op == Op::Insert {
+                    ins_after += chars(&diffs[p].value);
+                } else {
+                    del_after += chars(&diffs[p].value);
+                }
+                let fold = last_equality.is_some_and(|len| {
+                    len > 0 && len <= ins_before.max(del_before) && len <= ins_after.max(del_after)
+                });
+                if fold {
+                    split_equality(diffs, equalities.pop().unwrap());
+                    equalities.pop();
+                    pointer = equalities.last().map_or(-1, |&e| e as isize);
+                    (ins_before, del_before, ins_after, del_after) = (0, 0, 0, 0);
+                    last_equality = None;
+                    changed = true;
+                }
+            }
+        }
+        pointer += 1;
+    }
+
+    if changed {
+        merge(diffs);
+    }
+    semantic_lossless(diffs);
+    eliminate_overlaps(diffs);
+}
+
+/// Splits off the overlap of adjacent deletions and insertions as an
+/// equality when it is at least half of either, e.g. `-abcxxx +xxxdef`
+/// becomes `-abc =xxx +def`.
+fn eliminate_overlaps(diffs: &mut Vec<Change>) {
+    let mut pointer = 1;
+    while pointer < diffs.len() {
+        if op(&diffs[pointer - 1]) == Op::Delete && op(&diffs[pointer]) == Op::Insert {
+            let deletion = diffs[pointer - 1].value.clone();
+            let insertion = diffs[pointer].value.clone();
+            let (del_len, ins_len) = (chars(&deletion), chars(&insertion));
+            let overlap1 = common_overlap(&deletion, &insertion);
+            let overlap2 = common_overlap(&insertion, &deletion);
+            if overlap1 >= overlap2 {
+                if 2 * overlap1 >= del_len || 2 * overlap1 >= ins_len {
+                    let (del_head, _) = split_at_char(&deletion, del_len - overlap1);
+                    let (common, ins_tail) = split_at_char(&insertion, overlap1);
+                    diffs[pointer - 1] = edit(Op::Delete, del_head.to_string());
+                    diffs[pointer] = edit(Op::Insert, ins_tail.to_string());
+                    diffs.insert(pointer, edit(Op::Equal, common.to_string()));
+                    pointer += 1;
+                }
+            } else if 2 * overlap2 >= del_len || 2 * overlap2 >= ins_len {
+                let (ins_head, _) = split_at_char(&insertion, ins_len - overlap2);
+                let (common, del_tail) = split_at_char(&deletion, overlap2);
+                diffs[pointer - 1] = edit(Op::Insert, ins_head.to_string());
+                diffs[pointer] = edit(Op::Delete, del_tail.to_string());
+                diffs.insert(pointer, edit(Op::Equal, common.to_string()));
+                pointer += 1;
+            }
+            pointer += 1;
+        }
+        pointer += 1;
+    }
+    diffs.retain(|c| !c.value.is_empty());
+}
+
+/// Shifts single edits between two equalities sideways to the position that
+/// best lines up with word, sentence and line boundaries.
+fn semantic_lossless(diffs: &mut Vec<Change>) {
+    let mut pointer = 1;
+    while pointer + 1 < diffs.len() {
+        if op(&diffs[pointer - 1]) != Op::Equal || op(&diffs[pointer + 1]) != Op::Equal {
+            pointer += 1;
+            continue;
+        }
+
+        let mut equality1 = diffs[pointer - 1].value.clone();
+        let mut edit_text = diffs[pointer].value.clone();
+        let mut equality2 = diffs[pointer + 1].value.clone();
+
+        // Shift the edit as far left as possible first.
+        let offset = common_suffix(&equality1, &edit_text);
+        if offset > 0 {
+            let common = edit_text[edit_text.len() - offset..].to_string();
+            equality1.truncate(equality1.len() - offset);
+            edit_text = format!("{common}{}", &edit_text[..edit_text.len() - offset]);
+            equality2 = format!("{common}{equality2}");
+        }
+
+        // Then step right one character at a time, keeping the best score.
+        let mut best = (equality1.clone(), edit_text.clone(), equality2.clone());
+        let mut best_score = score(&equality1, &edit_text) + score(&edit_text, &equality2);
+        while let (Some(a), Some(b)) = (edit_text.chars().next(), equality2.chars().next()) {
+            if a != b {
+                break;
+            }
+            equality1.push(a);
+            edit_text.drain(..a.len_utf8());
+            edit_text.push(b);
+            equality2.drain(..b.len_utf8());
+            let s = score(&equality1, &edit_text) + score(&edit_text, &equality2);
+            // `>=` favours the rightmost of equally good positions.
+            if s >= best_score {
+                best_score = s;
+                best = (equality1.clone(), edit_text.clone(), equality2.clone());
+            }
+        }
+
+        let (best1, best_edit, best2) = best;
+        if diffs[pointer - 1].value != best1 {
+            let edit_op = op(&diffs[pointer]);
+            diffs[pointer] = edit(edit_op, best_edit);
+            if best2.is_empty() {
+                diffs.remove(pointer + 1);
+            } else {
+                diffs[pointer + 1] = edit(Op::Equal, best2);
+            }
+            if best1.is_empty() {
+                diffs.remove(pointer - 1);
+                pointer -= 1;
+            } else {
+                diffs[pointer - 1] = edit(Op::Equal, best1);
+            }
+        }
+        pointer += 1;
+    }
+}
+
+/// How well the boundary between `one` and `two` falls on a natural break:
+/// 6 at the edges, 5 at blank lines, 4 at line breaks, 3 after a sentence,
+/// 2 at whitespace, 1 at other punctuation and 0 inside a word.
+fn score(one: &str, two: &str) -> u32 {
+    let (Some(c1), Some(c2)) = (one.chars().next_back(), two.chars().next()) else {
+        return 6;
+    };
+    let non_alnum1 = !c1.is_alphanumeric();
+    let non_alnum2 = !c2.is_alphanumeric();
+    let space1 = non_alnum1 && c1.is_whitespace();
+    let space2 = non_alnum2 && c2.is_whitespace();
+    let break1 = space1 && (c1 == '\r' || c1 == '\n');
+    let break2 = space2 && (c2 == '\r' || c2 == '\n');
+    let blank1 = break1 && (one.ends_with("\n\n") || one.ends_with("\n\r\n"));
+    let blank2 = break2
+        && ["\n\n", "\n\r\n", "\r\n\n", "\r\n\r\n"]
+            .iter()
+            .any(|p| two.starts_with(p));
+
+    if blank1 || blank2 {
+        5
+    } else if break1 || break2 {
+        4
+    } else if non_alnum1 && !space1 && space2 {
+        3
+    } else if space1 || space2 {
+        2
+    } else if non_alnum1 || non_alnum2 {
+        1
+    } else {
+        0
+    }
+}
+
+fn efficiency(diffs: &mut Vec<Change>) {
+    let mut changed = false;
+    let mut equalities: Vec<usize> = Vec::new();
+    let mut last_equality: Option<usize> = None;
+    // Whether there is an insertion or deletion before or after the last
+    // equality.
+    let (mut pre_ins, mut pre_del) = (false, false);
+    let (mut post_ins, mut post_del) = (false, false);
+
+    let mut pointer = 0isize;
+    while (pointer as usize) < diffs.len() {
+        let p = pointer as usize;
+        match op(&diffs[p]) {
+            Op::Equal => {
+                let len = chars(&diffs[p].value);
+                if len < EDIT_COST && (post_ins || post_del) {
+                    equalities.push(p);
+                    pre_ins = post_ins;
+                    pre_del = post_del;
+                    last_equality = Some(len);
+                } else {
+                    equalities.clear();
+                    last_equality = None;
+                }
+                post_ins = false;
+                post_del = false;
+            }
+            edit_op => {
+                if edit_op == Op::Delete {
+                    post_del = true;
+                } else {
+                    post_ins = true;
+                }
+                let sides = [pre_ins, pre_del, post_ins, post_del]
+                    .iter()
+                    .filter(|&&b| b)
+                    .count();
+                let fold = last_equality
+                    .is_some_and(|len| sides == 4 || (2 * len < EDIT_COST && sides == 3));
+                if fold {
+                    split_equality(diffs, equalities.pop().unwrap());
+                    last_equality = None;
+                    if pre_ins && pre_del {
+                        post_ins = true;
+                        post_del = true;
+                        equalities.clear();
+                    } else {
+                        equalities.pop();
+                        pointer = equalities.last().map_or(-1, |&e| e as isize);
+                        post_ins = false;
+                        post_del = false;
+                    }
+                    changed = true;
+                }
+            }
+        }
+        pointer += 1;
+    }
+
+    if changed {
+        merge(diffs);
+    }
+}
+
+/// Normalises `diffs`: merges runs of the same kind, orders deletions before
+/// insertions, factors text common to both out into the neighbouring
+/// equalities and shifts single edits over equalities they repeat.
+fn merge(diffs: &mut Vec<Change>) {
+    diffs.push(edit(Op::Equal, String::new()));
+    let mut pointer = 0;
+    let (mut count_delete, mut count_insert) = (0, 0);
+    let (mut text_delete, mut text_insert) = (String::new(), String::new());
+    // Token counts of the merged edits while they stay exact.
+    let (mut tokens_delete, mut tokens_insert) = (Some(0), Some(0));
+
+    while pointer < diffs.len() {
+        match op(&diffs[pointer]) {
+            Op::Insert => {
+                count_insert += 1;
+                text_insert.push_str(&diffs[pointer].value);
+                tokens_insert = add_count(tokens_insert, diffs[pointer].count);
+                pointer += 1;
+            }
+            Op::Delete => {
+                count_delete += 1;
+                text_delete.push_str(&diffs[pointer].value);
+                tokens_delete = add_count(tokens_delete, diffs[pointer].count);
+                pointer += 1;
+            }
+            Op::Equal => {
+                if count_delete + count_insert > 1 {
+                    if count_delete != 0 && count_insert != 0 {
+                        let common = common_prefix(&text_insert, &text_delete);
+                        if common != 0 {
+                            let x = pointer as isize - count_delete - count_insert - 1;
+                            let prefix = text_insert[..common].to_string();
+                            if x >= 0 && op(&diffs[x as usize]) == Op::Equal {
+                                let before = &mut diffs[x as usize];
+                                before.value.push_str(&prefix);
+                                before.count = 0;
+                            } else {
+                                diffs.insert(0, edit(Op::Equal, prefix));
+                                pointer += 1;
+                            }
+                            text_insert.drain(..common);
+                            text_delete.drain(..common);
+                            (tokens_insert, tokens_delete) = (None, None);
+                        }
+                        let common = common_suffix(&text_insert, &text_delete);
+                        if common != 0 {
+                            let suffix = &text_insert[text_insert.len() - common..];
+                            let after = &mut diffs[pointer];
+                            after.value.insert_str(0, suffix);
+                            after.count = 0;
+                            text_insert.truncate(text_insert.len() - common);
+                            text_delete.truncate(text_delete.len() - common);
+                            (tokens_insert, tokens_delete) = (None, None);
+                        }
+                    }
+
+                    let mut ops = Vec::with_capacity(2);
+                    if !text_delete.is_empty() {
+                        let mut c = edit(Op::Delete, std::mem::take(&mut text_delete));
+                        c.count = tokens_delete.unwrap_or(0);
+                        ops.push(c);
+                    }
+                    if !text_insert.is_empty() {
+                        let mut c = edit(Op::Insert, std::mem::take(&mut text_insert));
+                        c.count = tokens_insert.unwrap_or(0);
+                        ops.push(c);
+                    }
+                    let start = pointer - (count_delete + count_insert) as usize;
+                    let added = ops.len();
+                    diffs.splice(start..pointer, ops);
+                    pointer = start + added + 1;
+                } else if pointer != 0 && op(&diffs[pointer - 1]) == Op::Equal {
+                    let current = diffs.remove(pointer);
+                    let previous = &mut diffs[pointer - 1];
+                    previous.value.push_str(&current.value);
+                    previous.count = match (previous.count, current.count) {
+                        (0, _) | (_, 0) => 0,
+                        (a, b) => a + b,
+                    };
+                } else {
+                    pointer += 1;
+                }
+                (count_delete, count_insert) = (0, 0);
+                text_delete.clear();
+                text_insert.clear();
+                (tokens_delete, tokens_insert) = (Some(0), Some(0));
+            }
+        }
+    }
+    if diffs.last().is_some_and(|c| c.value.is_empty()) {
+        diffs.pop();
+    }
+
+    // Shift single edits over the equality they end or start with, e.g.
+    // `=a +ba =c` becomes `+ab =ac`.
+    let mut changed = false;
+    let mut pointer = 1;
+    while pointer + 1 < diffs.len() {
+        if op(&diffs[pointer - 1]) == Op::Equal && op(&diffs[pointer + 1]) == Op::Equal {
+            let edit_op = op(&diffs[pointer]);
+            let before = diffs[pointer - 1].value.clone();
+            let after = diffs[pointer + 1].value.clone();
+            let text = diffs[pointer].value.clone();
+            if text.ends_with(&before) {
+                let shifted = format!("{before}{}", &text[..text.len() - before.len()]);
+                diffs[pointer] = edit(edit_op, shifted);
+                diffs[pointer + 1] = edit(Op::Equal, format!("{before}{after}"));
+                diffs.remove(pointer - 1);
+                changed = true;
+            } else if text.starts_with(&after) {
+                diffs[pointer - 1] = edit(Op::Equal, format!("{before}{after}"));
+                diffs[pointer] = edit(edit_op, format!("{}{after}", &text[after.len()..]));
+                diffs.remove(pointer + 1);
+                changed = true;
+            }
+        }
+        pointer += 1;
+    }
+    if changed {
+        merge(diffs);
+    }
+}
+
+#[inline]
+fn add_count(total: Option<u32>, count: u32) -> Option<u32> {
+    total.filter(|_| count != 0).map(|t| t + count)
+}
+
+/// Byte length of the common prefix of `a` and `b`.
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.chars()
+        .zip(b.chars())
+        .take_while(|(x, y)| x == y)
+        .map(|(x, _)| x.len_utf8())
+        .sum()
+}
+
+/// Byte length of the common suffix of `a` and `b`.
+fn common_suffix(a: &str, b: &str) -> usize {
+    a.chars()
+        .rev()
+        .zip(b.chars().rev())
+        .take_while(|(x, y)| x == y)
+        .map(|(x, _)| x.len_utf8())
+        .sum()
+}
+
+/// Length in characters of the longest suffix of `a` that is a prefix of
+/// `b`.
+fn common_overlap(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let len = a.len().min(b.len());
+    let (a, b) = (&a[a.len() - len..], &b[..len]);
+    if a == b {
+        return len;
+    }
+
+    // Grow candidate overlaps by searching for the current suffix of `a` in
+    // `b`, as in diff-match-patch.
+    let mut best = 0;
+    let mut length = 1;
+    while length <= len {
+        let pattern = &a[len - length..];
+        let Some(found) = b.windows(length).position(|w| w == pattern) else {
+            break;
+        };
+        length += found;
+        if length > len {
+            break;
+        }
+        if found == 0 || a[len - length..] == b[..length] {
+            best = length;
+        }
+        length += 1;
+    }
+    best
+}
+
+fn split_at_char(s: &str, n: usize) -> (&str, &str) {
+    let at = s.char_indices().nth(n).map_or(s.len(), |(i, _)| i);
+    s.split_at(at)
+}
@@ -0,0 +1,276 @@
+use crate::diff::cleanup::{cleanup_changes, Cleanup};
+use crate::diff::normalize::Normalizer;
+use crate::{
+    diff_chars, diff_words, Change, DiffOptions, DiffWordsOptions, WithComparator, WordBoundaries,
+};
+
+type Diffs<'a> = &'a [(char, &'a str)];
+
+/// Changes from `(op, text)` pairs, `op` being one of `-`, `+` and `=`.
+fn changes(diffs: &[(char, &str)]) -> Vec<Change> {
+    diffs
+        .iter()
+        .map(|&(op, text)| Change {
+            value: text.to_string(),
+            count: text.chars().count() as u32,
+            added: op == '+',
+            removed: op == '-',
+            position: None,
+        })
+        .collect()
+}
+
+fn check(mode: Cleanup, input: &[(char, &str)], expected: &[(char, &str)]) {
+    assert_eq!(
+        cleanup_changes(changes(input), mode),
+        changes(expected),
+        "{input:?}"
+    );
+}
+
+#[test]
+fn test_semantic_matches_diff_match_patch() {
+    let cases: &[(Diffs, Diffs)] = &[
+        (&[], &[]),
+        (
+            &[('-', "ab"), ('+', "cd"), ('=', "12"), ('-', "e")],
+            &[('-', "ab"), ('+', "cd"), ('=', "12"), ('-', "e")],
+        ),
+        (
+            &[('-', "abc"), ('+', "ABC"), ('=', "1234"), ('-', "wxyz")],
+            &[('-', "abc"), ('+', "ABC"), ('=', "1234"), ('-', "wxyz")],
+        ),
+        (
+            &[('-', "a"), ('=', "b"), ('-', "c")],
+            &[('-', "abc"), ('+', "b")],
+        ),
+        (
+            &[('-', "ab"), ('=', "cd"), ('-', "e"), ('=', "f"), ('+', "g")],
+            &[('-', "abcdef"), ('+', "cdfg")],
+        ),
+        (
+            &[
+                ('+', "1"),
+                ('=', "A"),
+                ('-', "B"),
+                ('+', "2"),
+                ('=', "_"),
+                ('+', "1"),
+                ('=', "A"),
+                ('-', "B"),
+                ('+', "2"),
+            ],
+            &[('-', "AB_AB"), ('+', "1A2_1A2")],
+        ),
+        (
+            &[('=', "The c"), ('-', "ow and the c"), ('=', "at.")],
+            &[('=', "The "), ('-', "cow and the "), ('=', "cat.")],
+        ),
+        (
+            &[('-', "abcxx"), ('+', "xxdef")],
+            &[('-', "abcxx"), ('+', "xxdef")],
+        ),
+        (
+            &[('-', "abcxxx"), ('+', "xxxdef")],
+            &[('-', "abc"), ('=', "xxx"), ('+', "def")],
+        ),
+        (
+            &[('-', "xxxabc"), ('+', "defxxx")],
+            &[('+', "def"), ('=', "xxx"), ('-', "abc")],
+        ),
+        (
+            &[
+                ('-', "abcd1212"),
+                ('+', "1212efghi"),
+                ('=', "----"),
+                ('-', "A3"),
+                ('+', "3BC"),
+            ],
+            &[
+                ('-', "abcd"),
+                ('=', "1212"),
+                ('+', "efghi"),
+                ('=', "----"),
+                ('-', "A"),
+                ('=', "3"),
+                ('+', "BC"),
+            ],
+        ),
+    ];
+    for (input, expected) in cases {
+        check(Cleanup::Semantic, input, expected);
+    }
+}
+
+#[test]
+fn test_efficiency_matches_diff_match_patch() {
+    let cases: &[(Diffs, Diffs)] = &[
+        (
+            &[
+                ('-', "ab"),
+                ('+', "12"),
+                ('=', "wxyz"),
+                ('-', "cd"),
+                ('+', "34"),
+            ],
+            &[
+                ('-', "ab"),
+                ('+', "12"),
+                ('=', "wxyz"),
+                ('-', "cd"),
+                ('+', "34"),
+            ],
+        ),
+        (
+            &[
+                ('-', "ab"),
+                ('+', "12"),
+                ('=', "xyz"),
+                ('-', "cd"),
+                ('+', "34"),
+            ],
+            &[('-', "abxyzcd"), ('+', "12xyz34")],
+        ),
+        (
+            &[('+', "12"), ('=', "x"), ('-', "cd"), ('+', "34")],
+            &[('-', "xcd"), ('+', "12x34")],
+        ),
+        (
+            &[
+                ('-', "ab"),
+                ('+', "12"),
+                ('=', "xy"),
+                ('+', "34"),
+                ('=', "z"),
+                ('-', "cd"),
+                ('+', "56"),
+            ],
+            &[('-', "abxyzcd"), ('+', "12xy34z56")],
+        ),
+    ];
+    for (input, expected) in cases {
+        check(Cleanup::Efficiency, input, expected);
+    }
+}
+
+#[test]
+fn test_cleanup_option_on_char_and_word_diffs() {
+    let old = "The cat sat on the mat.";
+    let new = "The dog sat on the rug.";
+    let opts = DiffOptions {
+        cleanup: Cleanup::Semantic,
+        ..Default::default()
+    };
+    assert_eq!(
+        diff_chars(old, new, &opts),
+        changes(&[
+            ('=', "The "),
+            ('-', "cat"),
+            ('+', "dog"),
+            ('=', " sat on the "),
+            ('-', "mat"),
+            ('+', "rug"),
+            ('=', "."),
+        ])
+    );
+
+    let opts = DiffWordsOptions {
+        cleanup: Cleanup::Semantic,
+        ..Default::default()
+    };
+    let words = diff_words("one two three four", "one 2 three 4", &opts);
+    let total: u32 = words.iter().filter(|c| !c.added).map(|c| c.count).sum();
+    assert_eq!(total, 4);
+
+    let raw = diff_chars(old, new, &DiffOptions::default());
+    assert_eq!(cleanup_changes(raw.clone(), Cleanup::None), raw);
+}
+
+#[test]
+fn test_cleanup_keeps_both_sides() {
+    let alphabet = ['a', 'b', ' ', '.', '\n', 'é'];
+    let text = |n: usize| -> String {
+        (0..n)
+            .map(|_| alphabet[fastrand::usize(..alphabet.len())])
+            .collect()
+    };
+    fastrand::seed(13);
+    for _ in 0..3000 {
+        let (old, new) = (text(fastrand::usize(..16)), text(fastrand::usize(..16)));
+        for mode in [Cleanup::Semantic, Cleanup::Efficiency] {
+            let opts = DiffOptions {
+                cleanup: mode,
+                ..Default::default()
+            };
+            let changes = diff_chars(&old, &new, &opts);
+            let side = |skip: fn(&Change) -> bool| -> String {
+                changes
+                    .iter()
+                    .filter(|c| !skip(c))
+                    .map(|c| c.value.as_str())
+                    .collect()
+            };
+            assert_eq!(side(|c| c.added), old, "{old:?} -> {new:?}");
+            assert_eq!(side(|c| c.removed), new, "{old:?} -> {new:?}");
+            assert!(changes.iter().all(|c| !c.value.is_empty()));
+        }
+    }
+}
+
+#[test]
+fn test_cleanup_skipped_for_loose_equality() {
+    let (old, new) = ("ab CD ef", "xAy cd z");
+    let exact = DiffOptions {
+        cleanup: Cleanup::Semantic,
+        ..Default::default()
+    };
+    let loose = [
+        DiffOptions {
+            ignore_case: true,
+            ..exact.clone()
+        },
+        DiffOptions {
+            normalizers: vec![Normalizer::mask("[A-Z]", "a").unwrap()],
+            ..exact.clone()
+        },
+        exact
+            .clone()
+            .with_comparator(|l, r| l.eq_ignore_ascii_case(r)),
+    ];
+    for opts in loose {
+        let uncleaned = DiffOptions {
+            cleanup: Cleanup::None,
+            ..opts.clone()
+        };
+        let changes = diff_chars(old, new, &opts);
+        assert_eq!(changes, diff_chars(old, new, &uncleaned));
+        assert!(changes
+            .iter()
+            .filter(|c| c.removed)
+            .all(|c| old.contains(c.value.as_str())));
+    }
+}
+
+#[test]
+fn test_cleanup_skipped_for_whitespace_insensitive_words() {
+    let (old, new) = ("alpha c  d gamma", "one c d two");
+    for word_boundaries in [
+        WordBoundaries::Jsdiff,
+        WordBoundaries::Unicode,
+        WordBoundaries::Code,
+    ] {
+        let opts = DiffWordsOptions {
+            cleanup: Cleanup::Semantic,
+            word_boundaries,
+            ..Default::default()
+        };
+        let uncleaned = DiffWordsOptions {
+            cleanup: Cleanup::None,
+            ..opts.clone()
+        };
+        assert_eq!(
+            diff_words(old, new, &opts),
+            diff_words(old, new, &uncleaned)
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    base::{Change, Tokeniser},
+    memory_pool::PooledDiff,
+    token::Token,
+};
+use crate::options::DiffCodeOptions;
+
+/// Language family whose lexical rules `CodeTokenizer` follows.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CodeLanguage {
+    /// C, C++, Java, C#, Go, Rust, JavaScript and TypeScript: `//` and
+    /// `/* */` comments, backslash escapes, template literals in backticks,
+    /// Rust lifetimes.
+    #[default]
+    C,
+    /// Python, Ruby, shell, Perl and YAML: `#` comments, backslash escapes,
+    /// triple-quoted strings.
+    Hash,
+    /// SQL: `--` and `/* */` comments, quotes escaped by doubling them.
+    Sql,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Escape {
+    Backslash,
+    Doubled,
+}
+
+struct Syntax {
+    line_comment: &'static str,
+    block_comment: Option<(&'static str, &'static str)>,
+    /// Quote characters, and whether their literals may span lines.
+    quotes: &'static [(u8, bool)],
+    escape: Escape,
+    triple_quotes: bool,
+    /// Whether `'ident` without a closing quote is a Rust lifetime or label.
+    lifetimes: bool,
+    /// Multi-character operators, longest first.
+    operators: &'static [&'static str],
+}
+
+const C_SYNTAX: Syntax = Syntax {
+    line_comment: "//",
+    block_comment: Some(("/*", "*/")),
+    quotes: &[(b'"', false), (b'\'', false), (b'`', true)],
+    escape: Escape::Backslash,
+    triple_quotes: false,
+    lifetimes: true,
+    operators: &[
+        ">>>=", "<<=", ">>=", ">>>", "===", "!==", "**=", "&&=", "||=", "??=", "...", "..=", "->",
+        "=>", "::", "==", "!=", "<=", ">=", "&&", "||", "++", "--", "+=", "-=", "*=", "/=", "%=",
+        "&=", "|=", "^=", "<<", ">>", "??", "?.", "**", "..",
+    ],
+};
+
+const HASH_SYNTAX: Syntax = Syntax {
+    line_comment: "#",
+    block_comment: None,
+    quotes: &[(b'"', false), (b'\'', false)],
+    escape: Escape::Backslash,
+    triple_quotes: true,
+    lifetimes: false,
+    operators: &[
+        "**=", "//=", ">>=", "<<=", "<=>", "...", "==", "!=", "<=", ">=", "->", "=>", "**", "//",
+        "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "<<", ">>", ":=", "&&", "||", "::", "..",
+    ],
+};
+
+const SQL_SYNTAX: Syntax = Syntax {
+    line_comment: "--",
+    block_comment: Some(("/*", "*/")),
+    quotes: &[(b'\'', true), (b'"', true), (b'`', true)],
+    escape: Escape::Doubled,
+    triple_quotes: false,
+    lifetimes: false,
+    operators: &["->>", "<>", "!=", "<=", ">=", "||", "::", ":=", "->", "=>"],
+};
+
+impl CodeLanguage {
+    fn syntax(self) -> &'static Syntax {
+        match self {
+            CodeLanguage::C => &C_SYNTAX,
+            CodeLanguage::Hash => &HASH_SYNTAX,
+            CodeLanguage::Sql => &SQL_SYNTAX,
+        }
+    }
+}
+
+/// Lexical tokenizer for source code: whitespace runs, comments, string
+/// literals, numbers, identifiers and operators are one token each.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct CodeTokenizer {
+    language: CodeLanguage,
+}
+
+impl CodeTokenizer {
+    pub fn new(language: CodeLanguage) -> Self {
+        CodeTokenizer { language }
+    }
+}
+
+#[inline]
+fn is_ident_start(c: char) -> bool {
+    c == '_' || c == '$' || c.is_alphabetic()
+}
+
+#[inline]
+fn is_ident_continue(c: char) -> bool {
+    c == '_' || c == '$' || c.is_alphanumeric()
+}
+
+fn scan_while(text: &str, pos: usize, f: impl Fn(char) -> bool) -> usize {
+    text[pos..]
+        .char_indices()
+        .find(|&(_, c)| !f(c))
+        .map_or(text.len(), |(i, _)| pos + i)
+}
+
+/// End of the literal opened by `quote` at `pos`. An unterminated literal
+/// ends at the line end, or at the end of input when it may span lines.
+fn scan_string(bytes: &[u8], pos: usize, quote: u8, multiline: bool, escape: Escape) -> usize {
+    let mut i = pos + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if escape == Escape::Backslash => i += 2,
+            b if b == quote => {
+                if escape == Escape::Doubled && bytes.get(i + 1) == Some(&quote) {
+                    i += 2;
+                } else {
+                    return i + 1;
+                }
+            }
+            b'\n' if !multiline => return i,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// End of the number starting at `pos`: digits, letters for radix prefixes
+/// and suffixes, one decimal point and a signed exponent.
+fn scan_number(bytes: &[u8], pos: usize) -> usize {
+    let hex = bytes[pos] == b'0' && matches!(bytes.get(pos + 1), Some(b'x' | b'X'));
+    let mut seen_dot = false;
+    let mut i = pos;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' | b'_' => {
+                if !hex
+                    && matches!(bytes[i], b'e' | b'E')
+                    && matches!(bytes.get(i + 1), Some(b'+' | b'-'))
+                    && bytes.get(i + 2).is_some_and(u8::is_ascii_digit)
+                {
+                    i += 1;
+                }
+                i += 1;
+            }
+            b'.' if !seen_dot && !hex && bytes.get(i + 1).is_some_and(u8::is_ascii_digit) => {
+                seen_dot = true;
+                i += 1;
+            }
+            _ => break,
+        }
+    }
+    i
+}
+
+/// End of the Rust lifetime or loop label at `pos`, or `None` when the quote
+/// opens a literal: `'a'`, or a string whose closing quote is not directly
+/// followed by an identifier (as in `<'a>(x: &'a`).
+fn scan_lifetime(text: &str, pos: usize, escape: Escape) -> Option<usize> {
+    if !text[pos + 1..].starts_with(is_ident_start) {
+        return None;
+    }
+    let end = scan_while(text, pos + 1, is_ident_continue);
+    if text[end..].starts_with('\'') {
+        return None;
+    }
+    let close = scan_string(text.as_bytes(), pos, b'\'', false, escape);
+    let closed = text[..close].ends_with('\'');
+    if closed && !text[close..].starts_with(is_ident_start) {
+        return None;
+    }
+    Some(end)
+}
+
+fn scan_token(text: &str, pos: usize, syntax: &Syntax) -> usize {
+    let bytes = text.as_bytes();
+    let rest = &text[pos..];
+    let ch = rest.chars().next().unwrap();
+
+    if ch.is_whitespace() {
+        return scan_while(text, pos, char::is_whitespace);
+    }
+    if rest.starts_with(syntax.line_comment) {
+        return rest.find('\n').map_or(text.len(), |i| pos + i);
+    }
+    if let Some((open, close)) = syntax.block_comment {
+        if let Some(body) = rest.strip_prefix(open) {
+            return body
+                .find(close)
+                .map_or(text.len(), |i| pos + open.len() + i + close.len());
+        }
+    }
+    if syntax.triple_quotes {
+        for delimiter in ["\"\"\"", "'''"] {
+            if rest.starts_with(delimiter) {
+                return rest[3..]
+                    .find(delimiter)
+                    .map_or(text.len(), |i| pos + 6 + i);
+            }
+        }
+    }
+    if syntax.lifetimes && ch == '\'' {
+        if let Some(end) = scan_lifetime(text, pos, syntax.escape) {
+            return end;
+        }
+    }
+    if let Some(&(quote, multiline)) = syntax.quotes.iter().find(|(q, _)| bytes[pos] == *q) {
+        return scan_string(bytes, pos, quote, multiline, syntax.escape);
+    }
+    if ch.is_ascii_digit() || (ch == '.' && bytes.get(pos + 1).is_some_and(u8::is_ascii_digit)) {
+        return scan_number(bytes, pos);
+    }
+    if is_ident_start(ch) {
+        return scan_while(text, pos, is_ident_continue);
+    }
+    if let Some(op) = syntax.operators.iter().find(|op| rest.starts_with(*op)) {
+        return pos + op.len();
+    }
+    pos + ch.len_utf8()
+}
+
+impl<'a> Tokeniser<'a> for CodeTokenizer {
+    fn tokenize<'b>(&self, text: &'a str, arena: &'b mut Vec<Token<'a>>) -> &'b [Token<'a>] {
+        arena.clear();
+        let syntax = self.language.syntax();
+        let mut pos = 0;
+        while pos < text.len() {
+            let end = scan_token(text, pos, syntax);
+            arena.push(Token {
+                text: &text[pos..end],
+            });
+            pos = end;
+        }
+        arena
+    }
+
+    fn join(&self, toks: &[Token<'a>]) -> String {
+        toks.iter().map(|t| t.text).collect()
+    }
+}
+
+pub fn diff_code(old_str: &str, new_str: &str, opts: &DiffCodeOptions) -> Vec<Change> {
+    let mut diff = PooledDiff::new(CodeTokenizer::new(opts.language), opts.into());
+    diff.diff(old_str, new_str)
+}
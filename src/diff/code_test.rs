@@ -0,0 +1,164 @@
+use super::base::Tokeniser;
+use super::code::{CodeLanguage, CodeTokenizer};
+use crate::{diff_code, DiffCodeOptions};
+
+fn tokens(language: CodeLanguage, text: &str) -> Vec<&str> {
+    let tokenizer = CodeTokenizer::new(language);
+    let mut arena = Vec::new();
+    let toks = tokenizer.tokenize(text, &mut arena);
+    assert_eq!(tokenizer.join(toks), text);
+    toks.iter().map(|t| t.text).collect()
+}
+
+#[test]
+fn test_c_like_tokens() {
+    assert_eq!(
+        tokens(
+            CodeLanguage::C,
+            "let s = \"a \\\"b\\\" c\"; // note\nx >>>= 0x1F;"
+        ),
+        vec![
+            "let",
+            " ",
+            "s",
+            " ",
+            "=",
+            " ",
+            "\"a \\\"b\\\" c\"",
+            ";",
+            " ",
+            "// note",
+            "\n",
+            "x",
+            " ",
+            ">>>=",
+            " ",
+            "0x1F",
+            ";"
+        ]
+    );
+    assert_eq!(
+        tokens(CodeLanguage::C, "/* a\n * b */f(1.5e-3, .5, 1..n)"),
+        vec![
+            "/* a\n * b */",
+            "f",
+            "(",
+            "1.5e-3",
+            ",",
+            " ",
+            ".5",
+            ",",
+            " ",
+            "1",
+            "..",
+            "n",
+            ")"
+        ]
+    );
+    assert_eq!(
+        tokens(CodeLanguage::C, "`a\n${b}` 'x"),
+        vec!["`a\n${b}`", " ", "'x"]
+    );
+    assert_eq!(
+        tokens(CodeLanguage::C, "'open\nnext"),
+        vec!["'open", "\n", "next"]
+    );
+}
+
+#[test]
+fn test_rust_lifetimes() {
+    assert_eq!(
+        tokens(CodeLanguage::C, "fn f<'a>(x: &'a str) -> char { 'x' }"),
+        vec![
+            "fn", " ", "f", "<", "'a", ">", "(", "x", ":", " ", "&", "'a", " ", "str", ")", " ",
+            "->", " ", "char", " ", "{", " ", "'x'", " ", "}"
+        ]
+    );
+    assert_eq!(
+        tokens(CodeLanguage::C, "'outer: loop { break 'outer; }"),
+        vec!["'outer", ":", " ", "loop", " ", "{", " ", "break", " ", "'outer", ";", " ", "}"]
+    );
+    assert_eq!(
+        tokens(CodeLanguage::C, "f('abc def', 'b')"),
+        vec!["f", "(", "'abc def'", ",", " ", "'b'", ")"]
+    );
+}
+
+#[test]
+fn test_hash_and_sql_tokens() {
+    assert_eq!(
+        tokens(CodeLanguage::Hash, "x //= 2  # halve\ns = '''a\n'b'\n'''"),
+        vec![
+            "x",
+            " ",
+            "//=",
+            " ",
+            "2",
+            "  ",
+            "# halve",
+            "\n",
+            "s",
+            " ",
+            "=",
+            " ",
+            "'''a\n'b'\n'''"
+        ]
+    );
+    assert_eq!(
+        tokens(
+            CodeLanguage::Sql,
+            "SELECT 'it''s' AS \"a\"\"b\" -- c\nWHERE x <> 1 /* d */"
+        ),
+        vec![
+            "SELECT",
+            " ",
+            "'it''s'",
+            " ",
+            "AS",
+            " ",
+            "\"a\"\"b\"",
+            " ",
+            "-- c",
+            "\n",
+            "WHERE",
+            " ",
+            "x",
+            " ",
+            "<>",
+            " ",
+            "1",
+            " ",
+            "/* d */"
+        ]
+    );
+}
+
+#[test]
+fn test_diff_code() {
+    let changes = diff_code(
+        "log(\"hello world\"); // greet",
+        "log(\"hello there\"); // greet",
+        &DiffCodeOptions::default(),
+    );
+    let summary: Vec<(&str, bool, bool)> = changes
+        .iter()
+        .map(|c| (c.value.as_str(), c.added, c.removed))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("log(", false, false),
+            ("\"hello world\"", false, true),
+            ("\"hello there\"", true, false),
+            ("); // greet", false, false),
+        ]
+    );
+
+    let sql = DiffCodeOptions {
+        language: CodeLanguage::Sql,
+        ignore_case: true,
+        ..Default::default()
+    };
+    let changes = diff_code("select a -- x\n", "SELECT a -- X\n", &sql);
+    assert_eq!(changes.len(), 1);
+}
@@ -1,8 +1,6 @@
-use serde_wasm_bindgen as swb;
-use wasm_bindgen::prelude::*;
-
 use super::{
-    base::{Options, Tokeniser},
+    base::{Change, Tokeniser},
+    memory_pool::PooledDiff,
     token::Token,
 };
 use crate::options::DiffOptions;
@@ -67,17 +65,7 @@ impl<'a> Tokeniser<'a> for CssTokenizer {
     }
 }
 
-#[wasm_bindgen(js_name = diffCss)]
-pub fn diff_css(old_str: &str, new_str: &str, opts: JsValue) -> Result<JsValue, JsValue> {
-    let o: DiffOptions = swb::from_value(opts).unwrap_or_default();
-    let base_opts = Options {
-        ignore_case: o.ignore_case.unwrap_or(false),
-        one_change_per_token: o.one_change_per_token.unwrap_or(false),
-        max_edit_length: o.max_edit_length,
-    };
-
-    use super::memory_pool::PooledDiff;
-    let mut diff = PooledDiff::new(CssTokenizer, base_opts);
-    let changes = diff.diff(old_str, new_str);
-    swb::to_value(&changes).map_err(Into::into)
+pub fn diff_css(old_str: &str, new_str: &str, opts: &DiffOptions) -> Vec<Change> {
+    let mut diff = PooledDiff::new(CssTokenizer, opts.into());
+    diff.diff(old_str, new_str)
 }
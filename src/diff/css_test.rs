@@ -274,3 +274,16 @@ fn test_css_test_case() {
     assert_eq!(tokens[13].text, "}");
     assert_eq!(tokenizer.join(tokens), input);
 }
+
+#[test]
+fn test_diff_css_native_api() {
+    use crate::{diff_css, DiffOptions};
+
+    let changes = diff_css("a{color:red;}", "a{color:blue;}", &DiffOptions::default());
+    let removed: Vec<_> = changes.iter().filter(|c| c.removed).collect();
+    let added: Vec<_> = changes.iter().filter(|c| c.added).collect();
+    assert_eq!(removed.len(), 1);
+    assert_eq!(removed[0].value, "red");
+    assert_eq!(added.len(), 1);
+    assert_eq!(added[0].value, "blue");
+}
@@ -0,0 +1,745 @@
+use super::base::{Algorithm, Options, Path};
+use super::component_pool::ComponentPool;
+use super::histogram::{self, Lcs};
+use super::linear::{Linear, LINEAR_SPACE_THRESHOLD};
+use super::patience;
+use crate::util::deadline::Deadline;
+use std::cell::RefCell;
+use std::hash::Hash;
+use std::ops::ControlFlow;
+
+thread_local! {
+    pub(super) static COMPONENT_POOL: RefCell<ComponentPool> = RefCell::new(ComponentPool::with_capacity(1024));
+    pub(super) static PATH_POOL: RefCell<Vec<Option<Path>>> = RefCell::new(Vec::with_capacity(256));
+}
+
+/// Diff engine over index-addressed sequences. Elements are only ever
+/// compared through an `eq(old_index, new_index)` callback, so the same
+/// search serves string tokens and arbitrary values alike.
+///
+/// Results are component chains in the pooled `ComponentPool`; `spans` turns
+/// one into run-length `(count, added, removed)` entries.
+pub(crate) struct Engine {
+    opts: Options,
+    components: ComponentPool,
+    paths: Vec<Option<Path>>,
+    deadline: Option<Deadline>,
+    timed_out: bool,
+}
+
+/// Search kept between calls, advanced a round at a time.
+pub(crate) enum Search {
+    /// Greedy Myers, one edit distance per round.
+    Greedy(Greedy),
+    /// Patience or histogram, one segment between anchors per round.
+    Anchored(Anchored),
+    /// Linear-space Myers, one divide step per round.
+    Linear(Linear),
+}
+
+impl Search {
+    /// Edit distance explored by the greedy search, or tokens found changed
+    /// so far by the others.
+    pub(crate) fn edit_length(&self) -> usize {
+        match self {
+            Search::Greedy(greedy) => greedy.d,
+            Search::Anchored(anchored) => {
+                anchored.ops.edits()
+                    + anchored
+                        .sub
+                        .as_ref()
+                        .map_or(0, |sub| sub.search.edit_length())
+            }
+            Search::Linear(linear) => linear.edits(),
+        }
+    }
+}
+
+/// Greedy Myers search state kept between `Engine::search` calls.
+#[derive(Clone, Copy)]
+pub(crate) struct Greedy {
+    /// Last edit distance explored.
+    pub(crate) d: usize,
+    max_d: usize,
+    offset: isize,
+    min_k: isize,
+    max_k: isize,
+    /// Common prefix and suffix cut off by `search_start`; the search runs
+    /// on the tokens in between.
+    prefix: usize,
+    suffix: usize,
+}
+
+/// Patience or histogram search state kept between `Engine::anchored` calls.
+pub(crate) struct Anchored {
+    /// Segments still to diff, the next one last.
+    stack: Vec<Segment>,
+    ops: EditScript,
+    /// Patience trims around every anchor; histogram, like git, only trims
+    /// the ends of the whole input.
+    trim: bool,
+    /// Myers search over the segment being diffed, which has no anchors.
+    sub: Option<Unanchored>,
+}
+
+/// Myers search over the segment starting at `a_lo`/`b_lo`, `n` by `m`
+/// tokens long.
+struct Unanchored {
+    search: Box<Search>,
+    a_lo: usize,
+    b_lo: usize,
+    n: usize,
+    m: usize,
+}
+
+impl Anchored {
+    pub(crate) fn new(n: usize, m: usize) -> Self {
+        Self {
+            stack: vec![Segment::Range(0, n, 0, m)],
+            ops: EditScript::default(),
+            trim: true,
+            sub: None,
+        }
+    }
+}
+
+pub(crate) enum Round {
+    Found(Option<u32>),
+    Exceeded,
+    Paused,
+}
+
+impl Engine {
+    pub(crate) fn new(opts: Options) -> Self {
+        let components = COMPONENT_POOL.with(|pool| {
+            let mut pool = pool.borrow_mut();
+            pool.clear(); // Reset for reuse
+            std::mem::take(&mut *pool)
+        });
+
+        let paths = PATH_POOL.with(|pool| {
+            let mut pool = pool.borrow_mut();
+            pool.clear();
+            std::mem::take(&mut *pool)
+        });
+
+        Self {
+            opts,
+            components,
+            paths,
+            deadline: None,
+            timed_out: false,
+        }
+    }
+
+    /// Starts the `Options::timeout` clock for a new diff.
+    pub(crate) fn reset_deadline(&mut self) {
+        self.deadline = self.opts.timeout.map(Deadline::after_ms);
+        self.timed_out = false;
+    }
+
+    #[inline]
+    pub(crate) fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    /// Run-length `(count, added, removed)` entries of the chain ending at `tail`.
+    pub(crate) fn spans(&self, tail: Option<u32>) -> Vec<(u32, bool, bool)> {
+        self.components
+            .trace_path(tail)
+            .into_iter()
+            .map(|idx| {
+                let (count, added, removed, _) = self.components.get(idx);
+                (count, added, removed)
+            })
+            .collect()
+    }
+
+    #[inline]
+    pub(crate) fn uses_linear_space(&self, n: usize, m: usize) -> bool {
+        let threshold = self
+            .opts
+            .linear_space_threshold
+            .unwrap_or(LINEAR_SPACE_THRESHOLD);
+        n + m > threshold
+    }
+
+    #[inline(always)]
+    fn extract_common<E>(
+        &mut self,
+        path: &mut Path,
+        n: usize,
+        m: usize,
+        eq: &E,
+        diagonal: isize,
+    ) -> isize
+    where
+        E: Fn(usize, usize) -> bool,
+    {
+        let (old_len, new_len) = (n as isize, m as isize);
+        let mut old_pos = path.old_pos;
+        let mut new_pos = old_pos - diagonal;
+        let mut common = 0;
+
+        while new_pos + 1 < new_len
+            && old_pos + 1 < old_len
+            && eq((old_pos + 1) as usize, (new_pos + 1) as usize)
+        {
+            old_pos += 1;
+            new_pos += 1;
+            common += 1;
+
+            if self.opts.one_change_per_token {
+                let idx = self.components.push(1, false, false, path.last);
+                path.last = Some(idx);
+            }
+        }
+
+        if common > 0 && !self.opts.one_change_per_token {
+            let idx = self.components.push(common as u32, false, false, path.last);
+            path.last = Some(idx);
+        }
+
+        path.old_pos = old_pos;
+        new_pos
+    }
+
+    #[inline(always)]
+    fn extend_path(&mut self, path: Path, added: bool, removed: bool, old_inc: isize) -> Path {
+        let merged = if !self.opts.one_change_per_token {
+            if let Some(prev_idx) = path.last {
+                let (prev_count, prev_added, prev_removed, prev_previous) =
+                    self.components.get(prev_idx);
+                if prev_added == added && prev_removed == removed {
+                    let idx = self
+                        .components
+                        .push(prev_count + 1, added, removed, prev_previous);
+                    Some(idx)
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        let last_idx = if let Some(idx) = merged {
+            idx
+        } else {
+            self.components.push(1, added, removed, path.last)
+        };
+
+        Path {
+            old_pos: path.old_pos + old_inc,
+            last: Some(last_idx),
+        }
+    }
+
+    /// Runs Myers over `n` old and `m` new elements and returns the tail of
+    /// the recorded component chain, or `None` when `max_edit_length` is
+    /// exceeded.
+    pub(crate) fn myers<E>(&mut self, n: usize, m: usize, eq: &E) -> Option<Option<u32>>
+    where
+        E: Fn(usize, usize) -> bool,
+    {
+        let round = if self.uses_linear_space(n, m) {
+            let mut linear = self.linear_start(n, m, eq)?;
+            self.linear(&mut linear, eq, usize::MAX)
+        } else {
+            match self.search_start(n, m, eq) {
+                ControlFlow::Break(round) => round,
+                ControlFlow::Continue(mut search) => self.search(&mut search, n, m, eq, usize::MAX),
+            }
+        };
+        match round {
+            Round::Found(tail) => Some(tail),
+            Round::Exceeded | Round::Paused => None,
+        }
+    }
+
+    /// Sets up the greedy search on the inputs minus their common prefix and
+    /// suffix, finishing straight away when what is left is a pure insertion
+    /// or deletion.
+    pub(crate) fn search_start<E>(
+        &mut self,
+        n: usize,
+        m: usize,
+        eq: &E,
+    ) -> ControlFlow<Round, Greedy>
+    where
+        E: Fn(usize, usize) -> bool,
+    {
+        let (prefix, suffix) = trim(n, m, eq);
+        let (n, m) = (n - prefix - suffix, m - prefix - suffix);
+        if self
+            .opts
+            .max_edit_length
+            .is_some_and(|limit| n.min(m) == 0 && n + m > limit)
+        {
+            return ControlFlow::Break(Round::Exceeded);
+        }
+
+        let head = self.push_run(None, prefix, false, false);
+        if n == 0 || m == 0 {
+            let tail = self.push_run(head, n, false, true);
+            let tail = self.push_run(tail, m, true, false);
+            return ControlFlow::Break(Round::Found(self.push_run(tail, suffix, false, false)));
+        }
+
+        let natural_limit = n + m;
+        let max_d = self
+            .opts
+            .max_edit_length
+            .map_or(natural_limit, |m| m.min(natural_limit));
+
+        let offset = max_d as isize;
+        let size = 2 * max_d + 1;
+
+        self.paths.clear();
+        self.paths.resize(size, None);
+        self.paths[offset as usize] = Some(Path {
+            old_pos: -1,
+            last: head,
+        });
+
+        ControlFlow::Continue(Greedy {
+            d: 0,
+            max_d,
+            offset,
+            min_k: isize::MIN,
+            max_k: isize::MAX,
+            prefix,
+            suffix,
+        })
+    }
+
+    /// Advances the greedy search by up to `rounds` edit distances.
+    pub(crate) fn search<E>(
+        &mut self,
+        search: &mut Greedy,
+        n: usize,
+        m: usize,
+        eq: &E,
+        rounds: usize,
+    ) -> Round
+    where
+        E: Fn(usize, usize) -> bool,
+    {
+        let prefix = search.prefix;
+        let eq = &|i: usize, j: usize| eq(prefix + i, prefix + j);
+        let (n, m) = (n - prefix - search.suffix, m - prefix - search.suffix);
+        let (a_len, b_len) = (n as isize, m as isize);
+        let offset = search.offset;
+        let size = self.paths.len();
+
+        for _ in 0..rounds {
+            if search.d >= search.max_d {
+                return Round::Exceeded;
+            }
+            search.d += 1;
+            let d = search.d as isize;
+
+            if self.deadline.is_some_and(|t| t.expired()) {
+                self.timed_out = true;
+                let tail = self.best_effort(n, m, eq, offset);
+                return Round::Found(self.push_suffix(tail, search.suffix));
+            }
+
+            let k_min = search.min_k.max(-d);
+            let k_max = search.max_k.min(d);
+
+            for k in (k_min..=k_max).step_by(2) {
+                let idx = (k + offset) as usize;
+
+                let remove_path = if idx > 0 {
+                    self.paths[idx - 1].take()
+                } else {
+                    None
+                };
+                let add_path = if idx + 1 < size {
+                    self.paths[idx + 1]
+                } else {
+                    None
+                };
+
+                let can_add = add_path
+                    .as_ref()
+                    .is_some_and(|p| (0..b_len).contains(&(p.old_pos - k)));
+                let can_remove = remove_path.as_ref().is_some_and(|p| p.old_pos + 1 < a_len);
+
+                if !can_add && !can_remove {
+                    self.paths[idx] = None;
+                    continue;
+                }
+
+                let base = if !can_remove
+                    || (can_add
+                        && remove_path.as_ref().unwrap().old_pos
+                            < add_path.as_ref().unwrap().old_pos)
+                {
+                    self.extend_path(add_path.unwrap(), true, false, 0)
+                } else {
+                    self.extend_path(remove_path.unwrap(), false, true, 1)
+                };
+
+                self.paths[idx] = Some(base);
+                let mut path = self.paths[idx].unwrap();
+                let new_pos_after = self.extract_common(&mut path, n, m, eq, k);
+                self.paths[idx] = Some(path);
+
+                let p = self.paths[idx].as_ref().unwrap();
+                if p.old_pos + 1 >= a_len && new_pos_after + 1 >= b_len {
+                    let tail = p.last;
+                    return Round::Found(self.push_suffix(tail, search.suffix));
+                }
+
+                if p.old_pos + 1 >= a_len {
+                    search.max_k = search.max_k.min(k - 1);
+                }
+                if new_pos_after + 1 >= b_len {
+                    search.min_k = search.min_k.max(k + 1);
+                }
+            }
+        }
+
+        if search.d >= search.max_d {
+            Round::Exceeded
+        } else {
+            Round::Paused
+        }
+    }
+
+    /// Patience and histogram diff: split the inputs on matching runs found by
+    /// the selected algorithm, using Myers for ranges where it finds none.
+    /// Handles up to `rounds` segments of `state`, a Myers search of a
+    /// segment taking one round per edit distance or divide step.
+    /// `a_keys`/`b_keys` must be equal exactly where `eq` holds.
+    pub(crate) fn anchored<K, E>(
+        &mut self,
+        state: &mut Anchored,
+        a_keys: &[K],
+        b_keys: &[K],
+        eq: &E,
+        rounds: usize,
+    ) -> Round
+    where
+        K: Hash + Eq,
+        E: Fn(usize, usize) -> bool,
+    {
+        let Anchored {
+            stack,
+            ops,
+            trim,
+            sub,
+        } = state;
+
+        for _ in 0..rounds {
+            if let Some(unanchored) = sub {
+                let (a_lo, b_lo) = (unanchored.a_lo, unanchored.b_lo);
+                let sub_eq = |i: usize, j: usize| eq(a_lo + i, b_lo + j);
+                let round = match &mut *unanchored.search {
+                    Search::Greedy(greedy) => {
+                        self.search(greedy, unanchored.n, unanchored.m, &sub_eq, 1)
+                    }
+                    Search::Linear(linear) => self.linear(linear, &sub_eq, 1),
+                    Search::Anchored(_) => unreachable!("segments are searched with Myers"),
+                };
+                match round {
+                    Round::Found(tail) => {
+                        for (count, added, removed) in self.spans(tail) {
+                            ops.push(count as usize, added, removed);
+                        }
+                        *sub = None;
+                    }
+                    Round::Exceeded => return Round::Exceeded,
+                    Round::Paused => {}
+                }
+                continue;
+            }
+
+            let Some(seg) = stack.pop() else {
+                return Round::Found(self.record(ops));
+            };
+            let (mut a_lo, mut a_hi, mut b_lo, mut b_hi) = match seg {
+                Segment::Equal(n) => {
+                    ops.push(n, false, false);
+                    continue;
+                }
+                Segment::Range(a_lo, a_hi, b_lo, b_hi) => (a_lo, a_hi, b_lo, b_hi),
+            };
+
+            let (start, end) = (a_lo, a_hi);
+            if *trim {
+                while a_lo < a_hi && b_lo < b_hi && eq(a_lo, b_lo) {
+                    a_lo += 1;
+                    b_lo += 1;
+                }
+                while a_hi > a_lo && b_hi > b_lo && eq(a_hi - 1, b_hi - 1) {
+                    a_hi -= 1;
+                    b_hi -= 1;
+                }
+                *trim = self.opts.algorithm == Algorithm::Patience;
+            }
+            ops.push(a_lo - start, false, false);
+            stack.push(Segment::Equal(end - a_hi));
+
+            if a_lo == a_hi || b_lo == b_hi {
+                ops.push(a_hi - a_lo, false, true);
+                ops.push(b_hi - b_lo, true, false);
+                continue;
+            }
+
+            let (a_sub, b_sub) = (&a_keys[a_lo..a_hi], &b_keys[b_lo..b_hi]);
+            let runs: Vec<(usize, usize, usize)> = match self.opts.algorithm {
+                Algorithm::Histogram => match histogram::find_lcs(a_sub, b_sub) {
+                    Lcs::Region { a, b, len } => vec![(a, b, len)],
+                    Lcs::Disjoint => {
+                        ops.push(a_hi - a_lo, false, true);
+                        ops.push(b_hi - b_lo, true, false);
+                        continue;
+                    }
+                    Lcs::TooCommon => Vec::new(),
+                },
+                _ => patience::unique_anchors(a_sub, b_sub)
+                    .into_iter()
+                    .map(|(i, j)| (i, j, 1))
+                    .collect(),
+            };
+            if runs.is_empty() {
+                let (n, m) = (a_hi - a_lo, b_hi - b_lo);
+                let sub_eq = |i: usize, j: usize| eq(a_lo + i, b_lo + j);
+                let search = if self.uses_linear_space(n, m) {
+                    match self.linear_start(n, m, &sub_eq) {
+                        Some(linear) => Search::Linear(linear),
+                        None => return Round::Exceeded,
+                    }
+                } else {
+                    match self.search_start(n, m, &sub_eq) {
+                        ControlFlow::Break(Round::Found(tail)) => {
+                            for (count, added, removed) in self.spans(tail) {
+                                ops.push(count as usize, added, removed);
+                            }
+                            continue;
+                        }
+                        ControlFlow::Break(_) => return Round::Exceeded,
+                        ControlFlow::Continue(greedy) => Search::Greedy(greedy),
+                    }
+                };
+                *sub = Some(Unanchored {
+                    search: Box::new(search),
+                    a_lo,
+                    b_lo,
+                    n,
+                    m,
+                });
+                continue;
+            }
+
+            let (mut next_a, mut next_b) = (a_hi, b_hi);
+            for &(i, j, len) in runs.iter().rev() {
+                let (i, j) = (a_lo + i, b_lo + j);
+                stack.push(Segment::Range(i + len, next_a, j + len, next_b));
+                stack.push(Segment::Equal(len));
+                next_a = i;
+                next_b = j;
+            }
+            stack.push(Segment::Range(a_lo, next_a, b_lo, next_b));
+        }
+
+        if stack.is_empty() && sub.is_none() {
+            Round::Found(self.record(ops))
+        } else {
+            Round::Paused
+        }
+    }
+
+    /// Completes the furthest-reaching path explored so far with a plain
+    /// removal and addition of whatever input it has not consumed yet.
+    fn best_effort<E>(&mut self, n: usize, m: usize, eq: &E, offset: isize) -> Option<u32>
+    where
+        E: Fn(usize, usize) -> bool,
+    {
+        let (k, path) = self
+            .paths
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, p)| p.map(|p| (idx as isize - offset, p)))
+            .max_by_key(|&(k, p)| 2 * p.old_pos - k)
+            .unwrap_or((0, Path::default()));
+
+        let mut ops = EditScript::default();
+        for (count, added, removed) in self.spans(path.last) {
+            ops.push(count as usize, added, removed);
+        }
+
+        let (old_done, new_done) = ((path.old_pos + 1) as usize, (path.old_pos - k + 1) as usize);
+        let (mut old_end, mut new_end) = (n, m);
+        while old_end > old_done && new_end > new_done && eq(old_end - 1, new_end - 1) {
+            old_end -= 1;
+            new_end -= 1;
+        }
+        ops.push(old_end - old_done, false, true);
+        ops.push(new_end - new_done, true, false);
+        ops.push(n - old_end, false, false);
+
+        self.record(&ops)
+    }
+
+    /// Sets up the linear-space variant of `myers` for large inputs, which
+    /// records only the final edit script instead of every explored path.
+    /// Returns `None` when `max_edit_length` is exceeded.
+    pub(crate) fn linear_start<E>(&self, n: usize, m: usize, eq: &E) -> Option<Linear>
+    where
+        E: Fn(usize, usize) -> bool,
+    {
+        Linear::new(n, m, eq, self.opts.max_edit_length, self.deadline)
+    }
+
+    /// Advances a linear-space search by up to `rounds` divide steps.
+    pub(crate) fn linear<E>(&mut self, state: &mut Linear, eq: &E, rounds: usize) -> Round
+    where
+        E: Fn(usize, usize) -> bool,
+    {
+        for _ in 0..rounds {
+            if !state.step(eq) {
+                self.timed_out |= state.timed_out;
+                let mut ops = EditScript::default();
+                for (count, added, removed) in state.finish() {
+                    ops.push(count, added, removed);
+                }
+                return Round::Found(self.record(&ops));
+            }
+        }
+        Round::Paused
+    }
+
+    /// Records an edit script as a component chain and returns its tail.
+    fn record(&mut self, ops: &EditScript) -> Option<u32> {
+        let mut last = None;
+        for &(count, added, removed) in &ops.0 {
+            last = self.push_run(last, count as usize, added, removed);
+        }
+        last
+    }
+
+    /// Appends the trimmed common suffix, extending a common run the search
+    /// ended on so the result matches an untrimmed search.
+    fn push_suffix(&mut self, tail: Option<u32>, suffix: usize) -> Option<u32> {
+        if let Some(idx) = tail.filter(|_| suffix > 0 && !self.opts.one_change_per_token) {
+            let (count, added, removed, previous) = self.components.get(idx);
+            if !added && !removed {
+                let merged = self
+                    .components
+                    .push(count + suffix as u32, false, false, previous);
+                return Some(merged);
+            }
+        }
+        self.push_run(tail, suffix, false, false)
+    }
+
+    /// Appends a run of `count` tokens to the chain ending at `last`.
+    fn push_run(
+        &mut self,
+        mut last: Option<u32>,
+        count: usize,
+        added: bool,
+        removed: bool,
+    ) -> Option<u32> {
+        if count == 0 {
+            return last;
+        }
+        if self.opts.one_change_per_token {
+            for _ in 0..count {
+                last = Some(self.components.push(1, added, removed, last));
+            }
+        } else {
+            last = Some(self.components.push(count as u32, added, removed, last));
+        }
+        last
+    }
+}
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        COMPONENT_POOL.with(|pool| {
+            *pool.borrow_mut() = std::mem::take(&mut self.components);
+        });
+
+        PATH_POOL.with(|pool| {
+            *pool.borrow_mut() = std::mem::take(&mut self.paths);
+        });
+    }
+}
+
+/// Suffix boundaries `trim` tries before giving up on cutting the suffix.
+const SUFFIX_PROBES: usize = 8;
+
+/// Lengths of the common prefix and of the part of the common suffix that can
+/// be cut off without changing the greedy result.
+///
+/// Myers sometimes aligns a common suffix against earlier tokens rather than
+/// the end of the input (`ab` -> `acbacb` matches the first `b`). That takes a
+/// copy of the suffix's first token in one of the middles, so the suffix is
+/// only cut at a token that occurs in neither.
+fn trim<E>(n: usize, m: usize, eq: &E) -> (usize, usize)
+where
+    E: Fn(usize, usize) -> bool,
+{
+    let mut prefix = 0;
+    while prefix < n && prefix < m && eq(prefix, prefix) {
+        prefix += 1;
+    }
+    let mut suffix = 0;
+    while suffix < n - prefix && suffix < m - prefix && eq(n - 1 - suffix, m - 1 - suffix) {
+        suffix += 1;
+    }
+
+    for _ in 0..SUFFIX_PROBES {
+        if suffix == 0 {
+            break;
+        }
+        let (i, j) = (n - suffix, m - suffix);
+        if !(prefix..i).any(|x| eq(x, j)) && !(prefix..j).any(|y| eq(i, y)) {
+            return (prefix, suffix);
+        }
+        suffix -= 1;
+    }
+    (prefix, 0)
+}
+
+pub(super) enum Segment {
+    Equal(usize),
+    /// Half-open ranges `a_lo..a_hi` and `b_lo..b_hi` still to be diffed.
+    Range(usize, usize, usize, usize),
+}
+
+/// Run-length edit script of `(count, added, removed)` entries.
+#[derive(Default)]
+struct EditScript(Vec<(u32, bool, bool)>);
+
+impl EditScript {
+    #[inline]
+    fn push(&mut self, count: usize, added: bool, removed: bool) {
+        if count == 0 {
+            return;
+        }
+        if let Some(last) = self.0.last_mut() {
+            if last.1 == added && last.2 == removed {
+                last.0 += count as u32;
+                return;
+            }
+        }
+        self.0.push((count as u32, added, removed));
+    }
+
+    /// Tokens removed or added.
+    fn edits(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|&&(_, added, removed)| added || removed)
+            .map(|&(count, ..)| count as usize)
+            .sum()
+    }
+}
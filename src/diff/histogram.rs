@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Keys occurring more often than this in `a` are not used as anchors; a
+/// range with nothing rarer in common falls back to Myers (as in git).
+pub const MAX_CHAIN_LENGTH: usize = 64;
+
+const NONE: usize = usize::MAX;
+
+/// Outcome of one histogram step over a pair of ranges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lcs {
+    /// Matching run `a[a..a + len] == b[b..b + len]` with the lowest
+    /// occurrence count, the longest among those.
+    Region { a: usize, b: usize, len: usize },
+    /// The ranges share no key at all.
+    Disjoint,
+    /// Every common key is too frequent to anchor on.
+    TooCommon,
+}
+
+/// Port of git's `find_lcs`/`try_lcs` from xdiff/xhistogram.c.
+pub fn find_lcs<K: Hash + Eq>(a: &[K], b: &[K]) -> Lcs {
+    // First occurrence and occurrence count of each key in `a`; `next` chains
+    // each position to the following occurrence of the same key.
+    let mut first: HashMap<&K, (usize, usize)> = HashMap::with_capacity(a.len());
+    let mut next = vec![NONE; a.len()];
+    for (i, k) in a.iter().enumerate().rev() {
+        match first.get_mut(k) {
+            Some(e) => {
+                next[i] = e.0;
+                e.0 = i;
+                e.1 += 1;
+            }
+            None => {
+                first.insert(k, (i, 1));
+            }
+        }
+    }
+    let count = |i: usize| first[&a[i]].1;
+
+    let mut best: Option<(usize, usize, usize)> = None;
+    let mut best_cnt = MAX_CHAIN_LENGTH + 1;
+    let mut has_common = false;
+
+    let mut b_ptr = 0;
+    while b_ptr < b.len() {
+        let mut b_next = b_ptr + 1;
+        if let Some(&(first_a, cnt)) = first.get(&b[b_ptr]) {
+            has_common = true;
+            if cnt <= best_cnt {
+                let mut a_ptr = first_a;
+                loop {
+                    let (mut a_start, mut b_start) = (a_ptr, b_ptr);
+                    let (mut a_end, mut b_end) = (a_ptr, b_ptr);
+                    let mut rc = cnt;
+
+                    while a_start > 0 && b_start > 0 && a[a_start - 1] == b[b_start - 1] {
+                        a_start -= 1;
+                        b_start -= 1;
+                        if rc > 1 {
+                            rc = rc.min(count(a_start));
+                        }
+                    }
+                    while a_end + 1 < a.len() && b_end + 1 < b.len() && a[a_end + 1] == b[b_end + 1]
+                    {
+                        a_end += 1;
+                        b_end += 1;
+                        if rc > 1 {
+                            rc = rc.min(count(a_end));
+                        }
+                    }
+
+                    b_next = b_next.max(b_end + 1);
+                    let best_span = best.map_or(0, |(_, _, len)| len - 1);
+                    if best_span < a_end - a_start || rc < best_cnt {
+                        best = Some((a_start, b_start, a_end - a_start + 1));
+                        best_cnt = rc;
+                    }
+
+                    let mut np = next[a_ptr];
+                    while np != NONE && np <= a_end {
+                        np = next[np];
+                    }
+                    if np == NONE {
+                        break;
+                    }
+                    a_ptr = np;
+                }
+            }
+        }
+        b_ptr = b_next;
+    }
+
+    match best {
+        _ if has_common && best_cnt > MAX_CHAIN_LENGTH => Lcs::TooCommon,
+        Some((a, b, len)) => Lcs::Region { a, b, len },
+        None => Lcs::Disjoint,
+    }
+}
@@ -0,0 +1,84 @@
+use crate::diff::base::Algorithm;
+use crate::diff::histogram::{find_lcs, Lcs, MAX_CHAIN_LENGTH};
+use crate::{diff_lines, structured_patch, DiffLinesOptions, StructuredOptions};
+
+fn histogram() -> DiffLinesOptions {
+    DiffLinesOptions {
+        algorithm: Algorithm::Histogram,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_find_lcs_prefers_rare_runs() {
+    let a = ["x", "a", "b", "x", "c"];
+    let b = ["x", "c", "a", "b", "x"];
+    // "x" occurs twice in `a`, so the run through the unique "a" and "b" wins
+    // over the run through "x" and "c".
+    assert_eq!(find_lcs(&a, &b), Lcs::Region { a: 1, b: 2, len: 3 });
+    assert_eq!(find_lcs(&["a"], &["b"]), Lcs::Disjoint);
+
+    let common = vec!["x"; MAX_CHAIN_LENGTH + 1];
+    assert_eq!(find_lcs(&common, &["x"]), Lcs::TooCommon);
+}
+
+#[test]
+fn test_histogram_matches_git() {
+    let old = "#include <stdio.h>\n\nint foo() {\n    return 1;\n}\n\nint bar() {\n    return 2;\n}\n\nint main() {\n    foo();\n    bar();\n    return 0;\n}\n";
+    let new = "#include <stdio.h>\n\nint baz() {\n    return 3;\n}\n\nint foo() {\n    return 1;\n}\n\nint main() {\n    baz();\n    foo();\n    return 0;\n}\n";
+    let opts = StructuredOptions {
+        context: Some(0),
+        line_options: histogram(),
+    };
+    let patch = structured_patch("a", "b", old, new, None, None, &opts).unwrap();
+
+    // Same hunks as `git diff --histogram -U0`.
+    let lines: Vec<Vec<&str>> = patch
+        .hunks
+        .iter()
+        .map(|h| h.lines.iter().map(String::as_str).collect())
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            vec!["+int baz() {", "+    return 3;", "+}", "+"],
+            vec!["-int bar() {", "-    return 2;", "-}", "-"],
+            vec!["+    baz();"],
+            vec!["-    bar();"],
+        ]
+    );
+}
+
+#[test]
+fn test_histogram_round_trips() {
+    let cases = [
+        ("", "a\nb\n"),
+        ("a\nb\n", ""),
+        ("a\nb\nc\nd\n", "d\nc\nb\na\n"),
+        ("x\ny\nx\ny\n", "y\nx\ny\nx\nz\n"),
+        ("a\n{\n}\nb\n{\n}\n", "b\n{\n}\na\n{\n}\nc\n"),
+        ("same\n", "same\n"),
+    ];
+    for (old, new) in cases {
+        let changes = diff_lines(old, new, &histogram());
+        let o: String = changes
+            .iter()
+            .filter(|c| !c.added)
+            .map(|c| c.value.as_str())
+            .collect();
+        let n: String = changes
+            .iter()
+            .filter(|c| !c.removed)
+            .map(|c| c.value.as_str())
+            .collect();
+        assert_eq!((o.as_str(), n.as_str()), (old, new));
+    }
+}
+
+#[test]
+fn test_histogram_falls_back_to_myers_on_common_lines() {
+    let old = "x\n".repeat(MAX_CHAIN_LENGTH + 1) + "a\n";
+    let new = "b\n".to_string() + &"x\n".repeat(MAX_CHAIN_LENGTH + 1);
+    let changes = diff_lines(&old, &new, &histogram());
+    assert_eq!(changes, diff_lines(&old, &new, &Default::default()));
+}
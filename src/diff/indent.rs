@@ -0,0 +1,420 @@
+use super::{
+    base::{Change, Options, Tokeniser},
+    line::LineTokenizer,
+    normalize::normalized_equals,
+    token::Token,
+};
+use crate::{change::locate_tokens, options::DiffLinesOptions};
+
+// Tuning of git's indent heuristic, from xdiff/xdiffi.c.
+const MAX_INDENT: i32 = 200;
+const MAX_BLANKS: i32 = 20;
+const MAX_SLIDING: usize = 100;
+const INDENT_WEIGHT: i32 = 60;
+
+const START_OF_FILE_PENALTY: i32 = 1;
+const END_OF_FILE_PENALTY: i32 = 21;
+const TOTAL_BLANK_WEIGHT: i32 = -30;
+const POST_BLANK_WEIGHT: i32 = 6;
+const RELATIVE_INDENT_PENALTY: i32 = -4;
+const RELATIVE_INDENT_WITH_BLANK_PENALTY: i32 = 10;
+const RELATIVE_OUTDENT_PENALTY: i32 = 24;
+const RELATIVE_OUTDENT_WITH_BLANK_PENALTY: i32 = 17;
+const RELATIVE_DEDENT_PENALTY: i32 = 23;
+const RELATIVE_DEDENT_WITH_BLANK_PENALTY: i32 = 17;
+
+/// Slides insertions and deletions of a `diff_lines(old, new, opts)` result
+/// that could sit at several positions to where `git diff --indent-heuristic`
+/// puts them, so that e.g. an added function ends with its own `}`.
+pub fn indent_heuristic(
+    old: &str,
+    new: &str,
+    changes: Vec<Change>,
+    opts: &DiffLinesOptions,
+) -> Vec<Change> {
+    if changes.is_empty() {
+        return changes;
+    }
+
+    let tokenizer = LineTokenizer::new(opts);
+    let options: Options = opts.into();
+    let eq = |l: &Token, r: &Token| match &options.comparator {
+        Some(comparator) => comparator.call(l.text, r.text),
+        None => normalized_equals(&tokenizer, l, r, &options),
+    };
+
+    let mut arena = Vec::new();
+    let mut old_side = Side::new(tokenizer.remove_empty(tokenizer.tokenize(old, &mut arena)));
+    let mut new_side = Side::new(tokenizer.remove_empty(tokenizer.tokenize(new, &mut arena)));
+
+    let (mut i, mut j) = (0, 0);
+    for change in &changes {
+        let count = change.count as usize;
+        if change.added {
+            new_side.changed[j..j + count].fill(true);
+            j += count;
+        } else if change.removed {
+            old_side.changed[i..i + count].fill(true);
+            i += count;
+        } else {
+            i += count;
+            j += count;
+        }
+    }
+
+    let (old_before, new_before) = (old_side.changed.clone(), new_side.changed.clone());
+    compact(&mut old_side, &mut new_side, &eq);
+    compact(&mut new_side, &mut old_side, &eq);
+    if old_side.changed == old_before && new_side.changed == new_before {
+        return changes;
+    }
+
+    let mut changes = rebuild(&old_side, &new_side, &tokenizer, opts.one_change_per_token);
+    if opts.positions {
+        locate_tokens(&mut changes, (old, &old_side.lines), (new, &new_side.lines));
+    }
+    changes
+}
+
+/// Lines of one input and which of them are changed.
+struct Side<'t> {
+    lines: Vec<Token<'t>>,
+    changed: Vec<bool>,
+}
+
+/// Run of changed lines `start..end`. Every unchanged line is followed by a
+/// possibly empty group, so the groups of both sides pair up in order.
+#[derive(Clone, Copy)]
+struct Group {
+    start: usize,
+    end: usize,
+}
+
+impl<'t> Side<'t> {
+    fn new(lines: Vec<Token<'t>>) -> Self {
+        Self {
+            changed: vec![false; lines.len()],
+            lines,
+        }
+    }
+
+    fn first(&self) -> Group {
+        let mut g = Group { start: 0, end: 0 };
+        self.extend_down(&mut g);
+        g
+    }
+
+    fn extend_down(&self, g: &mut Group) {
+        while g.end < self.lines.len() && self.changed[g.end] {
+            g.end += 1;
+        }
+    }
+
+    fn extend_up(&self, g: &mut Group) {
+        while g.start > 0 && self.changed[g.start - 1] {
+            g.start -= 1;
+        }
+    }
+
+    fn next(&self, g: &mut Group) -> bool {
+        if g.end == self.lines.len() {
+            return false;
+        }
+        g.start = g.end + 1;
+        g.end = g.start;
+        self.extend_down(g);
+        true
+    }
+
+    fn previous(&self, g: &mut Group) -> bool {
+        if g.start == 0 {
+            return false;
+        }
+        g.end = g.start - 1;
+        g.start = g.end;
+        self.extend_up(g);
+        true
+    }
+
+    /// Moves a non-empty group up by one line if the line above it equals its
+    /// last line, merging it with any group it then touches.
+    fn slide_up<E>(&mut self, g: &mut Group, eq: &E) -> bool
+    where
+        E: Fn(&Token, &Token) -> bool,
+    {
+        if g.start == 0 || !eq(&self.lines[g.start - 1], &self.lines[g.end - 1]) {
+            return false;
+        }
+        g.start -= 1;
+        g.end -= 1;
+        self.changed[g.start] = true;
+        self.changed[g.end] = false;
+        self.extend_up(g);
+        true
+    }
+
+    fn slide_down<E>(&mut self, g: &mut Group, eq: &E) -> bool
+    where
+        E: Fn(&Token, &Token) -> bool,
+    {
+        if g.end == self.lines.len() || !eq(&self.lines[g.start], &self.lines[g.end]) {
+            return false;
+        }
+        self.changed[g.start] = false;
+        self.changed[g.end] = true;
+        g.start += 1;
+        g.end += 1;
+        self.extend_down(g);
+        true
+    }
+
+    /// Indentation width with tabs to multiples of 8, or `None` for a blank
+    /// line.
+    fn indent(&self, line: usize) -> Option<i32> {
+        let mut width = 0;
+        for c in self.lines[line].text.chars() {
+            if !c.is_ascii_whitespace() {
+                return Some(width);
+            }
+            match c {
+                ' ' => width += 1,
+                '\t' => width += 8 - width % 8,
+                _ => {}
+            }
+            if width >= MAX_INDENT {
+                return Some(MAX_INDENT);
+            }
+        }
+        None
+    }
+
+    fn measure(&self, split: usize) -> Split {
+        let n = self.lines.len();
+        let mut m = Split {
+            end_of_file: split >= n,
+            indent: if split < n { self.indent(split) } else { None },
+            pre_blank: 0,
+            pre_indent: None,
+            post_blank: 0,
+            post_indent: None,
+        };
+        for i in (0..split.min(n)).rev() {
+            m.pre_indent = self.indent(i);
+            if m.pre_indent.is_some() {
+                break;
+            }
+            m.pre_blank += 1;
+            if m.pre_blank == MAX_BLANKS {
+                m.pre_indent = Some(0);
+                break;
+            }
+        }
+        for i in split + 1..n {
+            m.post_indent = self.indent(i);
+            if m.post_indent.is_some() {
+                break;
+            }
+            m.post_blank += 1;
+            if m.post_blank == MAX_BLANKS {
+                m.post_indent = Some(0);
+                break;
+            }
+        }
+        m
+    }
+}
+
+/// Surroundings of a split between two lines.
+struct Split {
+    end_of_file: bool,
+    indent: Option<i32>,
+    pre_blank: i32,
+    pre_indent: Option<i32>,
+    post_blank: i32,
+    post_indent: Option<i32>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Score {
+    effective_indent: i32,
+    penalty: i32,
+}
+
+impl Score {
+    fn add(&mut self, m: &Split) {
+        if m.pre_indent.is_none() && m.pre_blank == 0 {
+            self.penalty += START_OF_FILE_PENALTY;
+        }
+        if m.end_of_file {
+            self.penalty += END_OF_FILE_PENALTY;
+        }
+
+        let post_blank = if m.indent.is_none() {
+            1 + m.post_blank
+        } else {
+            0
+        };
+        let total_blank = m.pre_blank + post_blank;
+        self.penalty += TOTAL_BLANK_WEIGHT * total_blank + POST_BLANK_WEIGHT * post_blank;
+
+        let indent = m.indent.or(m.post_indent);
+        let any_blanks = total_blank != 0;
+        self.effective_indent += indent.unwrap_or(-1);
+
+        let (Some(indent), Some(pre_indent)) = (indent, m.pre_indent) else {
+            return;
+        };
+        if indent > pre_indent {
+            self.penalty += if any_blanks {
+                RELATIVE_INDENT_WITH_BLANK_PENALTY
+            } else {
+                RELATIVE_INDENT_PENALTY
+            };
+        } else if indent < pre_indent {
+            let outdent = m.post_indent.is_some_and(|post| post > indent);
+            self.penalty += match (outdent, any_blanks) {
+                (true, true) => RELATIVE_OUTDENT_WITH_BLANK_PENALTY,
+                (true, false) => RELATIVE_OUTDENT_PENALTY,
+                (false, true) => RELATIVE_DEDENT_WITH_BLANK_PENALTY,
+                (false, false) => RELATIVE_DEDENT_PENALTY,
+            };
+        }
+    }
+
+    /// Whether `self` is at least as good as `other`.
+    fn beats(&self, other: &Score) -> bool {
+        let indents = (self.effective_indent - other.effective_indent).signum();
+        INDENT_WEIGHT * indents + self.penalty - other.penalty <= 0
+    }
+}
+
+/// Port of git's `xdl_change_compact`: slides each group of `x` as far down
+/// as it goes, then back up to line up with a group of `o` if it passed one,
+/// or else to the split the indent heuristic scores best.
+fn compact<E>(x: &mut Side, o: &mut Side, eq: &E)
+where
+    E: Fn(&Token, &Token) -> bool,
+{
+    let (mut g, mut go) = (x.first(), o.first());
+    loop {
+        if g.end != g.start {
+            let (mut size, mut earliest_end, mut end_matching_other);
+            loop {
+                size = g.end - g.start;
+                end_matching_other = None;
+
+                while x.slide_up(&mut g, eq) {
+                    sync(o.previous(&mut go));
+                }
+                earliest_end = g.end;
+                if go.end > go.start {
+                    end_matching_other = Some(g.end);
+                }
+
+                while x.slide_down(&mut g, eq) {
+                    sync(o.next(&mut go));
+                    if go.end > go.start {
+                        end_matching_other = Some(g.end);
+                    }
+                }
+
+                // Sliding may have merged neighbouring groups; start over.
+                if size == g.end - g.start {
+                    break;
+                }
+            }
+
+            let target = if g.end == earliest_end {
+                None
+            } else if end_matching_other.is_some() {
+                while go.end == go.start {
+                    sync(x.slide_up(&mut g, eq));
+                    sync(o.previous(&mut go));
+                }
+                None
+            } else {
+                let first = earliest_end
+                    .max(g.end.saturating_sub(size + 1))
+                    .max(g.end.saturating_sub(MAX_SLIDING));
+                let mut best: Option<(usize, Score)> = None;
+                for shift in first..=g.end {
+                    let mut score = Score::default();
+                    score.add(&x.measure(shift));
+                    score.add(&x.measure(shift - size));
+                    if best.is_none_or(|(_, b)| score.beats(&b)) {
+                        best = Some((shift, score));
+                    }
+                }
+                best.map(|(shift, _)| shift)
+            };
+            if let Some(target) = target {
+                while g.end > target {
+                    sync(x.slide_up(&mut g, eq));
+                    sync(o.previous(&mut go));
+                }
+            }
+        }
+
+        if !x.next(&mut g) {
+            break;
+        }
+        sync(o.next(&mut go));
+    }
+}
+
+/// The groups of both sides always move together.
+#[inline(always)]
+fn sync(moved: bool) {
+    debug_assert!(moved, "group sync broken");
+}
+
+fn rebuild(
+    old: &Side,
+    new: &Side,
+    tokenizer: &LineTokenizer,
+    one_change_per_token: bool,
+) -> Vec<Change> {
+    let mut out = Vec::new();
+    let mut push = |toks: &[Token], added: bool, removed: bool| {
+        let runs: Vec<&[Token]> = if one_change_per_token {
+            toks.chunks(1).collect()
+        } else if toks.is_empty() {
+            Vec::new()
+        } else {
+            vec![toks]
+        };
+        for run in runs {
+            out.push(Change {
+                value: tokenizer.join(run),
+                count: run.len() as u32,
+                added,
+                removed,
+                position: None,
+            });
+        }
+    };
+
+    let (n, m) = (old.lines.len(), new.lines.len());
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        let common = j;
+        while i < n && j < m && !old.changed[i] && !new.changed[j] {
+            i += 1;
+            j += 1;
+        }
+        push(&new.lines[common..j], false, false);
+
+        let removed = i;
+        while i < n && old.changed[i] {
+            i += 1;
+        }
+        push(&old.lines[removed..i], false, true);
+
+        let added = j;
+        while j < m && new.changed[j] {
+            j += 1;
+        }
+        push(&new.lines[added..j], true, false);
+    }
+    out
+}
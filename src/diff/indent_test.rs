@@ -0,0 +1,85 @@
+use crate::{diff_lines, structured_patch, DiffLinesOptions, StructuredOptions};
+
+const OLD: &str = "#[test]\nfn a() {\n    run();\n}\n";
+const NEW: &str = "#[test]\nfn b() {\n    run();\n}\n\n#[test]\nfn a() {\n    run();\n}\n";
+
+fn patch_lines(opts: StructuredOptions) -> Vec<String> {
+    let patch = structured_patch("a", "b", OLD, NEW, None, None, &opts).unwrap();
+    patch.hunks.into_iter().flat_map(|h| h.lines).collect()
+}
+
+#[test]
+fn test_patch_keeps_inserted_function_whole() {
+    let opts = StructuredOptions {
+        context: Some(0),
+        ..Default::default()
+    };
+    // Same hunk as `git diff --indent-heuristic -U0`.
+    assert_eq!(
+        patch_lines(opts.clone()),
+        vec!["+#[test]", "+fn b() {", "+    run();", "+}", "+"]
+    );
+
+    let opts = StructuredOptions {
+        line_options: DiffLinesOptions {
+            indent_heuristic: Some(false),
+            ..Default::default()
+        },
+        ..opts
+    };
+    assert_eq!(
+        patch_lines(opts),
+        vec!["+fn b() {", "+    run();", "+}", "+", "+#[test]"]
+    );
+}
+
+#[test]
+fn test_diff_lines_slides_only_when_asked() {
+    let plain = diff_lines(OLD, NEW, &DiffLinesOptions::default());
+    assert_eq!(plain[1].value, "fn b() {\n    run();\n}\n\n#[test]\n");
+
+    let opts = DiffLinesOptions {
+        indent_heuristic: Some(true),
+        ..Default::default()
+    };
+    let slid = diff_lines(OLD, NEW, &opts);
+    let values: Vec<(&str, bool)> = slid.iter().map(|c| (c.value.as_str(), c.added)).collect();
+    assert_eq!(
+        values,
+        vec![
+            ("#[test]\nfn b() {\n    run();\n}\n\n", true),
+            ("#[test]\nfn a() {\n    run();\n}\n", false),
+        ]
+    );
+}
+
+#[test]
+fn test_sliding_keeps_diff_valid() {
+    let pieces = [
+        "{", "}", "", "  x();", "  y();", "if (a) {", "\tz();", "  }",
+    ];
+    let text = |k: usize| -> String {
+        (0..k)
+            .map(|_| format!("{}\n", pieces[fastrand::usize(..pieces.len())]))
+            .collect()
+    };
+    fastrand::seed(12);
+    for _ in 0..2000 {
+        let (old, new) = (text(fastrand::usize(..12)), text(fastrand::usize(..12)));
+        let opts = DiffLinesOptions {
+            indent_heuristic: Some(true),
+            one_change_per_token: fastrand::bool(),
+            ..Default::default()
+        };
+        let changes = diff_lines(&old, &new, &opts);
+        let side = |skip_added: bool| -> String {
+            changes
+                .iter()
+                .filter(|c| if skip_added { !c.added } else { !c.removed })
+                .map(|c| c.value.as_str())
+                .collect()
+        };
+        assert_eq!(side(true), old, "{old:?} -> {new:?}");
+        assert_eq!(side(false), new, "{old:?} -> {new:?}");
+    }
+}
@@ -1,18 +1,11 @@
-use js_sys::JSON;
-use serde::Deserialize;
-use serde_wasm_bindgen as swb;
-use wasm_bindgen::prelude::*;
+use std::borrow::Cow;
 
 use super::{
-    base::{Options, Tokeniser},
+    base::{Change, Options, Tokeniser},
+    memory_pool::PooledDiff,
     token::Token,
 };
-
-#[derive(Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-struct JsonOptions {
-    undefined_replacement: Option<serde_json::Value>,
-}
+use crate::options::DiffJsonOptions;
 
 #[derive(Default, Clone)]
 pub struct JsonTokenizer;
@@ -47,20 +40,30 @@ impl<'a> Tokeniser<'a> for JsonTokenizer {
     }
 
     fn equals(&self, l: &Token<'a>, r: &Token<'a>, _o: &Options) -> bool {
-        fn strip(line: &str) -> std::borrow::Cow<'_, str> {
-            if let Some(rest) = line.strip_suffix(",\n") {
-                let mut s = rest.to_owned();
-                s.push('\n');
-                s.into()
-            } else if let Some(rest) = line.strip_suffix(",\r") {
-                let mut s = rest.to_owned();
-                s.push('\r');
-                s.into()
-            } else {
-                line.into()
-            }
-        }
-        strip(l.text) == strip(r.text)
+        strip_trailing_comma(l.text) == strip_trailing_comma(r.text)
+    }
+
+    fn comparison_key(&self, tok: &Token<'a>, _o: &Options) -> Cow<'a, str> {
+        strip_trailing_comma(tok.text)
+    }
+
+    #[inline(always)]
+    fn interned(&self, _o: &Options) -> bool {
+        true
+    }
+}
+
+fn strip_trailing_comma(line: &str) -> Cow<'_, str> {
+    if let Some(rest) = line.strip_suffix(",\n") {
+        let mut s = rest.to_owned();
+        s.push('\n');
+        s.into()
+    } else if let Some(rest) = line.strip_suffix(",\r") {
+        let mut s = rest.to_owned();
+        s.push('\r');
+        s.into()
+    } else {
+        line.into()
     }
 }
 
@@ -82,20 +85,12 @@ pub fn canonicalize_value(v: &serde_json::Value) -> serde_json::Value {
     }
 }
 
-#[wasm_bindgen(js_name = canonicalize)]
-pub fn canonicalize(js_val: JsValue) -> Result<JsValue, JsValue> {
-    let val: serde_json::Value = swb::from_value(js_val)?;
-    let canon = canonicalize_value(&val);
-    let as_string = serde_json::to_string(&canon).unwrap();
-    JSON::parse(&as_string)
-}
-
-fn to_pretty_json(val: &JsValue, opts: &JsonOptions) -> Result<String, JsValue> {
-    if val.is_string() {
-        return Ok(val.as_string().unwrap());
+pub fn to_pretty_json(val: &serde_json::Value, opts: &DiffJsonOptions) -> String {
+    if let serde_json::Value::String(s) = val {
+        return s.clone();
     }
 
-    let mut ser: serde_json::Value = swb::from_value(val.clone())?;
+    let mut ser = val.clone();
     if let Some(repl) = &opts.undefined_replacement {
         fn patch(v: &mut serde_json::Value, rep: &serde_json::Value) {
             match v {
@@ -109,20 +104,17 @@ fn to_pretty_json(val: &JsValue, opts: &JsonOptions) -> Result<String, JsValue>
     }
 
     let canon = canonicalize_value(&ser);
-    Ok(serde_json::to_string_pretty(&canon).unwrap())
+    serde_json::to_string_pretty(&canon).unwrap()
 }
 
-#[wasm_bindgen(js_name = diffJson)]
-pub fn diff_json(old_val: JsValue, new_val: JsValue, opts: JsValue) -> Result<JsValue, JsValue> {
-    let jo: JsonOptions = swb::from_value(opts).unwrap_or_default();
-    let rust_opts = Options::default();
-
-    let old_s = to_pretty_json(&old_val, &jo)?;
-    let new_s = to_pretty_json(&new_val, &jo)?;
-
-    use super::memory_pool::PooledDiff;
-    let mut diff = PooledDiff::new(JsonTokenizer, rust_opts).with_longest_token();
-    let changes = diff.diff(&old_s, &new_s);
+pub fn diff_json(
+    old_val: &serde_json::Value,
+    new_val: &serde_json::Value,
+    opts: &DiffJsonOptions,
+) -> Vec<Change> {
+    let old_s = to_pretty_json(old_val, opts);
+    let new_s = to_pretty_json(new_val, opts);
 
-    swb::to_value(&changes).map_err(Into::into)
+    let mut diff = PooledDiff::new(JsonTokenizer, Options::default()).with_longest_token();
+    diff.diff(&old_s, &new_s)
 }
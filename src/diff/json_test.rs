@@ -203,3 +203,37 @@ fn test_real_world_example() {
     };
     assert!(tokenizer.equals(&a, &b, &options));
 }
+
+#[test]
+fn test_diff_json_native_api() {
+    use crate::{diff_json, DiffJsonOptions};
+    use serde_json::json;
+
+    let old = json!({"b": 2, "a": 1});
+    let new = json!({"a": 1, "b": 3});
+    let changes = diff_json(&old, &new, &DiffJsonOptions::default());
+
+    let rebuilt_old: String = changes
+        .iter()
+        .filter(|c| !c.added)
+        .map(|c| c.value.as_str())
+        .collect();
+    assert_eq!(rebuilt_old, "{\n  \"a\": 1,\n  \"b\": 2\n}");
+    assert!(changes
+        .iter()
+        .any(|c| c.removed && c.value == "  \"b\": 2\n"));
+    assert!(changes.iter().any(|c| c.added && c.value == "  \"b\": 3\n"));
+}
+
+#[test]
+fn test_diff_json_native_undefined_replacement() {
+    use crate::{diff_json, DiffJsonOptions};
+    use serde_json::json;
+
+    let opts = DiffJsonOptions {
+        undefined_replacement: Some(json!("x")),
+    };
+    let changes = diff_json(&json!({"a": null}), &json!({"a": "x"}), &opts);
+    assert_eq!(changes.len(), 1);
+    assert!(!changes[0].added && !changes[0].removed);
+}
@@ -1,41 +1,13 @@
-use serde::{Deserialize, Serialize};
-use serde_wasm_bindgen as swb;
-use wasm_bindgen::{prelude::*, JsValue};
+use std::borrow::Cow;
 
 use super::{
-    base::{Options, Tokeniser},
+    base::{Change, Options, Tokeniser},
+    case::{eq_ignore_case, fold_case},
+    indent::indent_heuristic,
+    memory_pool::PooledDiff,
     token::Token,
 };
-
-#[derive(Deserialize, Serialize, Default, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct LineOptions {
-    #[serde(default)]
-    strip_trailing_cr: bool,
-    #[serde(default)]
-    newline_is_token: bool,
-    #[serde(default)]
-    ignore_whitespace: bool,
-    #[serde(default)]
-    ignore_newline_at_eof: bool,
-
-    #[serde(default)]
-    ignore_case: bool,
-    #[serde(default)]
-    one_change_per_token: bool,
-    #[serde(default)]
-    max_edit_length: Option<usize>,
-}
-
-impl From<&LineOptions> for Options {
-    fn from(lo: &LineOptions) -> Self {
-        Options {
-            ignore_case: lo.ignore_case,
-            one_change_per_token: lo.one_change_per_token,
-            max_edit_length: lo.max_edit_length,
-        }
-    }
-}
+use crate::options::DiffLinesOptions;
 
 #[derive(Clone)]
 pub struct LineTokenizer {
@@ -45,7 +17,7 @@ pub struct LineTokenizer {
 }
 
 impl LineTokenizer {
-    pub fn new(lo: &LineOptions) -> Self {
+    pub fn new(lo: &DiffLinesOptions) -> Self {
         Self {
             newline_is_token: lo.newline_is_token,
             ignore_whitespace: lo.ignore_whitespace,
@@ -66,6 +38,24 @@ impl LineTokenizer {
     }
 }
 
+impl LineTokenizer {
+    /// The part of a line that takes part in comparisons.
+    #[inline]
+    fn comparable<'t>(&self, text: &'t str) -> &'t str {
+        if self.ignore_whitespace {
+            if self.newline_is_token && text.contains('\n') {
+                text
+            } else {
+                text.trim()
+            }
+        } else if self.ignore_newline_at_eof && !self.newline_is_token {
+            text.strip_suffix('\n').unwrap_or(text)
+        } else {
+            text
+        }
+    }
+}
+
 impl<'a> Tokeniser<'a> for LineTokenizer {
     fn cast_input(&self, input: &'a str, _opts: &Options) -> &'a str {
         // strip_trailing_cr preprocessing is handled at the diff_lines level
@@ -127,94 +117,72 @@ impl<'a> Tokeniser<'a> for LineTokenizer {
 
     #[inline]
     fn equals(&self, l: &Token<'a>, r: &Token<'a>, opts: &Options) -> bool {
-        let mut left = l.text;
-        let mut right = r.text;
+        let left = self.comparable(l.text);
+        let right = self.comparable(r.text);
 
-        if self.ignore_whitespace {
-            if !(self.newline_is_token && left.contains('\n')) {
-                left = left.trim();
-            }
-            if !(self.newline_is_token && right.contains('\n')) {
-                right = right.trim();
-            }
-        } else if self.ignore_newline_at_eof && !self.newline_is_token {
-            if left.ends_with('\n') {
-                left = &left[..left.len() - 1];
-            }
-            if right.ends_with('\n') {
-                right = &right[..right.len() - 1];
-            }
+        if opts.ignore_case {
+            eq_ignore_case(left, right, opts.case_locale)
+        } else {
+            left == right
         }
+    }
 
+    #[inline]
+    fn comparison_key(&self, tok: &Token<'a>, opts: &Options) -> Cow<'a, str> {
+        let text = self.comparable(tok.text);
         if opts.ignore_case {
-            left.eq_ignore_ascii_case(right)
+            fold_case(text, opts.case_locale)
         } else {
-            left == right
+            Cow::Borrowed(text)
         }
     }
-}
 
-#[wasm_bindgen(js_name = diffLines)]
-pub fn diff_lines(old_str: &str, new_str: &str, js_opts: JsValue) -> Result<JsValue, JsValue> {
-    let lo: LineOptions = swb::from_value(js_opts).unwrap_or_default();
+    #[inline(always)]
+    fn interned(&self, opts: &Options) -> bool {
+        // Plain lines already compare by length and then bytes.
+        self.ignore_whitespace || self.ignore_newline_at_eof || opts.ignore_case
+    }
+}
 
-    if lo.strip_trailing_cr {
+pub fn diff_lines(old_str: &str, new_str: &str, opts: &DiffLinesOptions) -> Vec<Change> {
+    if opts.strip_trailing_cr {
         let old_processed = old_str.replace("\r\n", "\n");
         let new_processed = new_str.replace("\r\n", "\n");
 
-        let tokenizer = LineTokenizer::new(&lo);
-        let options: Options = (&lo).into();
-
-        use super::memory_pool::PooledDiff;
-        let mut diff = PooledDiff::new(tokenizer, options);
-        let changes = diff.diff(&old_processed, &new_processed);
-        return swb::to_value(&changes).map_err(|e| JsValue::from(e.to_string()));
+        return diff_lines_raw(&old_processed, &new_processed, opts);
     }
 
-    let tokenizer = LineTokenizer::new(&lo);
-    let options: Options = (&lo).into();
+    diff_lines_raw(old_str, new_str, opts)
+}
 
-    use super::memory_pool::PooledDiff;
-    let mut diff = PooledDiff::new(tokenizer, options);
+fn diff_lines_raw(old_str: &str, new_str: &str, opts: &DiffLinesOptions) -> Vec<Change> {
+    let mut diff = PooledDiff::new(LineTokenizer::new(opts), opts.into());
     let changes = diff.diff(old_str, new_str);
-    swb::to_value(&changes).map_err(|e| JsValue::from(e.to_string()))
+    if opts.indent_heuristic == Some(true) {
+        return indent_heuristic(old_str, new_str, changes, opts);
+    }
+    changes
 }
 
-#[wasm_bindgen(js_name = diffTrimmedLines)]
-pub fn diff_trimmed_lines(
-    old_str: &str,
-    new_str: &str,
-    callback_or_opts: JsValue,
-) -> Result<JsValue, JsValue> {
-    let mut lo: LineOptions = swb::from_value(callback_or_opts).unwrap_or_default();
-    lo.ignore_whitespace = true;
-    diff_lines(old_str, new_str, swb::to_value(&lo)?)
+pub fn diff_trimmed_lines(old_str: &str, new_str: &str, opts: &DiffLinesOptions) -> Vec<Change> {
+    let lo = DiffLinesOptions {
+        ignore_whitespace: true,
+        ..opts.clone()
+    };
+    diff_lines(old_str, new_str, &lo)
 }
 
-#[wasm_bindgen(js_name = lineDiff)]
-pub struct LineDiff;
-
-#[wasm_bindgen(js_class = lineDiff)]
-impl LineDiff {
-    #[wasm_bindgen(js_name = tokenize)]
-    pub fn tokenize_js(value: &str, opts: JsValue) -> Result<JsValue, JsValue> {
-        let lo: LineOptions = swb::from_value(opts).unwrap_or_default();
-
-        let preprocessed;
-        let input = if lo.strip_trailing_cr {
-            preprocessed = value.replace("\r\n", "\n");
-            &preprocessed
-        } else {
-            value
-        };
-
-        let tokenizer = LineTokenizer::new(&lo);
-
-        let mut arena = Vec::new();
-        let tokens = tokenizer.tokenize(input, &mut arena);
-
-        // Convert tokens to Vec<String> to match expected behavior
-        let result: Vec<String> = tokens.iter().map(|t| t.text.to_string()).collect();
-        swb::to_value(&result).map_err(|e| JsValue::from(e.to_string()))
-    }
+pub fn tokenize_lines(value: &str, opts: &DiffLinesOptions) -> Vec<String> {
+    let preprocessed;
+    let input = if opts.strip_trailing_cr {
+        preprocessed = value.replace("\r\n", "\n");
+        &preprocessed
+    } else {
+        value
+    };
+
+    let tokenizer = LineTokenizer::new(opts);
+    let mut arena = Vec::new();
+    let tokens = tokenizer.tokenize(input, &mut arena);
+    tokens.iter().map(|t| t.text.to_string()).collect()
 }
@@ -1,4 +1,4 @@
-use crate::diff::base::{Options, Tokeniser};
+use crate::diff::base::{Options, Tokeniser, WithComparator};
 use crate::diff::line::LineTokenizer;
 use crate::diff::token::Token;
 
@@ -247,6 +247,7 @@ fn test_line_tokenizer_equals_with_options() {
                 ignore_case: false,
                 one_change_per_token: false,
                 max_edit_length: None,
+                ..Default::default()
             },
             expected_result: true,
         },
@@ -259,6 +260,7 @@ fn test_line_tokenizer_equals_with_options() {
                 ignore_case: false,
                 one_change_per_token: false,
                 max_edit_length: None,
+                ..Default::default()
             },
             expected_result: false,
         },
@@ -271,6 +273,7 @@ fn test_line_tokenizer_equals_with_options() {
                 ignore_case: true,
                 one_change_per_token: false,
                 max_edit_length: None,
+                ..Default::default()
             },
             expected_result: true,
         },
@@ -286,6 +289,7 @@ fn test_line_tokenizer_equals_with_options() {
                 ignore_case: false,
                 one_change_per_token: false,
                 max_edit_length: None,
+                ..Default::default()
             },
             expected_result: true,
         },
@@ -298,6 +302,7 @@ fn test_line_tokenizer_equals_with_options() {
                 ignore_case: false,
                 one_change_per_token: false,
                 max_edit_length: None,
+                ..Default::default()
             },
             expected_result: false,
         },
@@ -313,6 +318,7 @@ fn test_line_tokenizer_equals_with_options() {
                 ignore_case: false,
                 one_change_per_token: false,
                 max_edit_length: None,
+                ..Default::default()
             },
             expected_result: true,
         },
@@ -325,6 +331,7 @@ fn test_line_tokenizer_equals_with_options() {
                 ignore_case: false,
                 one_change_per_token: false,
                 max_edit_length: None,
+                ..Default::default()
             },
             expected_result: false,
         },
@@ -341,6 +348,7 @@ fn test_line_tokenizer_equals_with_options() {
                 ignore_case: false,
                 one_change_per_token: false,
                 max_edit_length: None,
+                ..Default::default()
             },
             expected_result: true,
         },
@@ -477,3 +485,89 @@ fn test_line_tokenizer_edge_cases() {
         );
     }
 }
+
+#[test]
+fn test_diff_lines_native_api() {
+    use crate::{diff_lines, diff_trimmed_lines, DiffLinesOptions};
+
+    let changes = diff_lines("a\nb\nc\n", "a\nx\nc\n", &DiffLinesOptions::default());
+    assert_eq!(changes.len(), 4);
+    assert_eq!(changes[0].value, "a\n");
+    assert_eq!(changes[1].value, "b\n");
+    assert!(changes[1].removed);
+    assert_eq!(changes[2].value, "x\n");
+    assert!(changes[2].added);
+    assert_eq!(changes[3].value, "c\n");
+
+    let crlf = DiffLinesOptions {
+        strip_trailing_cr: true,
+        ..Default::default()
+    };
+    let changes = diff_lines("a\r\nb\r\n", "a\nb\n", &crlf);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].count, 2);
+
+    let changes = diff_trimmed_lines("  a\nb\n", "a  \nb\n", &DiffLinesOptions::default());
+    assert_eq!(changes.len(), 1);
+    assert!(!changes[0].added && !changes[0].removed);
+}
+
+#[test]
+fn test_diff_lines_comparator_overrides_algorithm_keys() {
+    use crate::diff::base::Algorithm;
+    use crate::{diff_lines, DiffLinesOptions};
+
+    for algorithm in [Algorithm::Myers, Algorithm::Patience, Algorithm::Histogram] {
+        let opts = DiffLinesOptions {
+            algorithm,
+            ..Default::default()
+        }
+        .with_comparator(|l, r| l.trim_start() == r.trim_start());
+        let changes = diff_lines("a\n  b\nc\n", "a\nb\nd\n", &opts);
+        assert_eq!(changes.len(), 3, "{algorithm:?}");
+        assert_eq!(changes[0].value, "a\nb\n");
+        assert!(changes[1].removed && changes[2].added);
+    }
+}
+
+#[test]
+fn test_interned_lines_match_direct_comparison() {
+    use crate::diff::memory_pool::PooledDiff;
+    use crate::{diff_lines, DiffLinesOptions};
+
+    let mut rng = fastrand::Rng::with_seed(3);
+    let words = ["a", " a", "A ", "b", "B", "  c\t", "c"];
+    let text = |rng: &mut fastrand::Rng| -> String {
+        (0..60)
+            .map(|_| format!("{}\n", words[rng.usize(..words.len())]))
+            .collect()
+    };
+
+    for _ in 0..20 {
+        let (old, new) = (text(&mut rng), text(&mut rng));
+        for (ignore_whitespace, ignore_case) in [(true, false), (false, true), (true, true)] {
+            let lo = DiffLinesOptions {
+                ignore_whitespace,
+                ignore_case,
+                ..Default::default()
+            };
+            let tokenizer = LineTokenizer::new(&lo);
+            assert!(tokenizer.interned(&(&lo).into()));
+
+            // A comparator turns interning off, so this runs on `equals`.
+            let direct = Options::from(&lo).with_comparator(move |l, r| {
+                let tok = LineTokenizer::new(&DiffLinesOptions {
+                    ignore_whitespace,
+                    ..Default::default()
+                });
+                let opts = Options {
+                    ignore_case,
+                    ..Default::default()
+                };
+                tok.equals(&Token { text: l }, &Token { text: r }, &opts)
+            });
+            let expected = PooledDiff::new(tokenizer, direct).diff(&old, &new);
+            assert_eq!(diff_lines(&old, &new, &lo), expected);
+        }
+    }
+}
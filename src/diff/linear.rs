@@ -0,0 +1,319 @@
+use super::engine::Segment;
+use crate::util::deadline::Deadline;
+
+/// Combined token count above which `PooledDiff` uses the linear-space
+/// engine unless `Options::linear_space_threshold` says otherwise.
+pub const LINEAR_SPACE_THRESHOLD: usize = 100_000;
+
+/// Linear-space Myers (divide and conquer on middle snakes, Myers 1986 §4b).
+///
+/// Diffs `n` old tokens against `m` new ones, compared with `eq(i, j)`, and
+/// reports the edit script to `emit(count, added, removed)` with deletions
+/// ahead of insertions inside each changed run. Returns `None` without
+/// emitting anything when the edit distance exceeds `max_edit_length`, and
+/// otherwise whether `deadline` cut the search short; ranges left unsearched
+/// at that point are reported as a plain removal and addition.
+#[cfg(test)]
+pub(crate) fn diff<E, F>(
+    n: usize,
+    m: usize,
+    eq: E,
+    max_edit_length: Option<usize>,
+    deadline: Option<Deadline>,
+    mut emit: F,
+) -> Option<bool>
+where
+    E: Fn(usize, usize) -> bool,
+    F: FnMut(usize, bool, bool),
+{
+    let mut linear = Linear::new(n, m, &eq, max_edit_length, deadline)?;
+    while linear.step(&eq) {}
+    for (count, added, removed) in linear.finish() {
+        emit(count, added, removed);
+    }
+    Some(linear.timed_out)
+}
+
+/// Linear-space search in progress. Each `step` splits or settles one of the
+/// ranges still to diff, so the search can pause between steps.
+pub(crate) struct Linear {
+    vf: V,
+    vb: V,
+    /// Ranges and common runs still to emit, the next one last.
+    stack: Vec<Segment>,
+    script: Script,
+    deadline: Option<Deadline>,
+    pub(crate) timed_out: bool,
+}
+
+impl Linear {
+    /// Prepares a diff of `n` old against `m` new tokens, or returns `None`
+    /// when the edit distance exceeds `max_edit_length`.
+    pub(crate) fn new<E>(
+        n: usize,
+        m: usize,
+        eq: &E,
+        max_edit_length: Option<usize>,
+        deadline: Option<Deadline>,
+    ) -> Option<Self>
+    where
+        E: Fn(usize, usize) -> bool,
+    {
+        let d_max = (n + m).div_ceil(2) + 1;
+        let mut linear = Linear {
+            vf: V::new(d_max),
+            vb: V::new(d_max),
+            stack: vec![Segment::Range(0, n, 0, m)],
+            script: Script::default(),
+            deadline,
+            timed_out: false,
+        };
+
+        if let Some(limit) = max_edit_length {
+            let (a_lo, a_hi, b_lo, b_hi) = trim(eq, 0, n, 0, m);
+            if a_lo < a_hi && b_lo < b_hi {
+                // Middle snake at depth `d` means an edit distance of 2d - 1 or 2d.
+                match linear.middle_snake(eq, a_lo, a_hi, b_lo, b_hi, limit.div_ceil(2) + 1) {
+                    // The snake is the one the first step would search for.
+                    Some((x, y, d)) if d <= limit => {
+                        linear.stack.clear();
+                        linear.split((0, n), (a_lo, a_hi, b_lo, b_hi), Some((x, y)));
+                    }
+                    None if linear.timed_out => {}
+                    _ => return None,
+                }
+            } else if (a_hi - a_lo) + (b_hi - b_lo) > limit {
+                return None;
+            }
+        }
+        Some(linear)
+    }
+
+    /// Handles the next pending range or run. Returns whether any are left.
+    pub(crate) fn step<E>(&mut self, eq: &E) -> bool
+    where
+        E: Fn(usize, usize) -> bool,
+    {
+        match self.stack.pop() {
+            Some(Segment::Equal(count)) => self.script.equal(count),
+            Some(Segment::Range(a_lo, a_hi, b_lo, b_hi)) => {
+                let (lo, hi, b_lo2, b_hi2) = trim(eq, a_lo, a_hi, b_lo, b_hi);
+                let snake = (lo < hi && b_lo2 < b_hi2)
+                    .then(|| {
+                        let d_max = (hi - lo + b_hi2 - b_lo2).div_ceil(2) + 1;
+                        self.middle_snake(eq, lo, hi, b_lo2, b_hi2, d_max)
+                    })
+                    .flatten()
+                    .map(|(x, y, _)| (x, y));
+                self.split((a_lo, a_hi), (lo, hi, b_lo2, b_hi2), snake);
+            }
+            None => {}
+        }
+        !self.stack.is_empty()
+    }
+
+    /// Records the common prefix and suffix that trimmed old tokens
+    /// `a_lo..a_hi` down to the given ranges, and splits those at `snake`, or
+    /// records them as one change when there is none.
+    fn split(
+        &mut self,
+        (a_lo, a_hi): (usize, usize),
+        (lo, hi, b_lo, b_hi): (usize, usize, usize, usize),
+        snake: Option<(usize, usize)>,
+    ) {
+        self.script.equal(lo - a_lo);
+        self.stack.push(Segment::Equal(a_hi - hi));
+        match snake {
+            Some((x, y)) => {
+                self.stack.push(Segment::Range(x, hi, y, b_hi));
+                self.stack.push(Segment::Range(lo, x, b_lo, y));
+            }
+            None => self.script.change(hi - lo, b_hi - b_lo),
+        }
+    }
+
+    /// Tokens removed or added so far.
+    pub(crate) fn edits(&self) -> usize {
+        self.script.edits
+    }
+
+    /// The edit script, once `step` has run out of ranges.
+    pub(crate) fn finish(&mut self) -> Vec<(usize, bool, bool)> {
+        self.script.flush();
+        std::mem::take(&mut self.script.out)
+    }
+
+    /// Finds a point `(x, y)` on an optimal path through the given ranges
+    /// that splits it into two halves, along with the edit distance of the
+    /// whole path. Gives up (returning `None`) once `d` reaches `d_limit` or
+    /// the deadline passes.
+    fn middle_snake<E>(
+        &mut self,
+        eq: &E,
+        a_lo: usize,
+        a_hi: usize,
+        b_lo: usize,
+        b_hi: usize,
+        d_limit: usize,
+    ) -> Option<(usize, usize, usize)>
+    where
+        E: Fn(usize, usize) -> bool,
+    {
+        let (n, m) = (a_hi - a_lo, b_hi - b_lo);
+        let delta = n as isize - m as isize;
+        let odd = delta & 1 == 1;
+
+        self.vf[1] = 0;
+        self.vb[1] = 0;
+
+        for d in 0..d_limit.min(self.vf.max_d) as isize {
+            if self.timed_out || self.deadline.is_some_and(|t| t.expired()) {
+                self.timed_out = true;
+                return None;
+            }
+
+            // Forward search from the top-left corner.
+            for k in (-d..=d).rev().step_by(2) {
+                let mut x = if k == -d || (k != d && self.vf[k - 1] < self.vf[k + 1]) {
+                    self.vf[k + 1]
+                } else {
+                    self.vf[k - 1] + 1
+                };
+                let mut y = (x as isize - k) as usize;
+                let (x0, y0) = (x, y);
+                while x < n && y < m && eq(a_lo + x, b_lo + y) {
+                    x += 1;
+                    y += 1;
+                }
+                self.vf[k] = x;
+
+                if odd && (k - delta).abs() < d && self.vf[k] + self.vb[delta - k] >= n {
+                    return Some((a_lo + x0, b_lo + y0, (2 * d - 1) as usize));
+                }
+            }
+
+            // Backward search from the bottom-right corner; `vb` holds the
+            // distance travelled back from the end.
+            for k in (-d..=d).rev().step_by(2) {
+                let mut x = if k == -d || (k != d && self.vb[k - 1] < self.vb[k + 1]) {
+                    self.vb[k + 1]
+                } else {
+                    self.vb[k - 1] + 1
+                };
+                let mut y = (x as isize - k) as usize;
+                while x < n && y < m && eq(a_hi - x - 1, b_hi - y - 1) {
+                    x += 1;
+                    y += 1;
+                }
+                self.vb[k] = x;
+
+                if !odd && (k - delta).abs() <= d && self.vb[k] + self.vf[delta - k] >= n {
+                    return Some((a_hi - x, b_hi - y, (2 * d) as usize));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Narrows the ranges by their common prefix and suffix.
+fn trim<E>(
+    eq: &E,
+    mut a_lo: usize,
+    mut a_hi: usize,
+    mut b_lo: usize,
+    mut b_hi: usize,
+) -> (usize, usize, usize, usize)
+where
+    E: Fn(usize, usize) -> bool,
+{
+    while a_lo < a_hi && b_lo < b_hi && eq(a_lo, b_lo) {
+        a_lo += 1;
+        b_lo += 1;
+    }
+    while a_lo < a_hi && b_lo < b_hi && eq(a_hi - 1, b_hi - 1) {
+        a_hi -= 1;
+        b_hi -= 1;
+    }
+    (a_lo, a_hi, b_lo, b_hi)
+}
+
+/// Furthest-reaching `x` per diagonal, indexable by `-max_d..=max_d`.
+struct V {
+    max_d: usize,
+    v: Vec<usize>,
+}
+
+impl V {
+    fn new(max_d: usize) -> Self {
+        Self {
+            max_d,
+            v: vec![0; 2 * max_d + 2],
+        }
+    }
+}
+
+impl std::ops::Index<isize> for V {
+    type Output = usize;
+
+    #[inline]
+    fn index(&self, k: isize) -> &usize {
+        &self.v[(k + self.max_d as isize) as usize]
+    }
+}
+
+impl std::ops::IndexMut<isize> for V {
+    #[inline]
+    fn index_mut(&mut self, k: isize) -> &mut usize {
+        &mut self.v[(k + self.max_d as isize) as usize]
+    }
+}
+
+/// Buffers pending deletions and insertions so each changed run is recorded
+/// as one removal followed by one addition, like the greedy engine does.
+#[derive(Default)]
+struct Script {
+    out: Vec<(usize, bool, bool)>,
+    equal: usize,
+    removed: usize,
+    added: usize,
+    /// Tokens removed or added so far, pending ones included.
+    edits: usize,
+}
+
+impl Script {
+    fn equal(&mut self, count: usize) {
+        if count == 0 {
+            return;
+        }
+        if self.removed > 0 || self.added > 0 {
+            self.flush();
+        }
+        self.equal += count;
+    }
+
+    fn change(&mut self, removed: usize, added: usize) {
+        if self.equal > 0 {
+            self.flush();
+        }
+        self.removed += removed;
+        self.added += added;
+        self.edits += removed + added;
+    }
+
+    fn flush(&mut self) {
+        if self.equal > 0 {
+            self.out.push((self.equal, false, false));
+        }
+        if self.removed > 0 {
+            self.out.push((self.removed, false, true));
+        }
+        if self.added > 0 {
+            self.out.push((self.added, true, false));
+        }
+        self.equal = 0;
+        self.removed = 0;
+        self.added = 0;
+    }
+}
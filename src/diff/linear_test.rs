@@ -0,0 +1,148 @@
+use crate::diff::linear;
+use crate::{diff_chars, diff_lines, Change, DiffLinesOptions, DiffOptions};
+
+fn edits(changes: &[Change]) -> u32 {
+    changes
+        .iter()
+        .filter(|c| c.added || c.removed)
+        .map(|c| c.count)
+        .sum()
+}
+
+fn rebuild(changes: &[Change]) -> (String, String) {
+    let old = changes
+        .iter()
+        .filter(|c| !c.added)
+        .map(|c| c.value.as_str())
+        .collect();
+    let new = changes
+        .iter()
+        .filter(|c| !c.removed)
+        .map(|c| c.value.as_str())
+        .collect();
+    (old, new)
+}
+
+fn linear_opts() -> DiffOptions {
+    DiffOptions {
+        linear_space_threshold: Some(0),
+        ..Default::default()
+    }
+}
+
+/// Deterministic pseudo-random strings over a small alphabet.
+fn samples() -> Vec<(String, String)> {
+    let mut rng = fastrand::Rng::with_seed(7);
+    (0..200)
+        .map(|_| {
+            let old = (0..rng.usize(..24)).map(|_| rng.char('a'..='d')).collect();
+            let new = (0..rng.usize(..24)).map(|_| rng.char('a'..='d')).collect();
+            (old, new)
+        })
+        .collect()
+}
+
+#[test]
+fn test_linear_script_groups_changes() {
+    let (a, b) = (b"abcd", b"xbyd");
+    let mut ops = Vec::new();
+    let timed_out = linear::diff(
+        a.len(),
+        b.len(),
+        |i, j| a[i] == b[j],
+        None,
+        None,
+        |count, added, removed| ops.push((count, added, removed)),
+    );
+    assert_eq!(timed_out, Some(false));
+    assert_eq!(
+        ops,
+        vec![
+            (1, false, true),
+            (1, true, false),
+            (1, false, false),
+            (1, false, true),
+            (1, true, false),
+            (1, false, false),
+        ]
+    );
+}
+
+#[test]
+fn test_linear_matches_greedy_edit_distance() {
+    for (old, new) in samples() {
+        let greedy = diff_chars(&old, &new, &DiffOptions::default());
+        let linear = diff_chars(&old, &new, &linear_opts());
+        assert_eq!(rebuild(&linear), (old.clone(), new.clone()));
+        assert_eq!(edits(&linear), edits(&greedy), "{old:?} -> {new:?}");
+    }
+}
+
+#[test]
+fn test_linear_identical_on_simple_edits() {
+    let cases = [
+        ("a\nb\nc\n", "a\nx\nc\n"),
+        ("a\nb\n", "a\nb\nc\n"),
+        ("a\nb\nc\n", "c\n"),
+        ("one\ntwo\n", "three\nfour\n"),
+    ];
+    for (old, new) in cases {
+        let opts = DiffLinesOptions {
+            linear_space_threshold: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(
+            diff_lines(old, new, &opts),
+            diff_lines(old, new, &Default::default())
+        );
+    }
+}
+
+#[test]
+fn test_linear_respects_max_edit_length() {
+    let opts = DiffOptions {
+        max_edit_length: Some(2),
+        ..linear_opts()
+    };
+    assert!(diff_chars("abcdef", "uvwxyz", &opts).is_empty());
+    assert!(diff_chars(
+        "abc",
+        "uvw",
+        &DiffOptions {
+            max_edit_length: Some(5),
+            ..linear_opts()
+        }
+    )
+    .is_empty());
+    assert_eq!(edits(&diff_chars("abcdef", "abXdef", &opts)), 2);
+}
+
+#[test]
+fn test_large_input_switches_to_linear_space() {
+    let old: String = (0..60_000).map(|i| format!("line {i}\n")).collect();
+    let new = old.replace("line 30000\n", "changed\n") + "tail\n";
+    let changes = diff_lines(&old, &new, &DiffLinesOptions::default());
+    assert_eq!(edits(&changes), 3);
+    assert_eq!(rebuild(&changes), (old, new));
+}
+
+#[test]
+fn test_linear_max_edit_length_agrees_with_greedy() {
+    for (old, new) in samples().into_iter().take(50) {
+        for limit in 0..8 {
+            let greedy = DiffOptions {
+                max_edit_length: Some(limit),
+                ..Default::default()
+            };
+            let linear = DiffOptions {
+                max_edit_length: Some(limit),
+                ..linear_opts()
+            };
+            assert_eq!(
+                diff_chars(&old, &new, &greedy).is_empty(),
+                diff_chars(&old, &new, &linear).is_empty(),
+                "{old:?} -> {new:?} within {limit}"
+            );
+        }
+    }
+}
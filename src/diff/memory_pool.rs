@@ -1,43 +1,33 @@
-use super::base::{Change, Options, Path, Tokeniser};
-use super::component_pool::ComponentPool;
+use super::base::{Algorithm, Change, Options, Path, Tokeniser};
+use super::engine::{Anchored, Engine, Round, Search, COMPONENT_POOL, PATH_POOL};
+use super::normalize::normalized_key;
+use super::ops::{self, OpUnit};
+use super::pattern::clear_pattern_cache;
+use super::similarity::{lcs_bit_parallel, myers_distance, Similarity, BIT_PARALLEL_MAX};
 use super::token::Token;
+use crate::change::locate_tokens;
 use core::marker::PhantomData;
-use std::cell::RefCell;
-
-thread_local! {
-    static COMPONENT_POOL: RefCell<ComponentPool> = RefCell::new(ComponentPool::with_capacity(1024));
-    static PATH_POOL: RefCell<Vec<Option<Path>>> = RefCell::new(Vec::with_capacity(256));
-}
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::ControlFlow;
 
 pub struct PooledDiff<'a, T: Tokeniser<'a>> {
     tokenizer: T,
     opts: Options,
     use_longest_token: bool,
-    components: ComponentPool,
-    paths: Vec<Option<Path>>,
+    engine: Engine,
+    task: Option<Task<'a>>,
     _lt: PhantomData<&'a ()>,
 }
 
 impl<'a, T: Tokeniser<'a>> PooledDiff<'a, T> {
     pub fn new(tokenizer: T, opts: Options) -> Self {
-        let components = COMPONENT_POOL.with(|pool| {
-            let mut pool = pool.borrow_mut();
-            pool.clear(); // Reset for reuse
-            std::mem::take(&mut *pool)
-        });
-
-        let paths = PATH_POOL.with(|pool| {
-            let mut pool = pool.borrow_mut();
-            pool.clear();
-            std::mem::take(&mut *pool)
-        });
-
         Self {
             tokenizer,
+            engine: Engine::new(opts.clone()),
             opts,
             use_longest_token: false,
-            components,
-            paths,
+            task: None,
             _lt: PhantomData,
         }
     }
@@ -48,7 +38,27 @@ impl<'a, T: Tokeniser<'a>> PooledDiff<'a, T> {
         self
     }
 
+    /// Whether the last `diff` hit `Options::timeout`. Its result is then a
+    /// valid but possibly non-minimal diff.
+    #[inline]
+    pub fn timed_out(&self) -> bool {
+        self.engine.timed_out()
+    }
+
     pub fn diff(&mut self, old_raw: &'a str, new_raw: &'a str) -> Vec<Change> {
+        self.start(old_raw, new_raw);
+        loop {
+            if let Some(changes) = self.resume(usize::MAX) {
+                return changes;
+            }
+        }
+    }
+
+    /// Prepares an incremental diff of `old_raw` against `new_raw` to be run
+    /// with `resume`. This only tokenizes the inputs.
+    pub fn start(&mut self, old_raw: &'a str, new_raw: &'a str) {
+        self.engine.reset_deadline();
+
         let old_cast = self.tokenizer.cast_input(old_raw, &self.opts);
         let new_cast = self.tokenizer.cast_input(new_raw, &self.opts);
 
@@ -56,95 +66,228 @@ impl<'a, T: Tokeniser<'a>> PooledDiff<'a, T> {
         let a_tok = self.tokenizer.tokenize(old_cast, &mut arena_a);
         let b_tok = self.tokenizer.tokenize(new_cast, &mut arena_b);
 
-        let a_vec = self.tokenizer.remove_empty(a_tok);
-        let b_vec = self.tokenizer.remove_empty(b_tok);
+        let (a, b) = (
+            self.tokenizer.remove_empty(a_tok),
+            self.tokenizer.remove_empty(b_tok),
+        );
+        let ids = ((self.tokenizer.interned(&self.opts) || !self.opts.normalizers.is_empty())
+            && self.opts.comparator.is_none())
+        .then(|| self.intern(&a, &b));
+
+        self.task = Some(Task {
+            old_raw,
+            new_raw,
+            a,
+            b,
+            ids,
+            search: None,
+        });
+    }
 
-        let (a, b) = (a_vec.as_slice(), b_vec.as_slice());
-        let (a_len, b_len) = (a.len() as isize, b.len() as isize);
+    /// Runs the diff prepared by `start` for at most `rounds` more rounds and
+    /// returns the changes once it has finished. A round is one edit distance
+    /// of the greedy Myers search, one segment between anchors for patience
+    /// and histogram, or one divide step of the linear-space search.
+    pub fn resume(&mut self, rounds: usize) -> Option<Vec<Change>> {
+        let (task, spans) = self.advance(rounds)?;
+        let Some(spans) = spans else {
+            return Some(Vec::new());
+        };
+        let (a, b) = (task.a.as_slice(), task.b.as_slice());
 
-        if a_len == 0 && b_len == 0 {
-            return Vec::new();
-        }
-        if a_len == 0 {
-            return self.tokenizer.post_process(
+        Some(match (a.is_empty(), b.is_empty()) {
+            (true, true) => Vec::new(),
+            (true, false) => self.finish(
+                &task,
                 vec![Change {
-                    value: new_raw.to_string(),
-                    count: b_len as u32,
+                    value: task.new_raw.to_string(),
+                    count: b.len() as u32,
                     added: true,
                     removed: false,
+                    position: None,
                 }],
-                &self.opts,
-            );
-        }
-        if b_len == 0 {
-            return self.tokenizer.post_process(
+            ),
+            (false, true) => self.finish(
+                &task,
                 vec![Change {
-                    value: old_raw.to_string(),
-                    count: a_len as u32,
+                    value: task.old_raw.to_string(),
+                    count: a.len() as u32,
                     added: false,
                     removed: true,
+                    position: None,
                 }],
-                &self.opts,
-            );
+            ),
+            (false, false) => self.finish_and_postprocess(&task, spans),
+        })
+    }
+
+    /// Diffs `old_raw` against `new_raw` into a flat edit script of
+    /// `(op, old_start, old_len, new_start, new_len)` quintuples, one per run
+    /// of equal, inserted or deleted tokens, with ranges in `unit`. No change
+    /// values are built, so the tokenizer's post-processing (word whitespace
+    /// handling, `cleanup`) does not apply.
+    pub fn diff_ops(&mut self, old_raw: &'a str, new_raw: &'a str, unit: OpUnit) -> Vec<u32> {
+        self.start(old_raw, new_raw);
+        loop {
+            if let Some(ops) = self.resume_ops(usize::MAX, unit) {
+                return ops;
+            }
         }
+    }
 
-        self.run_myers(a_len, b_len, a, b)
+    /// `resume` for `diff_ops`.
+    pub fn resume_ops(&mut self, rounds: usize, unit: OpUnit) -> Option<Vec<u32>> {
+        let (task, spans) = self.advance(rounds)?;
+        Some(spans.map_or_else(Vec::new, |spans| {
+            let old = (task.old_raw, task.a.as_slice());
+            let new = (task.new_raw, task.b.as_slice());
+            ops::encode(&spans, old, new, unit)
+        }))
     }
 
-    #[inline(always)]
-    fn extract_common(
-        &mut self,
-        path: &mut Path,
-        new_toks: &[Token<'a>],
-        old_toks: &[Token<'a>],
-        diagonal: isize,
-    ) -> isize {
-        let (old_len, new_len) = (old_toks.len() as isize, new_toks.len() as isize);
-        let mut old_pos = path.old_pos;
-        let mut new_pos = old_pos - diagonal;
-        let mut common = 0;
-
-        while new_pos + 1 < new_len
-            && old_pos + 1 < old_len
-            && self.tokenizer.equals(
-                &old_toks[(old_pos + 1) as usize],
-                &new_toks[(new_pos + 1) as usize],
-                &self.opts,
-            )
-        {
-            old_pos += 1;
-            new_pos += 1;
-            common += 1;
-
-            if self.opts.one_change_per_token {
-                let idx = self.components.push(1, false, false, path.last);
-                path.last = Some(idx);
+    /// LCS length and edit distance of `old_raw` and `new_raw`, found without
+    /// recording a diff. `None` past `max_edit_length`.
+    pub fn similarity(&mut self, old_raw: &'a str, new_raw: &'a str) -> Option<Similarity> {
+        self.start(old_raw, new_raw);
+        let task = self.task.take()?;
+        let (n, m) = (task.a.len(), task.b.len());
+
+        let distance = if self.opts.comparator.is_none() && n.min(m) <= BIT_PARALLEL_MAX {
+            let interned;
+            let (a_ids, b_ids) = match &task.ids {
+                Some(ids) => ids,
+                None => {
+                    interned = self.intern(&task.a, &task.b);
+                    &interned
+                }
+            };
+            let lcs = if n <= m {
+                lcs_bit_parallel(a_ids, b_ids)
+            } else {
+                lcs_bit_parallel(b_ids, a_ids)
+            };
+            let distance = n + m - 2 * lcs;
+            if self.opts.max_edit_length.is_some_and(|max| distance > max) {
+                return None;
             }
+            distance
+        } else {
+            let eq = |i: usize, j: usize| tokens_equal(&self.tokenizer, &self.opts, &task, i, j);
+            myers_distance(n, m, &eq, self.opts.max_edit_length)?
+        };
+
+        Some(Similarity {
+            old_len: n,
+            new_len: m,
+            lcs: (n + m - distance) / 2,
+        })
+    }
+
+    /// Edit distance reached by the paused Myers search, or tokens found
+    /// changed so far by the other searches, for reporting progress between
+    /// `resume` calls.
+    #[inline]
+    pub fn edit_length(&self) -> usize {
+        self.task
+            .as_ref()
+            .and_then(|t| t.search.as_ref())
+            .map_or(0, Search::edit_length)
+    }
+
+    /// Searches for at most `rounds` rounds. Once done, returns the finished
+    /// task with its spans, or `None` spans if `max_edit_length` was exceeded.
+    fn advance(&mut self, rounds: usize) -> Option<(Task<'a>, Option<Vec<Span>>)> {
+        let Some(mut task) = self.task.take() else {
+            return Some((Task::default(), Some(Vec::new())));
+        };
+
+        let mut search = match task.search.take() {
+            Some(search) => search,
+            None => match self.begin(&mut task) {
+                ControlFlow::Break(spans) => return Some((task, spans)),
+                ControlFlow::Continue(search) => search,
+            },
+        };
+
+        let (a, b) = (task.a.as_slice(), task.b.as_slice());
+        let eq = |i: usize, j: usize| tokens_equal(&self.tokenizer, &self.opts, &task, i, j);
+        let round = match &mut search {
+            Search::Greedy(greedy) => self.engine.search(greedy, a.len(), b.len(), &eq, rounds),
+            Search::Anchored(anchored) => {
+                let (a_ids, b_ids) = task.ids.as_ref().expect("anchored search without IDs");
+                self.engine.anchored(anchored, a_ids, b_ids, &eq, rounds)
+            }
+            Search::Linear(linear) => self.engine.linear(linear, &eq, rounds),
+        };
+        let spans = match round {
+            Round::Found(tail) => Some(self.engine.spans(tail)),
+            Round::Exceeded => None,
+            Round::Paused => {
+                task.search = Some(search);
+                self.task = Some(task);
+                return None;
+            }
+        };
+        Some((task, spans))
+    }
+
+    fn begin(&mut self, task: &mut Task<'a>) -> ControlFlow<Option<Vec<Span>>, Search> {
+        let (n, m) = (task.a.len(), task.b.len());
+
+        if n == 0 && m == 0 {
+            return ControlFlow::Break(Some(Vec::new()));
+        }
+        if n == 0 {
+            return ControlFlow::Break(Some(vec![(m as u32, true, false)]));
+        }
+        if m == 0 {
+            return ControlFlow::Break(Some(vec![(n as u32, false, true)]));
         }
 
-        if common > 0 && !self.opts.one_change_per_token {
-            let idx = self.components.push(common as u32, false, false, path.last);
-            path.last = Some(idx);
+        if self.opts.algorithm != Algorithm::Myers && self.opts.comparator.is_none() {
+            if task.ids.is_none() {
+                task.ids = Some(self.intern(&task.a, &task.b));
+            }
+            return ControlFlow::Continue(Search::Anchored(Anchored::new(n, m)));
         }
 
-        path.old_pos = old_pos;
-        new_pos
+        let eq = |i: usize, j: usize| tokens_equal(&self.tokenizer, &self.opts, task, i, j);
+        if self.engine.uses_linear_space(n, m) {
+            return match self.engine.linear_start(n, m, &eq) {
+                Some(linear) => ControlFlow::Continue(Search::Linear(linear)),
+                None => ControlFlow::Break(None),
+            };
+        }
+        match self.engine.search_start(n, m, &eq) {
+            ControlFlow::Break(Round::Found(tail)) => {
+                ControlFlow::Break(Some(self.engine.spans(tail)))
+            }
+            ControlFlow::Break(_) => ControlFlow::Break(None),
+            ControlFlow::Continue(greedy) => ControlFlow::Continue(Search::Greedy(greedy)),
+        }
     }
 
-    fn finish_and_postprocess(
-        &self,
-        tail: Option<u32>,
-        new_toks: &[Token<'a>],
-        old_toks: &[Token<'a>],
-    ) -> Vec<Change> {
-        let chain = self.components.trace_path(tail);
+    /// Maps tokens to IDs that are equal exactly when the tokens are, so the
+    /// search compares integers instead of re-normalising strings.
+    fn intern(&self, a: &[Token<'a>], b: &[Token<'a>]) -> (Vec<u32>, Vec<u32>) {
+        let mut ids: HashMap<Cow<'a, str>, u32> = HashMap::with_capacity(a.len() + b.len());
+        let mut id = |tok: &Token<'a>| {
+            let next = ids.len() as u32;
+            *ids.entry(normalized_key(&self.tokenizer, tok, &self.opts))
+                .or_insert(next)
+        };
+        (
+            a.iter().map(&mut id).collect(),
+            b.iter().map(&mut id).collect(),
+        )
+    }
 
+    fn finish_and_postprocess(&self, task: &Task<'a>, spans: Vec<Span>) -> Vec<Change> {
+        let (old_toks, new_toks) = (task.a.as_slice(), task.b.as_slice());
         let (mut new_pos, mut old_pos) = (0usize, 0usize);
-        let mut out = Vec::with_capacity(chain.len());
-
-        for &idx in &chain {
-            let (count, added, removed, _) = self.components.get(idx);
+        let mut out = Vec::with_capacity(spans.len());
 
+        for (count, added, removed) in spans {
             let value = if removed {
                 let slice = &old_toks[old_pos..old_pos + count as usize];
                 old_pos += count as usize;
@@ -178,146 +321,57 @@ impl<'a, T: Tokeniser<'a>> PooledDiff<'a, T> {
                 count,
                 added,
                 removed,
+                position: None,
             });
         }
 
-        self.tokenizer.post_process(out, &self.opts)
+        self.finish(task, out)
     }
 
-    #[inline(always)]
-    fn extend_path(&mut self, path: Path, added: bool, removed: bool, old_inc: isize) -> Path {
-        let merged = if !self.opts.one_change_per_token {
-            if let Some(prev_idx) = path.last {
-                let (prev_count, prev_added, prev_removed, prev_previous) =
-                    self.components.get(prev_idx);
-                if prev_added == added && prev_removed == removed {
-                    let idx = self
-                        .components
-                        .push(prev_count + 1, added, removed, prev_previous);
-                    Some(idx)
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        } else {
-            None
-        };
-
-        let last_idx = if let Some(idx) = merged {
-            idx
-        } else {
-            self.components.push(1, added, removed, path.last)
-        };
-
-        Path {
-            old_pos: path.old_pos + old_inc,
-            last: Some(last_idx),
-        }
-    }
-
-    fn run_myers(
-        &mut self,
-        a_len: isize,
-        b_len: isize,
-        a: &[Token<'a>],
-        b: &[Token<'a>],
-    ) -> Vec<Change> {
-        let natural_limit = (a_len + b_len) as usize;
-        let max_d = self
-            .opts
-            .max_edit_length
-            .map_or(natural_limit, |m| m.min(natural_limit));
-
-        let offset = max_d as isize;
-        let size = 2 * max_d + 1;
-
-        self.paths.clear();
-        self.paths.resize(size, None);
-
-        let mut best0 = Path::default();
-
-        let new_pos = self.extract_common(&mut best0, b, a, 0);
-
-        if best0.old_pos + 1 >= a_len && new_pos + 1 >= b_len {
-            return self.finish_and_postprocess(best0.last, b, a);
-        }
-
-        self.paths[offset as usize] = Some(best0);
-
-        let (mut min_k_consider, mut max_k_consider) = (isize::MIN, isize::MAX);
-
-        for d in 1..=max_d {
-            let k_min = min_k_consider.max(-(d as isize));
-            let k_max = max_k_consider.min(d as isize);
-
-            for k in (k_min..=k_max).step_by(2) {
-                let idx = (k + offset) as usize;
-
-                let remove_path = if idx > 0 {
-                    self.paths[idx - 1].take()
-                } else {
-                    None
-                };
-                let add_path = if idx + 1 < size {
-                    self.paths[idx + 1]
-                } else {
-                    None
-                };
-
-                let can_add = add_path
-                    .as_ref()
-                    .is_some_and(|p| (0..b_len).contains(&(p.old_pos - k)));
-                let can_remove = remove_path.as_ref().is_some_and(|p| p.old_pos + 1 < a_len);
-
-                if !can_add && !can_remove {
-                    self.paths[idx] = None;
-                    continue;
-                }
-
-                let base = if !can_remove
-                    || (can_add
-                        && remove_path.as_ref().unwrap().old_pos
-                            < add_path.as_ref().unwrap().old_pos)
-                {
-                    self.extend_path(add_path.unwrap(), true, false, 0)
-                } else {
-                    self.extend_path(remove_path.unwrap(), false, true, 1)
-                };
-
-                self.paths[idx] = Some(base);
-                let mut path = self.paths[idx].unwrap();
-                let new_pos_after = self.extract_common(&mut path, b, a, k);
-                self.paths[idx] = Some(path);
-
-                let p = self.paths[idx].as_ref().unwrap();
-                if p.old_pos + 1 >= a_len && new_pos_after + 1 >= b_len {
-                    return self.finish_and_postprocess(p.last, b, a);
-                }
-
-                if p.old_pos + 1 >= a_len {
-                    max_k_consider = max_k_consider.min(k - 1);
-                }
-                if new_pos_after + 1 >= b_len {
-                    min_k_consider = min_k_consider.max(k + 1);
-                }
-            }
+    fn finish(&self, task: &Task<'a>, changes: Vec<Change>) -> Vec<Change> {
+        let mut changes = self.tokenizer.post_process(changes, &self.opts);
+        if self.opts.positions {
+            locate_tokens(
+                &mut changes,
+                (task.old_raw, &task.a),
+                (task.new_raw, &task.b),
+            );
         }
-
-        Vec::new()
+        changes
     }
 }
 
-impl<'a, T: Tokeniser<'a>> Drop for PooledDiff<'a, T> {
-    fn drop(&mut self) {
-        COMPONENT_POOL.with(|pool| {
-            *pool.borrow_mut() = std::mem::take(&mut self.components);
-        });
+/// Token count, added and removed flags of one run of the diff.
+pub(crate) type Span = (u32, bool, bool);
+
+/// Diff prepared by `PooledDiff::start`.
+#[derive(Default)]
+struct Task<'a> {
+    old_raw: &'a str,
+    new_raw: &'a str,
+    a: Vec<Token<'a>>,
+    b: Vec<Token<'a>>,
+    /// Interned token IDs, when the tokenizer asks for them.
+    ids: Option<(Vec<u32>, Vec<u32>)>,
+    search: Option<Search>,
+}
 
-        PATH_POOL.with(|pool| {
-            *pool.borrow_mut() = std::mem::take(&mut self.paths);
-        });
+/// Equality of `task.a[i]` and `task.b[j]`.
+#[inline(always)]
+fn tokens_equal<'a, T: Tokeniser<'a>>(
+    tokenizer: &T,
+    opts: &Options,
+    task: &Task<'a>,
+    i: usize,
+    j: usize,
+) -> bool {
+    if let Some((a, b)) = &task.ids {
+        return a[i] == b[j];
+    }
+    let (l, r) = (&task.a[i], &task.b[j]);
+    match &opts.comparator {
+        Some(comparator) => comparator.call(l.text, r.text),
+        None => tokenizer.equals(l, r, opts),
     }
 }
 
@@ -354,6 +408,8 @@ pub fn clear_pools() {
     PATH_POOL.with(|pool| {
         pool.borrow_mut().clear();
     });
+
+    clear_pattern_cache();
 }
 
 #[inline(always)]
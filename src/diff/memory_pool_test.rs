@@ -1,6 +1,7 @@
-use super::base::{Diff, Options};
+use super::base::{Algorithm, Diff, Options, WithComparator};
 use super::character::CharTokenizer;
 use super::memory_pool::PooledDiff;
+use crate::Change;
 use std::thread;
 
 #[test]
@@ -54,3 +55,214 @@ fn test_multiple_concurrent_diffs() {
         assert!(!result.is_empty());
     }
 }
+
+fn rebuild(changes: &[crate::Change]) -> (String, String) {
+    let old = changes
+        .iter()
+        .filter(|c| !c.added)
+        .map(|c| c.value.as_str())
+        .collect();
+    let new = changes
+        .iter()
+        .filter(|c| !c.removed)
+        .map(|c| c.value.as_str())
+        .collect();
+    (old, new)
+}
+
+#[test]
+fn test_timeout_returns_best_effort_diff() {
+    let (old, new) = ("the quick brown fox", "a quick red fox jumps");
+
+    for linear_space_threshold in [None, Some(0)] {
+        let opts = Options {
+            timeout: Some(0),
+            linear_space_threshold,
+            ..Default::default()
+        };
+        let mut diff = PooledDiff::new(CharTokenizer, opts);
+        let result = diff.diff(old, new);
+
+        assert!(diff.timed_out());
+        assert!(!result.is_empty());
+        assert_eq!(rebuild(&result), (old.to_string(), new.to_string()));
+    }
+}
+
+#[test]
+fn test_timeout_not_reported_when_search_finishes() {
+    let opts = Options {
+        timeout: Some(0),
+        ..Default::default()
+    };
+    let mut diff = PooledDiff::new(CharTokenizer, opts);
+    assert_eq!(diff.diff("same", "same").len(), 1);
+    assert!(!diff.timed_out());
+
+    let mut diff = PooledDiff::new(
+        CharTokenizer,
+        Options {
+            timeout: Some(60_000),
+            ..Default::default()
+        },
+    );
+    diff.diff("abc", "axc");
+    assert!(!diff.timed_out());
+}
+
+#[test]
+fn test_resume_in_slices_matches_diff() {
+    let (old, new) = ("the quick brown fox jumps", "a quick red fox leaps over");
+    let expected = PooledDiff::new(CharTokenizer, Options::default()).diff(old, new);
+
+    let mut diff = PooledDiff::new(CharTokenizer, Options::default());
+    diff.start(old, new);
+    let mut progress = Vec::new();
+    let result = loop {
+        if let Some(changes) = diff.resume(1) {
+            break changes;
+        }
+        progress.push(diff.edit_length());
+    };
+
+    assert_eq!(result, expected);
+    assert!(progress.len() > 1);
+    assert!(progress.windows(2).all(|w| w[1] == w[0] + 1));
+}
+
+/// Runs `diff` one round per `resume` call, returning the changes and the
+/// progress reported after each paused call.
+fn resume_by_rounds(opts: Options, old: &str, new: &str) -> (Vec<Change>, Vec<usize>) {
+    let mut diff = PooledDiff::new(CharTokenizer, opts);
+    diff.start(old, new);
+    let mut progress = Vec::new();
+    loop {
+        if let Some(changes) = diff.resume(1) {
+            return (changes, progress);
+        }
+        progress.push(diff.edit_length());
+    }
+}
+
+#[test]
+fn test_anchored_search_resumes_between_segments() {
+    let (old, new) = ("abc-def-ghi-jkl", "abX-dYf-ghi-Zkl");
+    for algorithm in [Algorithm::Patience, Algorithm::Histogram] {
+        let opts = Options {
+            algorithm,
+            ..Default::default()
+        };
+        let expected = PooledDiff::new(CharTokenizer, opts.clone()).diff(old, new);
+        let (changes, progress) = resume_by_rounds(opts, old, new);
+        assert_eq!(changes, expected);
+        assert!(progress.len() > 1, "{algorithm:?}");
+        assert!(progress.windows(2).all(|w| w[0] <= w[1]));
+    }
+}
+
+#[test]
+fn test_patience_without_anchors_resumes_within_segment() {
+    // No character is unique, so the whole input is one Myers segment.
+    let (old, new) = ("aabbaabbccaacc", "bbaaccbbaaccbb");
+    let opts = Options {
+        algorithm: Algorithm::Patience,
+        ..Default::default()
+    };
+    let expected = PooledDiff::new(CharTokenizer, opts.clone()).diff(old, new);
+    let (changes, progress) = resume_by_rounds(opts, old, new);
+    assert_eq!(changes, expected);
+    assert!(progress.len() > 4, "{progress:?}");
+    assert!(progress.windows(2).all(|w| w[0] <= w[1]));
+}
+
+#[test]
+fn test_linear_space_search_resumes_between_steps() {
+    let (old, new) = ("the quick brown fox jumps", "a quick red fox leaps over");
+    let opts = Options {
+        linear_space_threshold: Some(10),
+        ..Default::default()
+    };
+    let expected = PooledDiff::new(CharTokenizer, opts.clone()).diff(old, new);
+    let (changes, progress) = resume_by_rounds(opts, old, new);
+    assert_eq!(changes, expected);
+    assert!(progress.len() > 1);
+    assert!(progress.windows(2).all(|w| w[0] <= w[1]));
+    assert!(progress.last() > Some(&0));
+}
+
+#[test]
+fn test_resume_finishes_trivial_inputs_at_once() {
+    let mut diff = PooledDiff::new(CharTokenizer, Options::default());
+    diff.start("", "abc");
+    let changes = diff.resume(1).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert!(changes[0].added);
+    assert_eq!(diff.edit_length(), 0);
+}
+
+#[test]
+fn test_comparator_replaces_token_equality() {
+    let opts = Options::default().with_comparator(|l, r| {
+        l.chars().all(|c| c.is_ascii_digit()) == r.chars().all(|c| c.is_ascii_digit())
+    });
+    let changes = PooledDiff::new(CharTokenizer, opts).diff("a1b", "x2y");
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].value, "x2y");
+    assert!(!changes[0].added && !changes[0].removed);
+
+    let never = Options::default().with_comparator(|_, _| false);
+    let changes = PooledDiff::new(CharTokenizer, never).diff("ab", "ab");
+    assert_eq!(changes.len(), 2);
+    assert!(changes[0].removed && changes[1].added);
+}
+
+#[test]
+fn test_trimming_keeps_reference_output() {
+    let mut rng = fastrand::Rng::with_seed(5);
+    let text = |rng: &mut fastrand::Rng| -> String {
+        let alphabet = rng.usize(2..=5);
+        (0..rng.usize(0..14))
+            .map(|_| ['a', 'b', 'c', 'd', 'e'][rng.usize(0..alphabet)])
+            .collect()
+    };
+
+    for _ in 0..5000 {
+        let old = text(&mut rng);
+        let new = if rng.bool() {
+            let lo = rng.usize(0..=old.len());
+            let hi = rng.usize(lo..=old.len());
+            format!("{}{}{}", &old[..lo], text(&mut rng), &old[hi..])
+        } else {
+            text(&mut rng)
+        };
+        for one_change_per_token in [false, true] {
+            let opts = Options {
+                one_change_per_token,
+                ..Default::default()
+            };
+            let expected = Diff::new(CharTokenizer, opts.clone()).diff(&old, &new);
+            let result = PooledDiff::new(CharTokenizer, opts).diff(&old, &new);
+            assert_eq!(result, expected, "{old:?} -> {new:?}");
+        }
+    }
+}
+
+#[test]
+fn test_pure_insertion_between_common_ends() {
+    let old = "x".repeat(5000) + &"y".repeat(5000);
+    let new = "x".repeat(5000) + "inserted" + &"y".repeat(5000);
+    let changes = PooledDiff::new(CharTokenizer, Options::default()).diff(&old, &new);
+    assert_eq!(changes.len(), 3);
+    assert_eq!(changes[0].count, 5000);
+    assert!(changes[1].added);
+    assert_eq!(changes[1].value, "inserted");
+    assert_eq!(changes[2].count, 5000);
+
+    let limited = Options {
+        max_edit_length: Some(7),
+        ..Default::default()
+    };
+    assert!(PooledDiff::new(CharTokenizer, limited)
+        .diff(&new, &old)
+        .is_empty());
+}
@@ -1,40 +1,86 @@
+pub mod array;
 pub mod base;
+pub mod case;
 pub mod character;
+pub mod cleanup;
+pub mod code;
 pub mod component_pool;
 pub mod components;
 pub mod css;
+mod engine;
+pub mod histogram;
+pub mod indent;
 pub mod json;
 pub mod line;
+pub mod linear;
 pub mod memory_pool;
+pub mod normalize;
+pub mod ops;
+pub mod patience;
+pub mod pattern;
 pub mod sentences;
+pub mod similarity;
 pub mod token;
 pub mod word;
 
+#[cfg(test)]
+mod array_test;
+#[cfg(test)]
+mod case_test;
 #[cfg(test)]
 mod character_test;
 #[cfg(test)]
+mod cleanup_test;
+#[cfg(test)]
+mod code_test;
+#[cfg(test)]
 mod component_pool_test;
 #[cfg(test)]
 mod components_test;
 #[cfg(test)]
 mod css_test;
 #[cfg(test)]
+mod histogram_test;
+#[cfg(test)]
+mod indent_test;
+#[cfg(test)]
 mod json_test;
 #[cfg(test)]
 mod line_test;
 #[cfg(test)]
+mod linear_test;
+#[cfg(test)]
 mod memory_pool_test;
 #[cfg(test)]
+mod normalize_test;
+#[cfg(test)]
+mod ops_test;
+#[cfg(test)]
+mod patience_test;
+#[cfg(test)]
+mod pattern_test;
+#[cfg(test)]
 mod sentences_test;
 #[cfg(test)]
+mod similarity_test;
+#[cfg(test)]
 mod word_test;
 
-pub use character::CharTokenizer;
+pub use array::{diff_arrays, diff_arrays_by, ArrayChange};
+pub use character::{CharTokenizer, Granularity, GraphemeTokenizer};
+pub use cleanup::{cleanup_changes, Cleanup};
+pub use code::{CodeLanguage, CodeTokenizer};
 pub use css::CssTokenizer;
 pub use json::JsonTokenizer;
 pub use line::LineTokenizer;
-pub use sentences::SentenceTokenizer;
-pub use word::{WordTokenizer, WordWithSpaceTokenizer};
+pub use normalize::Normalizer;
+pub use ops::{OpUnit, OP_DELETE, OP_EQUAL, OP_INSERT};
+pub use pattern::PatternTokenizer;
+pub use sentences::{SentenceBoundaries, SentenceTokenizer, UnicodeSentenceTokenizer};
+pub use similarity::{similarity, Similarity};
+pub use word::{
+    CodeWordTokenizer, UnicodeWordTokenizer, WordBoundaries, WordTokenizer, WordWithSpaceTokenizer,
+};
 
 pub use component_pool::ComponentPool;
 pub use components::Component;
@@ -0,0 +1,168 @@
+use regex::Regex;
+use std::{borrow::Cow, str::FromStr};
+use unicode_normalization::{
+    char::is_combining_mark, is_nfc_quick, is_nfkc_quick, IsNormalized, UnicodeNormalization,
+};
+
+use super::{
+    base::{Options, Tokeniser},
+    case::fold_case,
+    pattern::compile,
+    token::Token,
+};
+
+/// Rewrite applied to tokens before they are compared. Only equality is
+/// affected; changes always carry the original text.
+#[derive(Debug, Clone)]
+pub enum Normalizer {
+    /// Unicode canonical composition, so precomposed and combining forms
+    /// of the same character match.
+    Nfc,
+    /// Unicode compatibility composition: also folds ligatures, full-width
+    /// forms, superscripts and the like.
+    Nfkc,
+    /// Every run of whitespace between two other characters becomes a
+    /// single space. Leading and trailing whitespace is kept.
+    CollapseWhitespace,
+    /// Drops accents and other combining marks, e.g. `"café"` as `"cafe"`.
+    StripDiacritics,
+    /// Replaces every match of `pattern` with `placeholder`, e.g. to ignore
+    /// timestamps or generated IDs.
+    Mask { pattern: Regex, placeholder: String },
+}
+
+/// Parses the JS name of a normalizer, e.g. `"collapseWhitespace"`.
+impl FromStr for Normalizer {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        match name {
+            "nfc" => Ok(Normalizer::Nfc),
+            "nfkc" => Ok(Normalizer::Nfkc),
+            "collapseWhitespace" => Ok(Normalizer::CollapseWhitespace),
+            "stripDiacritics" => Ok(Normalizer::StripDiacritics),
+            _ => Err(format!("Unknown normalizer {name:?}")),
+        }
+    }
+}
+
+impl Normalizer {
+    /// Masks matches of `pattern` with `placeholder`. Fails when the pattern
+    /// does not compile.
+    pub fn mask(pattern: &str, placeholder: &str) -> Result<Self, String> {
+        Ok(Normalizer::Mask {
+            pattern: compile(pattern)?,
+            placeholder: placeholder.to_string(),
+        })
+    }
+
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self {
+            Normalizer::Nfc => match is_nfc_quick(text.chars()) {
+                IsNormalized::Yes => Cow::Borrowed(text),
+                _ => Cow::Owned(text.nfc().collect()),
+            },
+            Normalizer::Nfkc => match is_nfkc_quick(text.chars()) {
+                IsNormalized::Yes => Cow::Borrowed(text),
+                _ => Cow::Owned(text.nfkc().collect()),
+            },
+            Normalizer::CollapseWhitespace => collapse_whitespace(text),
+            Normalizer::StripDiacritics => {
+                if text.is_ascii() {
+                    Cow::Borrowed(text)
+                } else {
+                    Cow::Owned(text.nfd().filter(|&c| !is_combining_mark(c)).collect())
+                }
+            }
+            Normalizer::Mask {
+                pattern,
+                placeholder,
+            } => pattern.replace_all(text, placeholder.as_str()),
+        }
+    }
+}
+
+fn collapse_whitespace(text: &str) -> Cow<'_, str> {
+    let start = text.len() - text.trim_start().len();
+    let end = text.trim_end().len().max(start);
+    let inner = &text[start..end];
+    let needs_collapse = inner.char_indices().any(|(i, c)| {
+        c.is_whitespace() && (c != ' ' || inner[i + 1..].starts_with(char::is_whitespace))
+    });
+    if !needs_collapse {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    out.push_str(&text[..start]);
+    let mut in_space = false;
+    for c in inner.chars() {
+        if c.is_whitespace() {
+            in_space = true;
+        } else {
+            if in_space {
+                out.push(' ');
+                in_space = false;
+            }
+            out.push(c);
+        }
+    }
+    out.push_str(&text[end..]);
+    Cow::Owned(out)
+}
+
+/// `text` passed through `normalizers` in order.
+pub fn normalize<'a>(text: Cow<'a, str>, normalizers: &[Normalizer]) -> Cow<'a, str> {
+    normalizers
+        .iter()
+        .fold(text, |text, normalizer| match text {
+            Cow::Borrowed(text) => normalizer.apply(text),
+            Cow::Owned(text) => {
+                let changed = match normalizer.apply(&text) {
+                    Cow::Owned(changed) => Some(changed),
+                    Cow::Borrowed(_) => None,
+                };
+                Cow::Owned(changed.unwrap_or(text))
+            }
+        })
+}
+
+/// The tokenizer's comparison key with `opts.normalizers` applied. They run
+/// before case folding, so a case-sensitive mask still matches under
+/// `ignore_case`.
+#[inline]
+pub(crate) fn normalized_key<'a, T: Tokeniser<'a>>(
+    tokenizer: &T,
+    tok: &Token<'a>,
+    opts: &Options,
+) -> Cow<'a, str> {
+    if opts.normalizers.is_empty() {
+        return tokenizer.comparison_key(tok, opts);
+    }
+    let key = normalize(
+        tokenizer.comparison_key(tok, &Options::default()),
+        &opts.normalizers,
+    );
+    if !opts.ignore_case {
+        return key;
+    }
+    match key {
+        Cow::Borrowed(key) => fold_case(key, opts.case_locale),
+        Cow::Owned(key) => Cow::Owned(fold_case(&key, opts.case_locale).into_owned()),
+    }
+}
+
+/// Token equality under the tokenizer's rules and `opts.normalizers`.
+#[inline]
+pub(crate) fn normalized_equals<'a, T: Tokeniser<'a>>(
+    tokenizer: &T,
+    l: &Token<'a>,
+    r: &Token<'a>,
+    opts: &Options,
+) -> bool {
+    if opts.normalizers.is_empty() {
+        tokenizer.equals(l, r, opts)
+    } else {
+        normalized_key(tokenizer, l, opts) == normalized_key(tokenizer, r, opts)
+    }
+}
@@ -0,0 +1,151 @@
+use super::base::{Algorithm, Options, WithComparator};
+use super::character::Granularity;
+use super::line::LineTokenizer;
+use super::normalize::{normalize, Normalizer};
+use crate::{
+    diff_chars, diff_lines, diff_words, similarity, Change, DiffLinesOptions, DiffOptions,
+    DiffWordsOptions,
+};
+
+fn normalized(text: &str, normalizers: &[Normalizer]) -> String {
+    normalize(text.into(), normalizers).into_owned()
+}
+
+fn unchanged(changes: &[Change]) -> bool {
+    changes.iter().all(|c| !c.added && !c.removed)
+}
+
+#[test]
+fn test_normalizers() {
+    assert_eq!(normalized("cafe\u{301}", &[Normalizer::Nfc]), "caf\u{e9}");
+    assert_eq!(
+        normalized("\u{fb01}le \u{ff11}", &[Normalizer::Nfkc]),
+        "file 1"
+    );
+    assert_eq!(
+        normalized("  a \t b\u{a0}c\n", &[Normalizer::CollapseWhitespace]),
+        "  a b c\n"
+    );
+    assert_eq!(
+        normalized(
+            "Cr\u{e8}me br\u{fb}l\u{e9}e",
+            &[Normalizer::StripDiacritics]
+        ),
+        "Creme brulee"
+    );
+    let mask = Normalizer::mask(r"\d+", "#").unwrap();
+    assert_eq!(normalized("id 42, id 7", &[mask]), "id #, id #");
+    assert!(Normalizer::mask("(", "").is_err());
+
+    let both = [Normalizer::Nfkc, Normalizer::StripDiacritics];
+    assert_eq!(normalized("\u{fb01}anc\u{e9}", &both), "fiance");
+}
+
+#[test]
+fn test_normalizer_names() {
+    let normalizers: Vec<Normalizer> = ["nfc", "collapseWhitespace", "stripDiacritics"]
+        .iter()
+        .map(|name| name.parse().unwrap())
+        .collect();
+    assert_eq!(normalized("a  \u{e9}", &normalizers), "a e");
+    assert!("nfd".parse::<Normalizer>().is_err());
+
+    // Normalizers come from JS through the wasm bindings, not serde.
+    let opts: DiffOptions =
+        serde_json::from_str(r#"{"ignoreCase": true, "normalizers": ["nfd"]}"#).unwrap();
+    assert!(opts.ignore_case);
+    assert!(opts.normalizers.is_empty());
+}
+
+#[test]
+fn test_diff_keeps_original_text() {
+    let lines = DiffLinesOptions {
+        normalizers: vec![
+            Normalizer::CollapseWhitespace,
+            Normalizer::mask(r"\d{2}:\d{2}", "").unwrap(),
+        ],
+        ..Default::default()
+    };
+    let changes = diff_lines("10:00 start  job\nend\n", "11:30 start job\nend\n", &lines);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].value, "11:30 start job\nend\n");
+
+    let patience = DiffLinesOptions {
+        algorithm: Algorithm::Patience,
+        ..lines.clone()
+    };
+    let changes = diff_lines("a\n12:00 x\nb\n", "a\n13:00 x\nc\n", &patience);
+    let summary: Vec<(&str, bool, bool)> = changes
+        .iter()
+        .map(|c| (c.value.as_str(), c.added, c.removed))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("a\n13:00 x\n", false, false),
+            ("b\n", false, true),
+            ("c\n", true, false),
+        ]
+    );
+}
+
+#[test]
+fn test_all_tokenizers() {
+    let words = DiffWordsOptions {
+        normalizers: vec![Normalizer::StripDiacritics],
+        ..Default::default()
+    };
+    assert!(unchanged(&diff_words(
+        "caf\u{e9} au lait",
+        "cafe au lait",
+        &words
+    )));
+
+    let graphemes = DiffOptions {
+        granularity: Granularity::Grapheme,
+        normalizers: vec![Normalizer::Nfc],
+        ..Default::default()
+    };
+    assert!(unchanged(&diff_chars(
+        "cafe\u{301}",
+        "caf\u{e9}",
+        &graphemes
+    )));
+
+    let chars = DiffOptions {
+        normalizers: vec![Normalizer::StripDiacritics],
+        ignore_case: true,
+        ..Default::default()
+    };
+    let changes = diff_chars("Na\u{ef}ve", "naive", &chars);
+    assert!(unchanged(&changes));
+    assert_eq!(changes[0].value, "naive");
+
+    let opts = Options {
+        normalizers: vec![Normalizer::Nfkc],
+        ..Default::default()
+    };
+    let tokenizer = LineTokenizer::new(&DiffLinesOptions::default());
+    let s = similarity(tokenizer, "\u{fb01}\nx\n", "fi\ny\n", &opts).unwrap();
+    assert_eq!(s.lcs, 1);
+
+    let comparator = DiffOptions {
+        normalizers: vec![Normalizer::Nfkc],
+        ..Default::default()
+    }
+    .with_comparator(|l, r| l == r);
+    assert!(!unchanged(&diff_chars("\u{ff21}", "A", &comparator)));
+}
+
+#[test]
+fn test_mask_before_ignore_case() {
+    let lines = DiffLinesOptions {
+        ignore_case: true,
+        normalizers: vec![Normalizer::mask(r"[A-Z]{3}-\d+", "TICKET").unwrap()],
+        ..Default::default()
+    };
+    let changes = diff_lines("Fixes ABC-12 today\n", "fixes XYZ-345 TODAY\n", &lines);
+    assert!(unchanged(&changes));
+    let changes = diff_lines("Fixes abc-12\n", "Fixes xyz-345\n", &lines);
+    assert!(!unchanged(&changes));
+}
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+
+use super::token::Token;
+
+/// Op codes of an edit script, see `PooledDiff::diff_ops`.
+pub const OP_EQUAL: u32 = 0;
+pub const OP_INSERT: u32 = 1;
+pub const OP_DELETE: u32 = 2;
+
+/// Unit of the ranges in an edit script.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OpUnit {
+    Token,
+    Byte,
+    /// UTF-16 code units, as used to index JS strings.
+    #[default]
+    Utf16,
+}
+
+/// Flattens engine spans into `(op, old_start, old_len, new_start, new_len)`
+/// quintuples. Text between tokens belongs to the next range on its side.
+pub(crate) fn encode(
+    spans: &[(u32, bool, bool)],
+    (old, a): (&str, &[Token]),
+    (new, b): (&str, &[Token]),
+    unit: OpUnit,
+) -> Vec<u32> {
+    let (mut old, mut new) = (Side::new(old, a, unit), Side::new(new, b, unit));
+    let mut out = Vec::with_capacity(spans.len() * 5);
+    for &(count, added, removed) in spans {
+        let count = count as usize;
+        let (op, old_count, new_count) = if added {
+            (OP_INSERT, 0, count)
+        } else if removed {
+            (OP_DELETE, count, 0)
+        } else {
+            (OP_EQUAL, count, count)
+        };
+        let (old_start, old_len) = old.take(old_count);
+        let (new_start, new_len) = new.take(new_count);
+        out.extend([op, old_start, old_len, new_start, new_len]);
+    }
+    out
+}
+
+/// Moves the ranges of an edit script computed on `old` and `new` with every
+/// `\r\n` replaced by `\n` back onto the original inputs, so a line's range
+/// covers its `\r` again.
+pub fn restore_cr(ops: &mut [u32], old: &str, new: &str, unit: OpUnit) {
+    if unit == OpUnit::Token {
+        return;
+    }
+    let (old_crs, new_crs) = (stripped_crs(old, unit), stripped_crs(new, unit));
+    for op in ops.chunks_mut(5) {
+        remap(&mut op[1..3], &old_crs);
+        remap(&mut op[3..5], &new_crs);
+    }
+}
+
+/// Offsets, in the stripped text, of the `\n`s that lost their `\r`.
+fn stripped_crs(text: &str, unit: OpUnit) -> Vec<u32> {
+    let mut crs = Vec::new();
+    let mut at = 0;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\r' && chars.peek() == Some(&'\n') {
+            crs.push(at);
+            continue;
+        }
+        at += match unit {
+            OpUnit::Byte => c.len_utf8(),
+            _ => c.len_utf16(),
+        } as u32;
+    }
+    crs
+}
+
+/// Shifts a `[start, len]` range by the `\r`s removed before each end.
+fn remap(range: &mut [u32], crs: &[u32]) {
+    let shift = |at: u32| at + crs.partition_point(|&cr| cr < at) as u32;
+    let (start, end) = (shift(range[0]), shift(range[0] + range[1]));
+    range[0] = start;
+    range[1] = end - start;
+}
+
+/// Read position in one input.
+struct Side<'s, 't> {
+    text: &'s str,
+    toks: &'t [Token<'s>],
+    unit: OpUnit,
+    token: usize,
+    byte: usize,
+    utf16: usize,
+}
+
+impl<'s, 't> Side<'s, 't> {
+    fn new(text: &'s str, toks: &'t [Token<'s>], unit: OpUnit) -> Self {
+        Self {
+            text,
+            toks,
+            unit,
+            token: 0,
+            byte: 0,
+            utf16: 0,
+        }
+    }
+
+    fn at(&self) -> u32 {
+        (match self.unit {
+            OpUnit::Token => self.token,
+            OpUnit::Byte => self.byte,
+            OpUnit::Utf16 => self.utf16,
+        }) as u32
+    }
+
+    /// Consumes `count` tokens and returns the range they cover.
+    fn take(&mut self, count: usize) -> (u32, u32) {
+        let start = self.at();
+        if count > 0 {
+            self.token += count;
+            let last = &self.toks[self.token - 1];
+            // Tokens borrow from the input, so their address gives the offset.
+            let end = last.text.as_ptr() as usize - self.text.as_ptr() as usize + last.text.len();
+            // Word tokens can share whitespace with their neighbours.
+            if end > self.byte {
+                if self.unit == OpUnit::Utf16 {
+                    self.utf16 += self.text[self.byte..end].encode_utf16().count();
+                }
+                self.byte = end;
+            }
+        }
+        (start, self.at() - start)
+    }
+}
@@ -0,0 +1,131 @@
+use super::base::{Options, Tokeniser};
+use super::memory_pool::PooledDiff;
+use super::ops::{restore_cr, OpUnit, OP_DELETE, OP_EQUAL, OP_INSERT};
+use super::{CharTokenizer, LineTokenizer, WordTokenizer};
+use crate::options::DiffLinesOptions;
+
+fn ops<'a, T: Tokeniser<'a>>(tokenizer: T, old: &'a str, new: &'a str, unit: OpUnit) -> Vec<u32> {
+    PooledDiff::new(tokenizer, Options::default()).diff_ops(old, new, unit)
+}
+
+/// Rebuilds both inputs from a byte-unit edit script.
+fn replay(ops: &[u32], old: &str, new: &str) -> (String, String) {
+    let (mut a, mut b) = (String::new(), String::new());
+    for op in ops.chunks(5) {
+        let (os, ol, ns, nl) = (
+            op[1] as usize,
+            op[2] as usize,
+            op[3] as usize,
+            op[4] as usize,
+        );
+        match op[0] {
+            OP_EQUAL => {
+                assert_eq!(ol > 0, nl > 0);
+                a += &old[os..os + ol];
+                b += &new[ns..ns + nl];
+            }
+            OP_DELETE => {
+                assert_eq!(nl, 0);
+                a += &old[os..os + ol];
+            }
+            OP_INSERT => {
+                assert_eq!(ol, 0);
+                b += &new[ns..ns + nl];
+            }
+            _ => unreachable!(),
+        }
+    }
+    (a, b)
+}
+
+#[test]
+fn test_char_ops_in_every_unit() {
+    let (old, new) = ("aé😀b", "aé😀xb");
+    assert_eq!(
+        ops(CharTokenizer, old, new, OpUnit::Token),
+        vec![OP_EQUAL, 0, 3, 0, 3, OP_INSERT, 3, 0, 3, 1, OP_EQUAL, 3, 1, 4, 1]
+    );
+    assert_eq!(
+        ops(CharTokenizer, old, new, OpUnit::Byte),
+        vec![OP_EQUAL, 0, 7, 0, 7, OP_INSERT, 7, 0, 7, 1, OP_EQUAL, 7, 1, 8, 1]
+    );
+    assert_eq!(
+        ops(CharTokenizer, old, new, OpUnit::Utf16),
+        vec![OP_EQUAL, 0, 4, 0, 4, OP_INSERT, 4, 0, 4, 1, OP_EQUAL, 4, 1, 5, 1]
+    );
+}
+
+#[test]
+fn test_ops_match_changes() {
+    let (old, new) = ("one\ntwo\nthree\n", "one\n2\nthree\nfour\n");
+    let tokenizer = LineTokenizer::new(&DiffLinesOptions::default());
+    let changes = PooledDiff::new(tokenizer.clone(), Options::default()).diff(old, new);
+    let ops = ops(tokenizer, old, new, OpUnit::Token);
+
+    assert_eq!(ops.len(), changes.len() * 5);
+    for (op, change) in ops.chunks(5).zip(&changes) {
+        let code = match (change.added, change.removed) {
+            (true, _) => OP_INSERT,
+            (_, true) => OP_DELETE,
+            _ => OP_EQUAL,
+        };
+        assert_eq!(op[0], code);
+        assert_eq!(op[2].max(op[4]), change.count);
+    }
+}
+
+#[test]
+fn test_empty_inputs() {
+    assert!(ops(CharTokenizer, "", "", OpUnit::Byte).is_empty());
+    assert_eq!(
+        ops(CharTokenizer, "", "ab", OpUnit::Byte),
+        vec![OP_INSERT, 0, 0, 0, 2]
+    );
+    assert_eq!(
+        ops(CharTokenizer, "ab", "", OpUnit::Byte),
+        vec![OP_DELETE, 0, 2, 0, 0]
+    );
+}
+
+#[test]
+fn test_word_ranges_tile_inputs() {
+    let (old, new) = ("The quick  brown fox.", "The slow brown  fox jumps.");
+    let ops = ops(WordTokenizer, old, new, OpUnit::Byte);
+    assert_eq!(replay(&ops, old, new), (old.to_string(), new.to_string()));
+}
+
+#[test]
+fn test_random_ranges_tile_inputs() {
+    let text = |k: usize| -> String {
+        (0..k)
+            .map(|_| ["a", "b ", "é", "\n", "😀", "  c"][fastrand::usize(..6)])
+            .collect()
+    };
+    fastrand::seed(15);
+    for _ in 0..500 {
+        let (old, new) = (text(fastrand::usize(..10)), text(fastrand::usize(..10)));
+        for ops in [
+            ops(CharTokenizer, &old, &new, OpUnit::Byte),
+            ops(WordTokenizer, &old, &new, OpUnit::Byte),
+        ] {
+            assert_eq!(replay(&ops, &old, &new), (old.clone(), new.clone()));
+        }
+    }
+}
+
+#[test]
+fn test_restore_cr_ranges() {
+    let (old, new) = ("a\r\nb\r\nc\n", "a\nB\r\nc\r\n");
+    let (old_lf, new_lf) = (old.replace("\r\n", "\n"), new.replace("\r\n", "\n"));
+    let tokenizer = LineTokenizer::new(&DiffLinesOptions::default());
+    let mut script = ops(tokenizer, &old_lf, &new_lf, OpUnit::Byte);
+    restore_cr(&mut script, old, new, OpUnit::Byte);
+    assert_eq!(replay(&script, old, new), (old.into(), new.into()));
+    assert_eq!(
+        script,
+        vec![
+            OP_EQUAL, 0, 3, 0, 2, OP_DELETE, 3, 3, 2, 0, OP_INSERT, 6, 0, 2, 3, OP_EQUAL, 6, 2, 5,
+            3
+        ]
+    );
+}
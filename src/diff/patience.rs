@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Index pairs `(i, j)` of keys that occur exactly once in `a` and exactly once
+/// in `b`, reduced to the longest chain that is increasing on both sides.
+pub fn unique_anchors<K: Hash + Eq>(a: &[K], b: &[K]) -> Vec<(usize, usize)> {
+    // (count in a, index in a, count in b, index in b)
+    let mut seen: HashMap<&K, (u32, usize, u32, usize)> = HashMap::with_capacity(a.len());
+    for (i, k) in a.iter().enumerate() {
+        let e = seen.entry(k).or_insert((0, 0, 0, 0));
+        e.0 += 1;
+        e.1 = i;
+    }
+    for (j, k) in b.iter().enumerate() {
+        if let Some(e) = seen.get_mut(k) {
+            e.2 += 1;
+            e.3 = j;
+        }
+    }
+
+    let mut pairs: Vec<(usize, usize)> = seen
+        .values()
+        .filter(|e| e.0 == 1 && e.2 == 1)
+        .map(|e| (e.1, e.3))
+        .collect();
+    pairs.sort_unstable();
+    longest_increasing(&pairs)
+}
+
+/// Longest subsequence of `pairs` (sorted by `.0`) that is strictly increasing
+/// in `.1`, found by patience sorting.
+pub fn longest_increasing(pairs: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    if pairs.is_empty() {
+        return Vec::new();
+    }
+
+    let mut tails: Vec<usize> = Vec::new();
+    let mut prev: Vec<Option<usize>> = vec![None; pairs.len()];
+
+    for (idx, &(_, j)) in pairs.iter().enumerate() {
+        let pos = tails.partition_point(|&t| pairs[t].1 < j);
+        prev[idx] = if pos > 0 { Some(tails[pos - 1]) } else { None };
+        if pos == tails.len() {
+            tails.push(idx);
+        } else {
+            tails[pos] = idx;
+        }
+    }
+
+    let mut out = Vec::with_capacity(tails.len());
+    let mut cur = tails.last().copied();
+    while let Some(idx) = cur {
+        out.push(pairs[idx]);
+        cur = prev[idx];
+    }
+    out.reverse();
+    out
+}
@@ -0,0 +1,115 @@
+use crate::diff::base::Algorithm;
+use crate::diff::patience::{longest_increasing, unique_anchors};
+use crate::{diff_lines, structured_patch, Change, DiffLinesOptions, StructuredOptions};
+
+fn patience() -> DiffLinesOptions {
+    DiffLinesOptions {
+        algorithm: Algorithm::Patience,
+        ..Default::default()
+    }
+}
+
+fn rebuild(changes: &[Change]) -> (String, String) {
+    let old = changes
+        .iter()
+        .filter(|c| !c.added)
+        .map(|c| c.value.as_str())
+        .collect();
+    let new = changes
+        .iter()
+        .filter(|c| !c.removed)
+        .map(|c| c.value.as_str())
+        .collect();
+    (old, new)
+}
+
+#[test]
+fn test_longest_increasing() {
+    assert!(longest_increasing(&[]).is_empty());
+    assert_eq!(
+        longest_increasing(&[(0, 3), (1, 0), (2, 1), (3, 4), (4, 2)]),
+        vec![(1, 0), (2, 1), (4, 2)]
+    );
+}
+
+#[test]
+fn test_unique_anchors_skip_repeated_keys() {
+    let a = ["x", "a", "x", "b", "c"];
+    let b = ["b", "x", "a", "c"];
+    // "x" repeats in `a`; of the unique pairs a, b and c only two can be kept
+    // in order on both sides.
+    assert_eq!(unique_anchors(&a, &b), vec![(3, 0), (4, 3)]);
+}
+
+#[test]
+fn test_patience_matches_myers_on_simple_edit() {
+    let changes = diff_lines("a\nb\nc\n", "a\nx\nc\n", &patience());
+    assert_eq!(
+        changes,
+        diff_lines("a\nb\nc\n", "a\nx\nc\n", &Default::default())
+    );
+}
+
+#[test]
+fn test_patience_anchors_on_unique_lines() {
+    let old = "}\nfn a() {\n}\n";
+    let new = "}\nfn b() {\n}\nfn a() {\n}\n";
+    let changes = diff_lines(old, new, &patience());
+    let (o, n) = rebuild(&changes);
+    assert_eq!(o, old);
+    assert_eq!(n, new);
+    // The unique `fn a() {` line stays unchanged, so the insertion is one block.
+    assert_eq!(changes.iter().filter(|c| c.added).count(), 1);
+    assert!(changes
+        .iter()
+        .any(|c| !c.added && !c.removed && c.value.contains("fn a() {")));
+}
+
+#[test]
+fn test_patience_round_trips() {
+    let cases = [
+        ("", "a\nb\n"),
+        ("a\nb\n", ""),
+        ("a\nb\nc\nd\n", "d\nc\nb\na\n"),
+        ("x\ny\nx\ny\n", "y\nx\ny\nx\nz\n"),
+        ("a\n{\n}\nb\n{\n}\n", "b\n{\n}\na\n{\n}\nc\n"),
+        ("same\n", "same\n"),
+    ];
+    for (old, new) in cases {
+        let changes = diff_lines(old, new, &patience());
+        assert_eq!(
+            rebuild(&changes),
+            (old.to_string(), new.to_string()),
+            "{old:?} -> {new:?}"
+        );
+    }
+}
+
+#[test]
+fn test_patience_respects_line_options() {
+    let opts = DiffLinesOptions {
+        ignore_whitespace: true,
+        one_change_per_token: true,
+        ..patience()
+    };
+    let changes = diff_lines("a\n b\nc\n", "a\nb \nx\ny\n", &opts);
+    assert_eq!(changes.iter().filter(|c| c.added).count(), 2);
+    assert!(changes.iter().all(|c| c.count == 1));
+
+    let limited = DiffLinesOptions {
+        max_edit_length: Some(1),
+        ..patience()
+    };
+    assert!(diff_lines("a\nb\nc\n", "x\ny\nz\n", &limited).is_empty());
+}
+
+#[test]
+fn test_structured_patch_with_patience() {
+    let opts = StructuredOptions {
+        context: Some(1),
+        line_options: patience(),
+    };
+    let patch = structured_patch("a", "b", "1\n2\n3\n", "1\n4\n3\n", None, None, &opts).unwrap();
+    assert_eq!(patch.hunks.len(), 1);
+    assert_eq!(patch.hunks[0].lines, vec![" 1", "-2", "+4", " 3"]);
+}
@@ -0,0 +1,126 @@
+use regex::Regex;
+use std::{borrow::Cow, cell::RefCell, collections::HashMap};
+
+use super::{
+    base::{Change, Options, Tokeniser},
+    case::{eq_ignore_case, fold_case},
+    memory_pool::PooledDiff,
+    token::Token,
+};
+use crate::options::DiffPatternOptions;
+
+/// Compiled patterns kept per thread. Past this many the cache starts over,
+/// so callers building patterns on the fly cannot grow it without bound.
+const PATTERN_CACHE_SIZE: usize = 64;
+
+thread_local! {
+    static PATTERN_CACHE: RefCell<HashMap<String, Regex>> = RefCell::new(HashMap::new());
+}
+
+/// `pattern` compiled, or taken from the cache of earlier calls.
+pub fn compile(pattern: &str) -> Result<Regex, String> {
+    PATTERN_CACHE.with(|cache| {
+        if let Some(re) = cache.borrow().get(pattern) {
+            return Ok(re.clone());
+        }
+        let re = Regex::new(pattern).map_err(|e| format!("Invalid pattern {pattern:?}: {e}"))?;
+        let mut cache = cache.borrow_mut();
+        if cache.len() >= PATTERN_CACHE_SIZE {
+            cache.clear();
+        }
+        cache.insert(pattern.to_string(), re.clone());
+        Ok(re)
+    })
+}
+
+pub(crate) fn clear_pattern_cache() {
+    PATTERN_CACHE.with(|cache| cache.borrow_mut().clear());
+}
+
+/// Tokenizer built from a regex: every non-empty match is a token, and so is
+/// the text between matches. Text matching `ignore` is left out when tokens
+/// are compared.
+#[derive(Debug, Clone)]
+pub struct PatternTokenizer {
+    token: Regex,
+    ignore: Option<Regex>,
+}
+
+impl PatternTokenizer {
+    pub fn new(token_pattern: &str, ignore_pattern: Option<&str>) -> Result<Self, String> {
+        Ok(PatternTokenizer {
+            token: compile(token_pattern)?,
+            ignore: ignore_pattern.map(compile).transpose()?,
+        })
+    }
+
+    #[inline]
+    fn comparable<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match &self.ignore {
+            Some(ignore) => ignore.replace_all(text, ""),
+            None => Cow::Borrowed(text),
+        }
+    }
+}
+
+impl<'a> Tokeniser<'a> for PatternTokenizer {
+    fn tokenize<'b>(&self, text: &'a str, arena: &'b mut Vec<Token<'a>>) -> &'b [Token<'a>] {
+        arena.clear();
+        let mut last = 0;
+        for m in self.token.find_iter(text).filter(|m| !m.is_empty()) {
+            if last < m.start() {
+                arena.push(Token {
+                    text: &text[last..m.start()],
+                });
+            }
+            arena.push(Token { text: m.as_str() });
+            last = m.end();
+        }
+        if last < text.len() {
+            arena.push(Token {
+                text: &text[last..],
+            });
+        }
+        arena
+    }
+
+    fn join(&self, toks: &[Token<'a>]) -> String {
+        toks.iter().map(|t| t.text).collect()
+    }
+
+    fn equals(&self, l: &Token<'a>, r: &Token<'a>, opts: &Options) -> bool {
+        let (left, right) = (self.comparable(l.text), self.comparable(r.text));
+        if opts.ignore_case {
+            eq_ignore_case(&left, &right, opts.case_locale)
+        } else {
+            left == right
+        }
+    }
+
+    fn comparison_key(&self, tok: &Token<'a>, opts: &Options) -> Cow<'a, str> {
+        let text = self.comparable(tok.text);
+        if !opts.ignore_case {
+            return text;
+        }
+        match text {
+            Cow::Borrowed(text) => fold_case(text, opts.case_locale),
+            Cow::Owned(text) => Cow::Owned(fold_case(&text, opts.case_locale).into_owned()),
+        }
+    }
+
+    #[inline(always)]
+    fn interned(&self, opts: &Options) -> bool {
+        self.ignore.is_some() || opts.ignore_case
+    }
+}
+
+/// Diffs `old_str` and `new_str` split by `opts.token_pattern`. Fails when a
+/// pattern does not compile.
+pub fn diff_with_pattern(
+    old_str: &str,
+    new_str: &str,
+    opts: &DiffPatternOptions,
+) -> Result<Vec<Change>, String> {
+    let tokenizer = PatternTokenizer::new(&opts.token_pattern, opts.ignore_pattern.as_deref())?;
+    Ok(PooledDiff::new(tokenizer, opts.into()).diff(old_str, new_str))
+}
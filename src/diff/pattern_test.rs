@@ -0,0 +1,77 @@
+use super::base::Tokeniser;
+use super::pattern::{compile, PatternTokenizer};
+use crate::{diff_with_pattern, DiffPatternOptions};
+
+#[test]
+fn test_tokens_cover_text() {
+    let tokenizer = PatternTokenizer::new(r"\w+", None).unwrap();
+    let mut arena = Vec::new();
+    let text = "GET /api/v1 -> 200 (3ms)";
+    let toks = tokenizer.tokenize(text, &mut arena);
+    let texts: Vec<&str> = toks.iter().map(|t| t.text).collect();
+    assert_eq!(
+        texts,
+        vec!["GET", " /", "api", "/", "v1", " -> ", "200", " (", "3ms", ")"]
+    );
+    assert_eq!(tokenizer.join(toks), text);
+
+    let lines = PatternTokenizer::new(r"(?m)^.*$", None).unwrap();
+    let toks = lines.tokenize("a\n\nb", &mut arena);
+    let texts: Vec<&str> = toks.iter().map(|t| t.text).collect();
+    assert_eq!(texts, vec!["a", "\n\n", "b"]);
+}
+
+#[test]
+fn test_invalid_pattern() {
+    let err = PatternTokenizer::new("(", None).unwrap_err();
+    assert!(err.starts_with("Invalid pattern \"(\""), "{err}");
+    let opts = DiffPatternOptions {
+        token_pattern: r"\w+".into(),
+        ignore_pattern: Some("[".into()),
+        ..Default::default()
+    };
+    assert!(diff_with_pattern("a", "b", &opts).is_err());
+}
+
+#[test]
+fn test_compiled_once() {
+    let a = compile(r"\d+ms").unwrap();
+    let b = compile(r"\d+ms").unwrap();
+    assert!(std::ptr::eq(a.as_str(), b.as_str()));
+}
+
+#[test]
+fn test_diff_with_ignore_pattern() {
+    let old = "10:01:02 start job\n10:01:05 step one\n10:01:09 done\n";
+    let new = "11:30:00 start job\n11:30:04 step two\n11:30:07 done\n";
+    let opts = DiffPatternOptions {
+        token_pattern: r"[^\n]*\n".into(),
+        ignore_pattern: Some(r"^\d\d:\d\d:\d\d ".into()),
+        ..Default::default()
+    };
+    let changes = diff_with_pattern(old, new, &opts).unwrap();
+    let summary: Vec<(&str, bool, bool)> = changes
+        .iter()
+        .map(|c| (c.value.as_str(), c.added, c.removed))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("11:30:00 start job\n", false, false),
+            ("10:01:05 step one\n", false, true),
+            ("11:30:04 step two\n", true, false),
+            ("11:30:07 done\n", false, false),
+        ]
+    );
+
+    let opts = DiffPatternOptions {
+        ignore_case: true,
+        ..opts
+    };
+    assert_eq!(
+        diff_with_pattern("01:00:00 A\n", "02:00:00 a\n", &opts)
+            .unwrap()
+            .len(),
+        1
+    );
+}
@@ -1,11 +1,30 @@
-use serde::Deserialize;
-use serde_wasm_bindgen as swb;
-use wasm_bindgen::{prelude::*, JsValue};
-
 use super::{
-    base::{Options, Tokeniser},
+    base::{Change, Tokeniser},
+    memory_pool::PooledDiff,
     token::Token,
 };
+use crate::options::DiffSentencesOptions;
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// How `diff_sentences` finds sentence boundaries.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SentenceBoundaries {
+    /// jsdiff's rules: after `.`, `!` or `?` followed by whitespace.
+    #[default]
+    Jsdiff,
+    /// Unicode sentence boundaries (UAX #29), which leave decimals and
+    /// lowercase continuations alone and break after CJK terminators.
+    Unicode,
+}
+
+/// Abbreviations, lowercase and without their final period, that
+/// `UnicodeSentenceTokenizer` never ends a sentence on.
+pub const ABBREVIATIONS: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "mt", "vs", "e.g", "i.e", "cf", "al",
+    "approx", "dept", "fig", "vol", "pp",
+];
 
 #[derive(Default)]
 pub struct SentenceTokenizer;
@@ -86,50 +105,101 @@ impl<'a> Tokeniser<'a> for SentenceTokenizer {
     }
 }
 
-#[derive(Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-struct SentenceOptions {
-    #[serde(default)]
-    ignore_case: bool,
-    #[serde(default)]
-    one_change_per_token: bool,
-    #[serde(default)]
-    max_edit_length: Option<usize>,
+/// Sentence tokenizer following UAX #29, except that a sentence never ends
+/// on a known abbreviation and an ellipsis (`…`) followed by whitespace and
+/// an uppercase letter does end one. Whitespace after a sentence is a token
+/// of its own, as with `SentenceTokenizer`.
+#[derive(Default, Debug, Clone)]
+pub struct UnicodeSentenceTokenizer {
+    abbreviations: Vec<String>,
 }
 
-impl From<SentenceOptions> for Options {
-    fn from(o: SentenceOptions) -> Self {
-        Options {
-            ignore_case: o.ignore_case,
-            one_change_per_token: o.one_change_per_token,
-            max_edit_length: o.max_edit_length,
+impl UnicodeSentenceTokenizer {
+    /// Tokenizer that also knows the `abbreviations` of `opts`, with or
+    /// without their final period.
+    pub fn new(opts: &DiffSentencesOptions) -> Self {
+        let abbreviations = opts
+            .abbreviations
+            .iter()
+            .map(|a| a.strip_suffix('.').unwrap_or(a).to_lowercase())
+            .collect();
+        UnicodeSentenceTokenizer { abbreviations }
+    }
+
+    fn ends_with_abbreviation(&self, sentence: &str) -> bool {
+        let Some(body) = sentence.strip_suffix('.') else {
+            return false;
+        };
+        let word = body
+            .rsplit(|c: char| c.is_whitespace() || matches!(c, '(' | '[' | '"' | '\'' | '“' | '‘'))
+            .next()
+            .unwrap_or_default();
+        if word.is_empty() {
+            return false;
         }
+        let word = word.to_lowercase();
+        ABBREVIATIONS.contains(&word.as_str()) || self.abbreviations.contains(&word)
     }
 }
 
-#[wasm_bindgen(js_name = diffSentences)]
-pub fn diff_sentences(old_str: &str, new_str: &str, opts: JsValue) -> Result<JsValue, JsValue> {
-    let s_opts: SentenceOptions = swb::from_value(opts).unwrap_or_default();
-    let options: Options = s_opts.into();
+/// Pushes `text[start..end]` as sentences, split after ellipses that are
+/// followed by whitespace and an uppercase letter.
+fn push_sentences<'a>(text: &'a str, start: usize, end: usize, arena: &mut Vec<Token<'a>>) {
+    let mut from = start;
+    for (idx, ellipsis) in text[start..end].match_indices('…') {
+        let after = start + idx + ellipsis.len();
+        let rest = &text[after..end];
+        let ws = rest.len() - rest.trim_start().len();
+        if ws > 0 && rest[ws..].chars().next().is_some_and(char::is_uppercase) {
+            arena.push(Token {
+                text: &text[from..after],
+            });
+            arena.push(Token {
+                text: &text[after..after + ws],
+            });
+            from = after + ws;
+        }
+    }
+    if from < end {
+        arena.push(Token {
+            text: &text[from..end],
+        });
+    }
+}
 
-    use super::memory_pool::PooledDiff;
-    let mut diff = PooledDiff::new(SentenceTokenizer, options);
-    let changes = diff.diff(old_str, new_str);
+impl<'a> Tokeniser<'a> for UnicodeSentenceTokenizer {
+    fn tokenize<'b>(&self, text: &'a str, arena: &'b mut Vec<Token<'a>>) -> &'b [Token<'a>] {
+        arena.clear();
+        let mut start = 0;
+        for (idx, segment) in text.split_sentence_bound_indices() {
+            let end = idx + segment.len();
+            let body_end = idx + segment.trim_end().len();
+            if end < text.len() && self.ends_with_abbreviation(&text[start..body_end]) {
+                continue;
+            }
+            push_sentences(text, start, body_end, arena);
+            if body_end < end {
+                arena.push(Token {
+                    text: &text[body_end..end],
+                });
+            }
+            start = end;
+        }
+        arena
+    }
 
-    swb::to_value(&changes).map_err(|e| JsValue::from(e.to_string()))
+    fn join(&self, toks: &[Token<'a>]) -> String {
+        toks.iter().map(|t| t.text).collect()
+    }
 }
 
-#[wasm_bindgen(js_name = sentenceDiff)]
-pub struct SentenceDiff;
-
-#[wasm_bindgen(js_class = sentenceDiff)]
-impl SentenceDiff {
-    #[wasm_bindgen(js_name = tokenize)]
-    pub fn tokenize_js(text: &str) -> Result<JsValue, JsValue> {
-        let tok = SentenceTokenizer;
-        let mut arena = Vec::new();
-        let toks = tok.tokenize(text, &mut arena);
-        let vec: Vec<&str> = toks.iter().map(|t| t.text).collect();
-        swb::to_value(&vec).map_err(|e| JsValue::from(e.to_string()))
+pub fn diff_sentences(old_str: &str, new_str: &str, opts: &DiffSentencesOptions) -> Vec<Change> {
+    match opts.sentence_boundaries {
+        SentenceBoundaries::Jsdiff => {
+            PooledDiff::new(SentenceTokenizer, opts.into()).diff(old_str, new_str)
+        }
+        SentenceBoundaries::Unicode => {
+            PooledDiff::new(UnicodeSentenceTokenizer::new(opts), opts.into()).diff(old_str, new_str)
+        }
     }
 }
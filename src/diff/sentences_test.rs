@@ -1,5 +1,5 @@
 use crate::diff::base::{Options, Tokeniser};
-use crate::diff::sentences::SentenceTokenizer;
+use crate::diff::sentences::{SentenceBoundaries, SentenceTokenizer, UnicodeSentenceTokenizer};
 use crate::diff::token::Token;
 
 struct TestCase<'a> {
@@ -215,6 +215,7 @@ fn test_sentence_tokenizer_equals_with_options() {
                 ignore_case: false,
                 one_change_per_token: false,
                 max_edit_length: None,
+                ..Default::default()
             },
             expected_result: true,
         },
@@ -226,6 +227,7 @@ fn test_sentence_tokenizer_equals_with_options() {
                 ignore_case: false,
                 one_change_per_token: false,
                 max_edit_length: None,
+                ..Default::default()
             },
             expected_result: false,
         },
@@ -237,6 +239,7 @@ fn test_sentence_tokenizer_equals_with_options() {
                 ignore_case: true,
                 one_change_per_token: false,
                 max_edit_length: None,
+                ..Default::default()
             },
             expected_result: true,
         },
@@ -248,6 +251,7 @@ fn test_sentence_tokenizer_equals_with_options() {
                 ignore_case: false,
                 one_change_per_token: false,
                 max_edit_length: None,
+                ..Default::default()
             },
             expected_result: false,
         },
@@ -259,6 +263,7 @@ fn test_sentence_tokenizer_equals_with_options() {
                 ignore_case: false,
                 one_change_per_token: false,
                 max_edit_length: None,
+                ..Default::default()
             },
             expected_result: false,
         },
@@ -323,3 +328,111 @@ fn test_sentence_tokenizer_join_edge_cases() {
         "First sentence.Second sentence."
     );
 }
+
+#[test]
+fn test_diff_sentences_native_api() {
+    use crate::{diff_sentences, DiffSentencesOptions};
+
+    let changes = diff_sentences(
+        "Hello world. How are you?",
+        "Hello world. Who are you?",
+        &DiffSentencesOptions::default(),
+    );
+    assert_eq!(changes.len(), 3);
+    assert_eq!(changes[0].value, "Hello world. ");
+    assert!(changes[1].removed);
+    assert_eq!(changes[1].value, "How are you?");
+    assert!(changes[2].added);
+    assert_eq!(changes[2].value, "Who are you?");
+}
+
+#[test]
+fn test_unicode_sentence_tokenizer() {
+    let cases: [(&str, Vec<&str>); 7] = [
+        (
+            "See e.g. this one. Dr. Smith paid 3.14 dollars.",
+            vec!["See e.g. this one.", " ", "Dr. Smith paid 3.14 dollars."],
+        ),
+        (
+            "It works, i.e. It does. Done!",
+            vec!["It works, i.e. It does.", " ", "Done!"],
+        ),
+        (
+            "你好。今天很好！真的吗？",
+            vec!["你好。", "今天很好！", "真的吗？"],
+        ),
+        (
+            "Wait... What now? Wait… Fine… ok.",
+            vec!["Wait...", " ", "What now?", " ", "Wait…", " ", "Fine… ok."],
+        ),
+        (
+            "He said \"This is great.\" Then he left.  ",
+            vec!["He said \"This is great.\"", " ", "Then he left.", "  "],
+        ),
+        ("  ", vec!["  "]),
+        ("", vec![]),
+    ];
+    let tokenizer = UnicodeSentenceTokenizer::default();
+    let mut arena = Vec::new();
+    for (input, expected) in cases {
+        let tokens = tokenizer.tokenize(input, &mut arena);
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text).collect();
+        assert_eq!(texts, expected, "{input:?}");
+        assert_eq!(tokenizer.join(tokens), input);
+    }
+}
+
+#[test]
+fn test_unicode_sentence_abbreviations_from_options() {
+    use crate::DiffSentencesOptions;
+
+    let text = "Costs rose ca. Ten percent. Fine.";
+    let mut arena = Vec::new();
+    let plain = UnicodeSentenceTokenizer::default();
+    assert_eq!(plain.tokenize(text, &mut arena).len(), 5);
+
+    let opts = DiffSentencesOptions {
+        abbreviations: vec!["CA.".into(), "approx".into()],
+        ..Default::default()
+    };
+    let extended = UnicodeSentenceTokenizer::new(&opts);
+    let texts: Vec<&str> = extended
+        .tokenize(text, &mut arena)
+        .iter()
+        .map(|t| t.text)
+        .collect();
+    assert_eq!(texts, vec!["Costs rose ca. Ten percent.", " ", "Fine."]);
+}
+
+#[test]
+fn test_diff_sentences_common_options() {
+    use crate::{diff_sentences, DiffSentencesOptions};
+
+    let unicode = DiffSentencesOptions {
+        sentence_boundaries: SentenceBoundaries::Unicode,
+        ..Default::default()
+    };
+    let changes = diff_sentences(
+        "Dr. Smith came. He left.",
+        "Dr. Jones came. He left.",
+        &unicode,
+    );
+    assert_eq!(changes.len(), 3);
+    assert!(changes[0].removed && changes[1].added);
+    assert_eq!(changes[1].value, "Dr. Jones came.");
+
+    let ignore_case = DiffSentencesOptions {
+        ignore_case: true,
+        ..unicode.clone()
+    };
+    let changes = diff_sentences("Hello there. Bye.", "HELLO THERE. BYE.", &ignore_case);
+    assert_eq!(changes.len(), 1);
+    assert!(!changes[0].added && !changes[0].removed);
+
+    let limited = DiffSentencesOptions {
+        max_edit_length: Some(2),
+        ..Default::default()
+    };
+    assert!(diff_sentences("A. B. C.", "A. X. Y.", &limited).is_empty());
+    assert_eq!(diff_sentences("A. B. C.", "A. B. C. D.", &limited).len(), 2);
+}
@@ -0,0 +1,111 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::base::{Options, Tokeniser};
+use super::memory_pool::PooledDiff;
+
+/// Shorter inputs, in tokens, get their LCS from the bit-parallel algorithm.
+pub(crate) const BIT_PARALLEL_MAX: usize = 64;
+
+/// How much two token sequences have in common.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Similarity {
+    pub old_len: usize,
+    pub new_len: usize,
+    /// Length of the longest common subsequence.
+    pub lcs: usize,
+}
+
+impl Similarity {
+    /// Insertions plus deletions, the edit distance Myers minimises.
+    #[inline]
+    pub fn edit_distance(&self) -> usize {
+        self.old_len + self.new_len - 2 * self.lcs
+    }
+
+    /// `2 * lcs / (old_len + new_len)` like Python's
+    /// `difflib.SequenceMatcher.ratio`, and 1 for two empty inputs.
+    pub fn ratio(&self) -> f64 {
+        match self.old_len + self.new_len {
+            0 => 1.0,
+            total => 2.0 * self.lcs as f64 / total as f64,
+        }
+    }
+}
+
+/// Compares `old` and `new` as tokenized by `tokenizer` without building a
+/// diff. `None` when the edit distance exceeds `opts.max_edit_length`.
+pub fn similarity<'a, T: Tokeniser<'a>>(
+    tokenizer: T,
+    old: &'a str,
+    new: &'a str,
+    opts: &Options,
+) -> Option<Similarity> {
+    PooledDiff::new(tokenizer, opts.clone()).similarity(old, new)
+}
+
+/// Edit distance of the greedy Myers search, keeping only the furthest
+/// reaching point of each diagonal. `None` past `max_d`.
+pub(crate) fn myers_distance<E>(n: usize, m: usize, eq: &E, max_d: Option<usize>) -> Option<usize>
+where
+    E: Fn(usize, usize) -> bool,
+{
+    let mut prefix = 0;
+    while prefix < n && prefix < m && eq(prefix, prefix) {
+        prefix += 1;
+    }
+    let mut suffix = 0;
+    while suffix < n - prefix && suffix < m - prefix && eq(n - 1 - suffix, m - 1 - suffix) {
+        suffix += 1;
+    }
+    let (n, m) = (n - prefix - suffix, m - prefix - suffix);
+    let limit = max_d.map_or(n + m, |max| max.min(n + m));
+    if n == 0 || m == 0 {
+        return (n + m <= limit).then_some(n + m);
+    }
+
+    let (a_len, b_len) = (n as isize, m as isize);
+    let offset = limit as isize + 1;
+    let mut v = vec![0isize; 2 * limit + 3];
+    for d in 0..=limit as isize {
+        for k in (-d..=d).step_by(2) {
+            let idx = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+                v[idx + 1]
+            } else {
+                v[idx - 1] + 1
+            };
+            let mut y = x - k;
+            while x < a_len && y < b_len && eq(prefix + x as usize, prefix + y as usize) {
+                x += 1;
+                y += 1;
+            }
+            v[idx] = x;
+            if x >= a_len && y >= b_len {
+                return Some(d as usize);
+            }
+        }
+    }
+    None
+}
+
+/// LCS length by Hyyrö's bit-vector algorithm, one word operation per token
+/// of `b`. `a` holds at most `BIT_PARALLEL_MAX` tokens.
+pub(crate) fn lcs_bit_parallel(a: &[u32], b: &[u32]) -> usize {
+    debug_assert!(a.len() <= BIT_PARALLEL_MAX);
+    let mut masks: HashMap<u32, u64> = HashMap::with_capacity(a.len());
+    for (i, &id) in a.iter().enumerate() {
+        *masks.entry(id).or_default() |= 1 << i;
+    }
+    let bits = u64::MAX >> (64 - a.len().max(1));
+
+    // Each zero bit of `v` adds one to the LCS of `a` and the tokens of `b`
+    // seen so far.
+    let mut v = u64::MAX;
+    for id in b {
+        let u = v & masks.get(id).copied().unwrap_or(0);
+        v = v.wrapping_add(u) | (v - u);
+    }
+    (!v & bits).count_ones() as usize
+}
@@ -0,0 +1,93 @@
+use super::base::{Comparator, Options};
+use super::similarity::{lcs_bit_parallel, myers_distance, similarity, Similarity};
+use super::{CharTokenizer, LineTokenizer, PooledDiff, WordTokenizer};
+use crate::options::DiffLinesOptions;
+
+fn chars(old: &str, new: &str, opts: &Options) -> Option<Similarity> {
+    similarity(CharTokenizer, old, new, opts)
+}
+
+#[test]
+fn test_difflib_ratio() {
+    let s = chars("abcd", "bcde", &Options::default()).unwrap();
+    assert_eq!((s.lcs, s.edit_distance()), (3, 2));
+    assert_eq!(s.ratio(), 0.75);
+
+    let empty = chars("", "", &Options::default()).unwrap();
+    assert_eq!((empty.edit_distance(), empty.ratio()), (0, 1.0));
+    assert_eq!(chars("abc", "", &Options::default()).unwrap().ratio(), 0.0);
+}
+
+#[test]
+fn test_tokenizers_and_options() {
+    let lines = LineTokenizer::new(&DiffLinesOptions::default());
+    let s = similarity(lines, "a\nb\nc\n", "a\nx\nc\n", &Options::default()).unwrap();
+    assert_eq!((s.old_len, s.lcs, s.edit_distance()), (3, 2, 2));
+
+    let s = similarity(
+        WordTokenizer,
+        "The cat sat",
+        "the cat sat",
+        &Options::default(),
+    )
+    .unwrap();
+    assert_eq!(s.edit_distance(), 2);
+    let opts = Options {
+        ignore_case: true,
+        ..Default::default()
+    };
+    let s = similarity(WordTokenizer, "The cat sat", "the cat sat", &opts).unwrap();
+    assert_eq!(s.edit_distance(), 0);
+
+    let opts = Options {
+        comparator: Some(Comparator::new(|l, r| l.eq_ignore_ascii_case(r))),
+        ..Default::default()
+    };
+    assert_eq!(chars("ABC", "abd", &opts).unwrap().lcs, 2);
+}
+
+#[test]
+fn test_max_edit_length() {
+    let opts = Options {
+        max_edit_length: Some(2),
+        ..Default::default()
+    };
+    assert!(chars("abcd", "bcde", &opts).is_some());
+    assert!(chars("abcd", "wxyz", &opts).is_none());
+
+    let long = "x".repeat(100);
+    assert!(chars(&long, &format!("{long}ab"), &opts).is_some());
+    assert!(chars(&long, &format!("a{long}bc"), &opts).is_none());
+}
+
+#[test]
+fn test_matches_diff_counts() {
+    let text = |k: usize| -> String { (0..k).map(|_| fastrand::char('a'..='d')).collect() };
+    fastrand::seed(16);
+    for _ in 0..1000 {
+        let (old, new) = (text(fastrand::usize(..90)), text(fastrand::usize(..90)));
+        let changes = PooledDiff::new(CharTokenizer, Options::default()).diff(&old, &new);
+        let edits: u32 = changes
+            .iter()
+            .filter(|c| c.added || c.removed)
+            .map(|c| c.count)
+            .sum();
+        let s = chars(&old, &new, &Options::default()).unwrap();
+        assert_eq!(s.edit_distance(), edits as usize, "{old:?} -> {new:?}");
+    }
+}
+
+#[test]
+fn test_bit_parallel_agrees_with_myers() {
+    fastrand::seed(64);
+    for _ in 0..2000 {
+        let a: Vec<u32> = (0..fastrand::usize(..=64))
+            .map(|_| fastrand::u32(..4))
+            .collect();
+        let b: Vec<u32> = (0..fastrand::usize(..100))
+            .map(|_| fastrand::u32(..4))
+            .collect();
+        let d = myers_distance(a.len(), b.len(), &|i, j| a[i] == b[j], None).unwrap();
+        assert_eq!(a.len() + b.len() - 2 * lcs_bit_parallel(&a, &b), d);
+    }
+}
@@ -1,16 +1,19 @@
-use serde::Deserialize;
-use serde_wasm_bindgen as swb;
-use wasm_bindgen::prelude::*;
-use wasm_bindgen::JsValue;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use unicode_segmentation::UnicodeSegmentation;
 
 use super::{
     base::{Change, Options, Tokeniser},
+    case::{eq_ignore_case, fold_case},
+    cleanup::{self, cleanup},
+    memory_pool::PooledDiff,
     token::Token,
 };
 use crate::util::string::{
     leading_ws, longest_common_prefix, longest_common_suffix, maximum_overlap, remove_prefix,
     remove_suffix, replace_prefix, replace_suffix, trailing_ws,
 };
+use crate::{change::Position, options::DiffWordsOptions};
 
 #[inline]
 fn is_word_char(c: char) -> bool {
@@ -43,12 +46,6 @@ fn is_ascii_whitespace_no_nl(b: u8) -> bool {
     matches!(b, b' ' | b'\t' | 0x0B | 0x0C)
 }
 
-#[inline(always)]
-fn ascii_eq_ignore_case(a: &str, b: &str) -> bool {
-    let (ab, bb) = (a.as_bytes(), b.as_bytes());
-    ab.len() == bb.len() && ab.iter().zip(bb).all(|(x, y)| x.eq_ignore_ascii_case(y))
-}
-
 #[inline(always)]
 fn scan_whitespace(text: &str, bytes: &[u8], mut pos: usize) -> usize {
     let len = bytes.len();
@@ -175,81 +172,127 @@ fn scan_word_with_space_token(text: &str, bytes: &[u8], pos: usize) -> usize {
     pos + ch.len_utf8()
 }
 
-#[derive(Default)]
-pub struct WordTokenizer;
-
-impl<'a> Tokeniser<'a> for WordTokenizer {
-    fn tokenize<'b>(&self, text: &'a str, arena: &'b mut Vec<Token<'a>>) -> &'b [Token<'a>] {
-        arena.clear();
-        if text.is_empty() {
-            return &arena[..];
-        }
-
-        #[derive(Copy, Clone)]
-        struct Range {
-            start: usize,
-            end: usize, // exclusive
-        }
-
-        let bytes = text.as_bytes();
-        let len = bytes.len();
-        let mut pos = 0usize;
+/// How `diff_words` finds word boundaries.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WordBoundaries {
+    /// jsdiff's rules: runs of Latin letters and digits, every other
+    /// character on its own.
+    #[default]
+    Jsdiff,
+    /// Unicode word boundaries (UAX #29), which also split scripts written
+    /// without spaces, like Chinese, Japanese and Thai.
+    Unicode,
+    /// jsdiff's rules, with identifiers further split into their camelCase,
+    /// PascalCase, snake_case and digit parts.
+    Code,
+}
 
-        let mut ranges: Vec<Range> = Vec::new();
+/// Builds word tokens from the parts of `text`, given as `(start, end,
+/// is_whitespace)`. Whitespace is attached to the neighbouring words the way
+/// jsdiff does it, so adjacent tokens may share it.
+fn attach_whitespace<'a, 'b>(
+    text: &'a str,
+    parts: impl Iterator<Item = (usize, usize, bool)>,
+    arena: &'b mut Vec<Token<'a>>,
+) -> &'b [Token<'a>] {
+    #[derive(Copy, Clone)]
+    struct Range {
+        start: usize,
+        end: usize, // exclusive
+    }
 
-        let mut prev_is_ws: Option<bool> = None;
-        let mut prev_start: usize = 0;
-        let mut prev_end: usize = 0;
+    let mut ranges: Vec<Range> = Vec::new();
 
-        while pos < len {
-            // This merging logic is required to preserve jsdiff's whitespace attachment semantics.
-            let start = pos;
-            let (end, is_ws_part) = scan_word_token(text, bytes, pos);
-            pos = end;
+    let mut prev_is_ws: Option<bool> = None;
+    let mut prev_start: usize = 0;
+    let mut prev_end: usize = 0;
 
-            match (is_ws_part, prev_is_ws) {
-                (true, None) => {
-                    ranges.push(Range { start, end });
+    for (start, end, is_ws_part) in parts {
+        // This merging logic is required to preserve jsdiff's whitespace attachment semantics.
+        match (is_ws_part, prev_is_ws) {
+            (true, None) => {
+                ranges.push(Range { start, end });
+            }
+            (true, Some(_)) => {
+                if let Some(last) = ranges.last_mut() {
+                    last.end = end;
                 }
-                (true, Some(_)) => {
+            }
+            (false, Some(true)) => {
+                let prev_ws_is_standalone = ranges
+                    .last()
+                    .map(|r| r.start == prev_start && r.end == prev_end)
+                    .unwrap_or(false);
+
+                if prev_ws_is_standalone {
                     if let Some(last) = ranges.last_mut() {
                         last.end = end;
                     }
-                }
-                (false, Some(true)) => {
-                    let prev_ws_is_standalone = ranges
-                        .last()
-                        .map(|r| r.start == prev_start && r.end == prev_end)
-                        .unwrap_or(false);
-
-                    if prev_ws_is_standalone {
-                        if let Some(last) = ranges.last_mut() {
-                            last.end = end;
-                        }
-                    } else {
-                        ranges.push(Range {
-                            start: prev_start,
-                            end,
-                        });
-                    }
-                }
-                _ => {
-                    ranges.push(Range { start, end });
+                } else {
+                    ranges.push(Range {
+                        start: prev_start,
+                        end,
+                    });
                 }
             }
-
-            prev_is_ws = Some(is_ws_part);
-            prev_start = start;
-            prev_end = end;
+            _ => {
+                ranges.push(Range { start, end });
+            }
         }
 
-        arena.reserve(ranges.len());
-        for r in ranges {
-            arena.push(Token {
-                text: &text[r.start..r.end],
-            });
+        prev_is_ws = Some(is_ws_part);
+        prev_start = start;
+        prev_end = end;
+    }
+
+    arena.reserve(ranges.len());
+    for r in ranges {
+        arena.push(Token {
+            text: &text[r.start..r.end],
+        });
+    }
+    &arena[..]
+}
+
+/// jsdiff's word and whitespace runs of `text`, as `(start, end,
+/// is_whitespace)`.
+fn word_parts(text: &str) -> impl Iterator<Item = (usize, usize, bool)> + '_ {
+    let bytes = text.as_bytes();
+    let mut pos = 0usize;
+    std::iter::from_fn(move || {
+        if pos >= bytes.len() {
+            return None;
         }
-        &arena[..]
+        let start = pos;
+        let (end, is_ws_part) = scan_word_token(text, bytes, pos);
+        pos = end;
+        Some((start, end, is_ws_part))
+    })
+}
+
+/// Whitespace fix-up and cleanup shared by the tokenizers that attach
+/// whitespace to words.
+fn post_process_words<T>(tokenizer: &T, changes: Vec<Change>, opts: &Options) -> Vec<Change>
+where
+    T: for<'t> Tokeniser<'t>,
+{
+    let changes = post_process_whitespace(changes, opts);
+    if opts.one_change_per_token {
+        return changes;
+    }
+    cleanup(changes, cleanup::mode(opts, true), |s| {
+        count_tokens(tokenizer, s)
+    })
+}
+
+#[derive(Default)]
+pub struct WordTokenizer;
+
+impl<'a> Tokeniser<'a> for WordTokenizer {
+    fn tokenize<'b>(&self, text: &'a str, arena: &'b mut Vec<Token<'a>>) -> &'b [Token<'a>] {
+        arena.clear();
+        attach_whitespace(text, word_parts(text), arena)
     }
 
     #[inline]
@@ -270,14 +313,145 @@ impl<'a> Tokeniser<'a> for WordTokenizer {
     fn equals(&self, l: &Token<'a>, r: &Token<'a>, opts: &Options) -> bool {
         let (lt, rt) = (l.text.trim(), r.text.trim());
         if opts.ignore_case {
-            ascii_eq_ignore_case(lt, rt)
+            eq_ignore_case(lt, rt, opts.case_locale)
         } else {
             lt == rt
         }
     }
 
+    #[inline]
+    fn comparison_key(&self, tok: &Token<'a>, opts: &Options) -> Cow<'a, str> {
+        let text = tok.text.trim();
+        if opts.ignore_case {
+            fold_case(text, opts.case_locale)
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+
+    fn post_process(&self, changes: Vec<Change>, opts: &Options) -> Vec<Change> {
+        post_process_words(self, changes, opts)
+    }
+}
+
+/// `WordTokenizer` splitting on Unicode word boundaries instead.
+#[derive(Default)]
+pub struct UnicodeWordTokenizer;
+
+impl<'a> Tokeniser<'a> for UnicodeWordTokenizer {
+    fn tokenize<'b>(&self, text: &'a str, arena: &'b mut Vec<Token<'a>>) -> &'b [Token<'a>] {
+        arena.clear();
+        let parts = text.split_word_bound_indices().map(|(start, part)| {
+            let is_ws_part = part.chars().any(char::is_whitespace);
+            (start, start + part.len(), is_ws_part)
+        });
+        attach_whitespace(text, parts, arena)
+    }
+
+    #[inline]
+    fn join(&self, toks: &[Token<'a>]) -> String {
+        WordTokenizer.join(toks)
+    }
+
+    #[inline]
+    fn equals(&self, l: &Token<'a>, r: &Token<'a>, opts: &Options) -> bool {
+        WordTokenizer.equals(l, r, opts)
+    }
+
+    #[inline]
+    fn comparison_key(&self, tok: &Token<'a>, opts: &Options) -> Cow<'a, str> {
+        WordTokenizer.comparison_key(tok, opts)
+    }
+
+    fn post_process(&self, changes: Vec<Change>, opts: &Options) -> Vec<Change> {
+        post_process_words(self, changes, opts)
+    }
+}
+
+/// Splits a word run into identifier parts: `getHTTPResponse2_x` gives
+/// `get`, `HTTP`, `Response`, `2`, `_` and `x`. Yields `(start, end)` offsets
+/// relative to `word`.
+fn identifier_parts(word: &str) -> impl Iterator<Item = (usize, usize)> + '_ {
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Class {
+        Lower,
+        Upper,
+        Digit,
+        Underscore,
+    }
+    let class = |c: char| match c {
+        '_' => Class::Underscore,
+        c if c.is_numeric() => Class::Digit,
+        c if c.is_uppercase() => Class::Upper,
+        _ => Class::Lower,
+    };
+
+    let mut chars = word.char_indices().peekable();
+    let mut start = 0;
+    std::iter::from_fn(move || {
+        let (_, first) = chars.next()?;
+        let mut prev = class(first);
+        while let Some(&(idx, c)) = chars.peek() {
+            let cur = class(c);
+            let boundary = match (prev, cur) {
+                (Class::Lower, Class::Upper) => true,
+                // The last capital of an acronym starts the next word.
+                (Class::Upper, Class::Upper) => {
+                    let mut ahead = word[idx..].chars().skip(1);
+                    ahead.next().is_some_and(|n| class(n) == Class::Lower)
+                }
+                (Class::Upper, Class::Lower) => false,
+                (prev, cur) => prev != cur,
+            };
+            if boundary {
+                let part = (start, idx);
+                start = idx;
+                return Some(part);
+            }
+            chars.next();
+            prev = cur;
+        }
+        let part = (start, word.len());
+        start = word.len();
+        Some(part)
+    })
+}
+
+/// `WordTokenizer` that also splits identifiers into their parts, so that
+/// renaming `getUserById` to `getUserByEmail` only changes `Id`.
+#[derive(Default)]
+pub struct CodeWordTokenizer;
+
+impl<'a> Tokeniser<'a> for CodeWordTokenizer {
+    fn tokenize<'b>(&self, text: &'a str, arena: &'b mut Vec<Token<'a>>) -> &'b [Token<'a>] {
+        arena.clear();
+        let parts = word_parts(text).flat_map(|(start, end, is_ws_part)| {
+            let subwords = (!is_ws_part).then(|| {
+                identifier_parts(&text[start..end]).map(move |(s, e)| (start + s, start + e, false))
+            });
+            let whole = is_ws_part.then_some((start, end, true));
+            subwords.into_iter().flatten().chain(whole)
+        });
+        attach_whitespace(text, parts, arena)
+    }
+
+    #[inline]
+    fn join(&self, toks: &[Token<'a>]) -> String {
+        WordTokenizer.join(toks)
+    }
+
+    #[inline]
+    fn equals(&self, l: &Token<'a>, r: &Token<'a>, opts: &Options) -> bool {
+        WordTokenizer.equals(l, r, opts)
+    }
+
+    #[inline]
+    fn comparison_key(&self, tok: &Token<'a>, opts: &Options) -> Cow<'a, str> {
+        WordTokenizer.comparison_key(tok, opts)
+    }
+
     fn post_process(&self, changes: Vec<Change>, opts: &Options) -> Vec<Change> {
-        post_process_whitespace(changes, opts)
+        post_process_words(self, changes, opts)
     }
 }
 
@@ -310,6 +484,15 @@ impl<'a> Tokeniser<'a> for WordWithSpaceTokenizer {
     fn join(&self, toks: &[Token<'a>]) -> String {
         toks.iter().map(|t| t.text).collect()
     }
+
+    fn post_process(&self, changes: Vec<Change>, opts: &Options) -> Vec<Change> {
+        if opts.one_change_per_token {
+            return changes;
+        }
+        cleanup(changes, cleanup::mode(opts, false), |s| {
+            count_tokens(self, s)
+        })
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -444,117 +627,75 @@ fn post_process_whitespace(mut changes: Vec<Change>, opts: &Options) -> Vec<Chan
     changes
 }
 
-#[derive(Deserialize, Default, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-struct WordOptions {
-    #[serde(default)]
-    ignore_case: bool,
-    #[serde(default)]
-    ignore_whitespace: Option<bool>,
-    #[serde(default)]
-    one_change_per_token: bool,
-    #[serde(default)]
-    max_edit_length: Option<usize>,
-}
-
-impl From<WordOptions> for Options {
-    fn from(wo: WordOptions) -> Self {
-        Options {
-            ignore_case: wo.ignore_case,
-            one_change_per_token: wo.one_change_per_token,
-            max_edit_length: wo.max_edit_length,
-        }
-    }
-}
-
 fn count_tokens<'a, T: Tokeniser<'a>>(tok: &T, s: &'a str) -> u32 {
     let mut arena = Vec::new();
     tok.tokenize(s, &mut arena).len() as u32
 }
 
-#[wasm_bindgen(js_name = diffWords)]
-pub fn diff_words(old: &str, new_: &str, opts: JsValue) -> Result<JsValue, JsValue> {
-    let wo: WordOptions = swb::from_value(opts).unwrap_or_default();
-    let base_opts: Options = wo.clone().into();
-    let use_with_space = wo.ignore_whitespace == Some(false);
+fn unchanged<'a, T: Tokeniser<'a>>(tok: &T, value: &'a str, opts: &Options) -> Vec<Change> {
+    if value.is_empty() {
+        return Vec::new();
+    }
+    let position = opts.positions.then(Position::default);
+    vec![Change {
+        value: value.to_string(),
+        count: count_tokens(tok, value),
+        added: false,
+        removed: false,
+        position,
+    }]
+}
 
-    let equal = if base_opts.ignore_case {
-        old.eq_ignore_ascii_case(new_)
+pub(crate) fn inputs_equal(old: &str, new_: &str, opts: &Options) -> bool {
+    if opts.comparator.is_some() {
+        false
+    } else if opts.ignore_case {
+        eq_ignore_case(old, new_, opts.case_locale)
     } else {
         old == new_
-    };
-    if equal {
-        if new_.is_empty() {
-            let empty: Vec<Change> = Vec::new();
-            return swb::to_value(&empty).map_err(Into::into);
-        }
-        let cnt = if use_with_space {
-            count_tokens(&WordWithSpaceTokenizer, new_)
-        } else {
-            count_tokens(&WordTokenizer, new_)
-        };
-        let changes = vec![Change {
-            value: new_.to_string(),
-            count: cnt,
-            added: false,
-            removed: false,
-        }];
-        return swb::to_value(&changes).map_err(Into::into);
-    }
-
-    use super::memory_pool::PooledDiff;
-    let changes = if use_with_space {
-        let mut diff = PooledDiff::new(WordWithSpaceTokenizer, base_opts);
-        diff.diff(old, new_)
-    } else {
-        let mut diff = PooledDiff::new(WordTokenizer, base_opts);
-        diff.diff(old, new_)
-    };
-    swb::to_value(&changes).map_err(|e| JsValue::from(e.to_string()))
+    }
 }
 
-#[wasm_bindgen(js_name = diffWordsWithSpace)]
-pub fn diff_words_with_space(old: &str, new_: &str, opts: JsValue) -> Result<JsValue, JsValue> {
-    let wo: WordOptions = swb::from_value(opts).unwrap_or_default();
-    let base_opts: Options = wo.into();
+pub fn diff_words(old: &str, new_: &str, opts: &DiffWordsOptions) -> Vec<Change> {
+    if opts.ignore_whitespace == Some(false) {
+        return diff_words_with_space(old, new_, opts);
+    }
 
-    let equal = if base_opts.ignore_case {
-        old.eq_ignore_ascii_case(new_)
-    } else {
-        old == new_
-    };
-    if equal {
-        if new_.is_empty() {
-            let empty: Vec<Change> = Vec::new();
-            return swb::to_value(&empty).map_err(Into::into);
-        }
-        let cnt = count_tokens(&WordWithSpaceTokenizer, new_);
-        let changes = vec![Change {
-            value: new_.to_string(),
-            count: cnt,
-            added: false,
-            removed: false,
-        }];
-        return swb::to_value(&changes).map_err(Into::into);
+    let base_opts: Options = opts.into();
+    match opts.word_boundaries {
+        WordBoundaries::Jsdiff => words(WordTokenizer, old, new_, base_opts),
+        WordBoundaries::Unicode => words(UnicodeWordTokenizer, old, new_, base_opts),
+        WordBoundaries::Code => words(CodeWordTokenizer, old, new_, base_opts),
     }
+}
 
-    use super::memory_pool::PooledDiff;
-    let mut diff = PooledDiff::new(WordWithSpaceTokenizer, base_opts);
-    let changes = diff.diff(old, new_);
-    swb::to_value(&changes).map_err(|e| JsValue::from(e.to_string()))
+/// `diff_words` with `WordBoundaries::Code`.
+pub fn diff_code_words(old: &str, new_: &str, opts: &DiffWordsOptions) -> Vec<Change> {
+    let opts = DiffWordsOptions {
+        word_boundaries: WordBoundaries::Code,
+        ..opts.clone()
+    };
+    diff_words(old, new_, &opts)
 }
 
-#[wasm_bindgen(js_name = wordDiff)]
-pub struct WordDiff;
+fn words<'a, T: Tokeniser<'a>>(
+    tokenizer: T,
+    old: &'a str,
+    new_: &'a str,
+    opts: Options,
+) -> Vec<Change> {
+    if inputs_equal(old, new_, &opts) {
+        return unchanged(&tokenizer, new_, &opts);
+    }
+    PooledDiff::new(tokenizer, opts).diff(old, new_)
+}
 
-#[wasm_bindgen(js_class = wordDiff)]
-impl WordDiff {
-    #[wasm_bindgen(js_name = tokenize)]
-    pub fn tokenize_js(text: &str) -> Result<JsValue, JsValue> {
-        let tok = WordTokenizer;
-        let mut arena = Vec::new();
-        let toks = tok.tokenize(text, &mut arena);
-        let vec: Vec<&str> = toks.iter().map(|t| t.text).collect();
-        swb::to_value(&vec).map_err(|e| JsValue::from(e.to_string()))
+pub fn diff_words_with_space(old: &str, new_: &str, opts: &DiffWordsOptions) -> Vec<Change> {
+    let base_opts: Options = opts.into();
+    if inputs_equal(old, new_, &base_opts) {
+        return unchanged(&WordWithSpaceTokenizer, new_, &base_opts);
     }
+
+    let mut diff = PooledDiff::new(WordWithSpaceTokenizer, base_opts);
+    diff.diff(old, new_)
 }
@@ -1,4 +1,4 @@
-use crate::diff::base::{Options, Tokeniser};
+use crate::diff::base::{Options, Tokeniser, WithComparator};
 use crate::diff::token::Token;
 use crate::diff::word::WordTokenizer;
 
@@ -137,6 +137,7 @@ fn test_word_tokenizer_equals_behavior() {
         ignore_case: false,
         one_change_per_token: false,
         max_edit_length: None,
+        ..Default::default()
     };
 
     assert!(tokenizer.equals(
@@ -153,6 +154,7 @@ fn test_word_tokenizer_equals_behavior() {
         ignore_case: true,
         one_change_per_token: false,
         max_edit_length: None,
+        ..Default::default()
     };
 
     assert!(tokenizer.equals(
@@ -178,3 +180,140 @@ fn test_word_tokenizer_join_behavior() {
 
     assert_eq!(tokenizer.join(&tokens_with_spaces), "  hello  world  ");
 }
+
+#[test]
+fn test_diff_words_native_api() {
+    use crate::{diff_words, diff_words_with_space, DiffWordsOptions};
+
+    let changes = diff_words(
+        "New Value",
+        "New ValueMoreData",
+        &DiffWordsOptions::default(),
+    );
+    assert_eq!(changes.len(), 3);
+    assert_eq!(changes[0].value, "New ");
+    assert!(changes[1].removed);
+    assert_eq!(changes[1].value, "Value");
+    assert!(changes[2].added);
+    assert_eq!(changes[2].value, "ValueMoreData");
+
+    let unchanged = diff_words("same text", "same text", &DiffWordsOptions::default());
+    assert_eq!(unchanged.len(), 1);
+    assert_eq!(unchanged[0].count, 2);
+
+    assert!(diff_words("", "", &DiffWordsOptions::default()).is_empty());
+
+    let ws = diff_words_with_space("a  b", "a b", &DiffWordsOptions::default());
+    assert!(ws.iter().any(|c| c.removed && c.value == "  "));
+    assert!(ws.iter().any(|c| c.added && c.value == " "));
+}
+
+#[test]
+fn test_diff_words_comparator() {
+    use crate::{diff_words, DiffWordsOptions};
+
+    let opts = DiffWordsOptions::default().with_comparator(|l, r| l.len() == r.len());
+    let changes = diff_words("one two three", "six ten eight", &opts);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].value, "six ten eight");
+
+    // Identical inputs still go through the comparator.
+    let never = DiffWordsOptions::default().with_comparator(|_, _| false);
+    let changes = diff_words("same", "same", &never);
+    assert!(changes[0].removed && changes[1].added);
+}
+
+#[test]
+fn test_unicode_word_boundaries() {
+    use crate::diff::word::UnicodeWordTokenizer;
+    use crate::{diff_words, DiffWordsOptions, WordBoundaries};
+
+    let tokens = |text| -> Vec<&str> {
+        let mut arena = Vec::new();
+        UnicodeWordTokenizer
+            .tokenize(text, &mut arena)
+            .iter()
+            .map(|t| t.text)
+            .collect()
+    };
+    assert_eq!(tokens("我喜欢猫"), vec!["我", "喜", "欢", "猫"]);
+    assert_eq!(tokens("hello  world"), vec!["hello  ", "  world"]);
+    assert_eq!(tokens("can't stop"), vec!["can't ", " stop"]);
+
+    let mut arena = Vec::new();
+    assert_eq!(WordTokenizer.tokenize("カタカナです", &mut arena).len(), 6);
+    assert_eq!(tokens("カタカナです"), vec!["カタカナ", "で", "す"]);
+
+    let opts = DiffWordsOptions {
+        word_boundaries: WordBoundaries::Unicode,
+        ..Default::default()
+    };
+    let changes = diff_words("The cat's 猫 sat.", "The dog's 猫 sat.", &opts);
+    let summary: Vec<(&str, bool, bool)> = changes
+        .iter()
+        .map(|c| (c.value.as_str(), c.added, c.removed))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("The ", false, false),
+            ("cat's", false, true),
+            ("dog's", true, false),
+            (" 猫 sat.", false, false),
+        ]
+    );
+}
+
+#[test]
+fn test_code_word_boundaries() {
+    use crate::diff::word::CodeWordTokenizer;
+    use crate::{diff_code_words, DiffWordsOptions};
+
+    let tokens = |text| -> Vec<&str> {
+        let mut arena = Vec::new();
+        CodeWordTokenizer
+            .tokenize(text, &mut arena)
+            .iter()
+            .map(|t| t.text)
+            .collect()
+    };
+    assert_eq!(tokens("getUserById"), vec!["get", "User", "By", "Id"]);
+    assert_eq!(
+        tokens("XMLHttpRequest2"),
+        vec!["XML", "Http", "Request", "2"]
+    );
+    assert_eq!(
+        tokens("__max_retry_count"),
+        vec!["__", "max", "_", "retry", "_", "count"]
+    );
+    assert_eq!(
+        tokens("user-id v10a"),
+        vec!["user", "-", "id ", " v", "10", "a"]
+    );
+    assert_eq!(tokens("ÉtéÀLaPlage"), vec!["Été", "À", "La", "Plage"]);
+
+    for text in ["let fooBar = baz_qux(1, 'HTTPServer');", "  aB  CdE_f\n"] {
+        let mut arena = Vec::new();
+        let toks = CodeWordTokenizer.tokenize(text, &mut arena);
+        assert_eq!(CodeWordTokenizer.join(toks), text);
+    }
+
+    let changes = diff_code_words(
+        "return getUserById(id);",
+        "return getUserByEmail(id);",
+        &DiffWordsOptions::default(),
+    );
+    let summary: Vec<(&str, bool, bool)> = changes
+        .iter()
+        .map(|c| (c.value.as_str(), c.added, c.removed))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("return getUserBy", false, false),
+            ("Id", false, true),
+            ("Email", true, false),
+            ("(id);", false, false),
+        ]
+    );
+}
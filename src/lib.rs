@@ -1,6 +1,6 @@
-use wasm_bindgen::prelude::*;
-
 mod change;
+#[cfg(test)]
+mod change_test;
 pub mod diff;
 mod options;
 pub mod patch;
@@ -8,34 +8,41 @@ pub mod util;
 pub mod convert {
     pub mod xml;
 }
-
-pub use change::Change;
-pub use options::{DiffLinesOptions, DiffOptions, DiffSentencesOptions, DiffWordsOptions};
-pub use patch::apply::ApplyOptions;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use change::{locate, Change, Location, Position};
+pub use diff::base::WithComparator;
+pub use options::{
+    DiffCodeOptions, DiffJsonOptions, DiffLinesOptions, DiffOptions, DiffPatternOptions,
+    DiffSentencesOptions, DiffWordsOptions,
+};
+pub use patch::apply::{ApplyOptions, CompareLine};
+pub use patch::create::StructuredOptions;
 pub use patch::types::{Hunk, Patch};
 
-pub use diff::character::diff_chars;
+pub use diff::array::{diff_arrays, diff_arrays_by, ArrayChange};
+pub use diff::character::{diff_chars, Granularity};
+pub use diff::cleanup::{cleanup_changes, Cleanup};
+pub use diff::code::{diff_code, CodeLanguage};
 pub use diff::css::diff_css;
-pub use diff::json::{canonicalize, diff_json};
+pub use diff::json::{canonicalize_value, diff_json};
 pub use diff::line::{diff_lines, diff_trimmed_lines};
-pub use diff::sentences::diff_sentences;
-pub use diff::word::{diff_words, diff_words_with_space};
+pub use diff::normalize::Normalizer;
+pub use diff::ops::{OpUnit, OP_DELETE, OP_EQUAL, OP_INSERT};
+pub use diff::pattern::diff_with_pattern;
+pub use diff::sentences::{diff_sentences, SentenceBoundaries};
+pub use diff::similarity::{similarity, Similarity};
+pub use diff::word::{diff_code_words, diff_words, diff_words_with_space, WordBoundaries};
 
 pub use diff::{clear_pools, get_pool_stats, CharTokenizer, Component, ComponentPool, PooledDiff};
 
 pub use convert::xml::convert_changes_to_xml;
 
 pub use patch::{
-    apply_patch, apply_patches, create_patch, create_two_files_patch, format_patch,
-    line_endings::{is_unix, is_win, unix_to_win, win_to_unix},
-    parse::parse_patch,
-    reverse_patch, structured_patch,
+    apply_patch, create_patch, create_two_files_patch, format_patch, format_patches, parse_patch,
+    reverse_patch, reverse_patches, structured_patch,
 };
 
-pub use patch::parse::parse_patch_internal;
-
-#[wasm_bindgen]
-pub fn set_panic_hook() {
-    #[cfg(feature = "console_error_panic_hook")]
-    console_error_panic_hook::set_once();
-}
+#[cfg(feature = "wasm")]
+pub use wasm::set_panic_hook;
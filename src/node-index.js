@@ -4,13 +4,29 @@ module.exports = {
   diffChars: diffNative.diffChars,
   diffWordsWithSpace: diffNative.diffWordsWithSpace,
   diffWords: diffNative.diffWords,
+  diffCodeWords: diffNative.diffCodeWords,
   diffLines: diffNative.diffLines,
   diffTrimmedLines: diffNative.diffTrimmedLines,
   diffSentences: diffNative.diffSentences,
   diffCss: diffNative.diffCss,
+  diffCode: diffNative.diffCode,
+  diffWithPattern: diffNative.diffWithPattern,
   diffJson: diffNative.diffJson,
+  diffArrays: diffNative.diffArrays,
+  createDiff: diffNative.createDiff,
+
+  diffCharsAsync: diffNative.diffCharsAsync,
+  diffWordsWithSpaceAsync: diffNative.diffWordsWithSpaceAsync,
+  diffWordsAsync: diffNative.diffWordsAsync,
+  diffLinesAsync: diffNative.diffLinesAsync,
+  diffTrimmedLinesAsync: diffNative.diffTrimmedLinesAsync,
+  diffSentencesAsync: diffNative.diffSentencesAsync,
+  diffCssAsync: diffNative.diffCssAsync,
+  diffCodeAsync: diffNative.diffCodeAsync,
 
   convertChangesToXML: diffNative.convertChangesToXML,
+  cleanupChanges: diffNative.cleanupChanges,
+  similarity: diffNative.similarity,
 
   parsePatch: diffNative.parsePatch,
   createPatch: diffNative.createPatch,
@@ -1,16 +1,49 @@
+//! Options of the public diff functions. Fields they share, such as
+//! `locale`, mean the same in every struct and are documented on `Options`.
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+use crate::diff::{
+    base::{Algorithm, Comparator, Options, WithComparator},
+    case::CaseLocale,
+    character::Granularity,
+    cleanup::Cleanup,
+    code::CodeLanguage,
+    normalize::Normalizer,
+    sentences::SentenceBoundaries,
+    word::WordBoundaries,
+};
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct DiffOptions {
-    #[serde(alias = "ignoreCase")]
-    pub ignore_case: Option<bool>,
-    #[serde(alias = "oneChangePerToken")]
-    pub one_change_per_token: Option<bool>,
-    #[serde(alias = "maxEditLength")]
+    #[serde(default)]
+    pub ignore_case: bool,
+    #[serde(default)]
+    pub one_change_per_token: bool,
+    #[serde(default)]
     pub max_edit_length: Option<usize>,
+    #[serde(default)]
+    pub linear_space_threshold: Option<usize>,
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub cleanup: Cleanup,
+    #[serde(default)]
+    pub positions: bool,
+    /// Read by `diff_chars` only; the other diffs taking `DiffOptions`
+    /// ignore it and always work on their own tokens.
+    #[serde(default)]
+    pub granularity: Granularity,
+    #[serde(default)]
+    pub locale: Option<String>,
+    #[serde(skip)]
+    pub normalizers: Vec<Normalizer>,
+    #[serde(skip)]
+    pub comparator: Option<Comparator>,
 }
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct DiffLinesOptions {
     #[serde(default)]
@@ -20,23 +53,296 @@ pub struct DiffLinesOptions {
     #[serde(default)]
     pub strip_trailing_cr: bool,
     #[serde(default)]
+    pub ignore_newline_at_eof: bool,
+    #[serde(default)]
     pub one_change_per_token: bool,
     #[serde(default)]
     pub ignore_case: bool,
+    #[serde(default)]
+    pub max_edit_length: Option<usize>,
+    #[serde(default)]
+    pub linear_space_threshold: Option<usize>,
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub algorithm: Algorithm,
+    /// Slide ambiguous insertions and deletions like git's indent heuristic.
+    /// Off for `diff_lines` and on for patches unless set.
+    #[serde(default)]
+    pub indent_heuristic: Option<bool>,
+    #[serde(default)]
+    pub positions: bool,
+    #[serde(default)]
+    pub locale: Option<String>,
+    #[serde(skip)]
+    pub normalizers: Vec<Normalizer>,
+    #[serde(skip)]
+    pub comparator: Option<Comparator>,
 }
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct DiffWordsOptions {
     #[serde(default)]
     pub ignore_case: bool,
     #[serde(default)]
+    pub ignore_whitespace: Option<bool>,
+    #[serde(default)]
     pub one_change_per_token: bool,
+    #[serde(default)]
+    pub max_edit_length: Option<usize>,
+    #[serde(default)]
+    pub linear_space_threshold: Option<usize>,
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub cleanup: Cleanup,
+    #[serde(default)]
+    pub positions: bool,
+    #[serde(default)]
+    pub word_boundaries: WordBoundaries,
+    #[serde(default)]
+    pub locale: Option<String>,
+    #[serde(skip)]
+    pub normalizers: Vec<Normalizer>,
+    #[serde(skip)]
+    pub comparator: Option<Comparator>,
 }
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct DiffSentencesOptions {
+    #[serde(default)]
+    pub ignore_case: bool,
+    #[serde(default)]
+    pub one_change_per_token: bool,
+    #[serde(default)]
+    pub max_edit_length: Option<usize>,
+    #[serde(default)]
+    pub linear_space_threshold: Option<usize>,
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub positions: bool,
+    #[serde(default)]
+    pub locale: Option<String>,
+    #[serde(default)]
+    pub sentence_boundaries: SentenceBoundaries,
+    /// Extra abbreviations, such as `"approx."`, that a sentence never ends
+    /// on with `SentenceBoundaries::Unicode`.
+    #[serde(default)]
+    pub abbreviations: Vec<String>,
+    #[serde(skip)]
+    pub normalizers: Vec<Normalizer>,
+    #[serde(skip)]
+    pub comparator: Option<Comparator>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffCodeOptions {
+    #[serde(default)]
+    pub ignore_case: bool,
     #[serde(default)]
     pub one_change_per_token: bool,
+    #[serde(default)]
+    pub max_edit_length: Option<usize>,
+    #[serde(default)]
+    pub linear_space_threshold: Option<usize>,
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub positions: bool,
+    #[serde(default)]
+    pub language: CodeLanguage,
+    #[serde(default)]
+    pub locale: Option<String>,
+    #[serde(skip)]
+    pub normalizers: Vec<Normalizer>,
+    #[serde(skip)]
+    pub comparator: Option<Comparator>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffPatternOptions {
+    /// Regex whose matches are the tokens; the text between matches is
+    /// tokens too.
+    #[serde(skip)]
+    pub token_pattern: String,
+    /// Regex for text, such as timestamps, to leave out of token comparison.
+    #[serde(skip)]
+    pub ignore_pattern: Option<String>,
+    #[serde(default)]
+    pub ignore_case: bool,
+    #[serde(default)]
+    pub one_change_per_token: bool,
+    #[serde(default)]
+    pub max_edit_length: Option<usize>,
+    #[serde(default)]
+    pub linear_space_threshold: Option<usize>,
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub positions: bool,
+    #[serde(default)]
+    pub locale: Option<String>,
+    #[serde(skip)]
+    pub normalizers: Vec<Normalizer>,
+    #[serde(skip)]
+    pub comparator: Option<Comparator>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffJsonOptions {
+    #[serde(default)]
+    pub undefined_replacement: Option<serde_json::Value>,
+}
+
+impl WithComparator for DiffOptions {
+    #[inline]
+    fn comparator_mut(&mut self) -> &mut Option<Comparator> {
+        &mut self.comparator
+    }
+}
+
+impl WithComparator for DiffLinesOptions {
+    #[inline]
+    fn comparator_mut(&mut self) -> &mut Option<Comparator> {
+        &mut self.comparator
+    }
+}
+
+impl WithComparator for DiffWordsOptions {
+    #[inline]
+    fn comparator_mut(&mut self) -> &mut Option<Comparator> {
+        &mut self.comparator
+    }
+}
+
+impl WithComparator for DiffSentencesOptions {
+    #[inline]
+    fn comparator_mut(&mut self) -> &mut Option<Comparator> {
+        &mut self.comparator
+    }
+}
+
+impl WithComparator for DiffCodeOptions {
+    #[inline]
+    fn comparator_mut(&mut self) -> &mut Option<Comparator> {
+        &mut self.comparator
+    }
+}
+
+impl WithComparator for DiffPatternOptions {
+    #[inline]
+    fn comparator_mut(&mut self) -> &mut Option<Comparator> {
+        &mut self.comparator
+    }
+}
+
+impl From<&DiffOptions> for Options {
+    fn from(o: &DiffOptions) -> Self {
+        Options {
+            ignore_case: o.ignore_case,
+            case_locale: CaseLocale::from_option(o.locale.as_deref()),
+            one_change_per_token: o.one_change_per_token,
+            max_edit_length: o.max_edit_length,
+            linear_space_threshold: o.linear_space_threshold,
+            timeout: o.timeout,
+            cleanup: o.cleanup,
+            positions: o.positions,
+            comparator: o.comparator.clone(),
+            normalizers: o.normalizers.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&DiffLinesOptions> for Options {
+    fn from(lo: &DiffLinesOptions) -> Self {
+        Options {
+            ignore_case: lo.ignore_case,
+            case_locale: CaseLocale::from_option(lo.locale.as_deref()),
+            one_change_per_token: lo.one_change_per_token,
+            max_edit_length: lo.max_edit_length,
+            linear_space_threshold: lo.linear_space_threshold,
+            timeout: lo.timeout,
+            algorithm: lo.algorithm,
+            positions: lo.positions,
+            comparator: lo.comparator.clone(),
+            normalizers: lo.normalizers.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&DiffWordsOptions> for Options {
+    fn from(wo: &DiffWordsOptions) -> Self {
+        Options {
+            ignore_case: wo.ignore_case,
+            case_locale: CaseLocale::from_option(wo.locale.as_deref()),
+            one_change_per_token: wo.one_change_per_token,
+            max_edit_length: wo.max_edit_length,
+            linear_space_threshold: wo.linear_space_threshold,
+            timeout: wo.timeout,
+            cleanup: wo.cleanup,
+            positions: wo.positions,
+            comparator: wo.comparator.clone(),
+            normalizers: wo.normalizers.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&DiffSentencesOptions> for Options {
+    fn from(so: &DiffSentencesOptions) -> Self {
+        Options {
+            ignore_case: so.ignore_case,
+            case_locale: CaseLocale::from_option(so.locale.as_deref()),
+            one_change_per_token: so.one_change_per_token,
+            max_edit_length: so.max_edit_length,
+            linear_space_threshold: so.linear_space_threshold,
+            timeout: so.timeout,
+            positions: so.positions,
+            comparator: so.comparator.clone(),
+            normalizers: so.normalizers.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&DiffCodeOptions> for Options {
+    fn from(co: &DiffCodeOptions) -> Self {
+        Options {
+            ignore_case: co.ignore_case,
+            case_locale: CaseLocale::from_option(co.locale.as_deref()),
+            one_change_per_token: co.one_change_per_token,
+            max_edit_length: co.max_edit_length,
+            linear_space_threshold: co.linear_space_threshold,
+            timeout: co.timeout,
+            positions: co.positions,
+            comparator: co.comparator.clone(),
+            normalizers: co.normalizers.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&DiffPatternOptions> for Options {
+    fn from(po: &DiffPatternOptions) -> Self {
+        Options {
+            ignore_case: po.ignore_case,
+            case_locale: CaseLocale::from_option(po.locale.as_deref()),
+            one_change_per_token: po.one_change_per_token,
+            max_edit_length: po.max_edit_length,
+            linear_space_threshold: po.linear_space_threshold,
+            timeout: po.timeout,
+            positions: po.positions,
+            comparator: po.comparator.clone(),
+            normalizers: po.normalizers.clone(),
+            ..Default::default()
+        }
+    }
 }
@@ -1,13 +1,11 @@
-use js_sys::{Array, Reflect};
 use serde::Deserialize;
-use wasm_bindgen::prelude::*;
+use std::{fmt, rc::Rc};
 
 use crate::{
     patch::{
         line_endings::{
             is_unix_internal, is_win_internal, unix_to_win_internal, win_to_unix_internal,
         },
-        parse::parse_patch_internal,
         types::{Hunk, Patch},
     },
     util::{
@@ -16,20 +14,41 @@ use crate::{
     },
 };
 
-#[derive(Default, Deserialize, Clone, Debug)]
+/// Custom line comparison used while applying hunks, mirroring jsdiff's
+/// `compareLine(lineNumber, line, operation, patchContent)`.
+pub type CompareLine = Rc<dyn Fn(usize, &str, char, &str) -> bool>;
+
+#[derive(Default, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ApplyOptions {
     pub auto_convert_line_endings: Option<bool>,
     pub fuzz_factor: Option<usize>,
     #[serde(skip)]
-    pub compare_line: Option<js_sys::Function>,
+    pub compare_line: Option<CompareLine>,
 }
 
-pub(crate) fn apply_patch_internal(
-    source: &str,
-    patch: &Patch,
-    options: &ApplyOptions,
-) -> Result<String, String> {
+impl ApplyOptions {
+    #[inline]
+    pub fn with_compare_line<F>(mut self, compare_line: F) -> Self
+    where
+        F: Fn(usize, &str, char, &str) -> bool + 'static,
+    {
+        self.compare_line = Some(Rc::new(compare_line));
+        self
+    }
+}
+
+impl fmt::Debug for ApplyOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApplyOptions")
+            .field("auto_convert_line_endings", &self.auto_convert_line_endings)
+            .field("fuzz_factor", &self.fuzz_factor)
+            .field("compare_line", &self.compare_line.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+pub fn apply_patch(source: &str, patch: &Patch, options: &ApplyOptions) -> Result<String, String> {
     let mut patch = patch.clone();
     let fuzz = options.fuzz_factor.unwrap_or(0);
 
@@ -156,66 +175,6 @@ pub(crate) fn apply_patch_internal(
     Ok(output)
 }
 
-#[wasm_bindgen(js_name = applyPatch)]
-pub fn apply_patch(source: &str, uni_diff: JsValue, opts: JsValue) -> Result<JsValue, JsValue> {
-    let mut patch_val = uni_diff.clone();
-
-    if Array::is_array(&uni_diff) {
-        let arr = Array::from(&uni_diff);
-        if arr.length() != 1 {
-            return Err(JsValue::from_str(
-                "applyPatch only works with a single input.",
-            ));
-        }
-        patch_val = arr.get(0);
-    }
-
-    let patch: Patch = if patch_val.is_string() {
-        let s = patch_val.as_string().unwrap();
-        parse_patch_internal(&s)
-            .map_err(|e| JsValue::from_str(&e))?
-            .into_iter()
-            .next()
-            .ok_or_else(|| JsValue::from_str("empty patch"))?
-    } else {
-        serde_wasm_bindgen::from_value(patch_val)?
-    };
-
-    if !opts.is_undefined() && !opts.is_null() {
-        let ff =
-            Reflect::get(&opts, &JsValue::from_str("fuzzFactor")).unwrap_or(JsValue::UNDEFINED);
-        if !ff.is_undefined() && !ff.is_null() {
-            if let Some(n) = ff.as_f64() {
-                if n < 0.0 || n.fract() != 0.0 {
-                    return Err(JsValue::from_str(
-                        "fuzzFactor must be a non-negative integer",
-                    ));
-                }
-            } else {
-                return Err(JsValue::from_str(
-                    "fuzzFactor must be a non-negative integer",
-                ));
-            }
-        }
-    }
-
-    let mut options: ApplyOptions =
-        serde_wasm_bindgen::from_value(opts.clone()).unwrap_or_default();
-
-    if !opts.is_undefined() && !opts.is_null() {
-        let compare_line =
-            Reflect::get(&opts, &JsValue::from_str("compareLine")).unwrap_or(JsValue::UNDEFINED);
-        if !compare_line.is_undefined() && !compare_line.is_null() && compare_line.is_function() {
-            options.compare_line = Some(js_sys::Function::from(compare_line));
-        }
-    }
-
-    match apply_patch_internal(source, &patch, &options) {
-        Ok(result) => Ok(JsValue::from_str(&result)),
-        Err(_) => Ok(JsValue::from_bool(false)),
-    }
-}
-
 #[derive(Clone, Debug)]
 pub(crate) struct ApplyRes {
     pub(crate) patched_lines: Vec<String>,
@@ -229,17 +188,21 @@ pub(crate) fn try_apply_hunk_with_options(
     errs: usize,
     options: &ApplyOptions,
 ) -> Option<ApplyRes> {
-    apply_hunk_rec(
-        &h.lines,
-        lines,
-        pos,
-        errs,
-        0,
-        true,
-        Vec::new(),
-        0,
-        &options.compare_line,
-    )
+    apply_hunk_rec(&h.lines, lines, pos, errs, 0, true, Vec::new(), 0, options)
+}
+
+#[inline]
+fn compare_line(
+    options: &ApplyOptions,
+    line_number: isize,
+    line: &str,
+    operation: char,
+    patch_content: &str,
+) -> bool {
+    match &options.compare_line {
+        Some(compare_fn) => compare_fn(line_number as usize, line, operation, patch_content),
+        None => line == patch_content,
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -252,7 +215,7 @@ fn apply_hunk_rec(
     mut last_ctx_matched: bool,
     mut patched: Vec<String>,
     mut patched_len: usize,
-    compare_line: &Option<js_sys::Function>,
+    options: &ApplyOptions,
 ) -> Option<ApplyRes> {
     let mut n_consecutive_ctx = 0;
     let mut next_ctx_must_match = false;
@@ -270,29 +233,14 @@ fn apply_hunk_rec(
 
         match op {
             '-' => {
-                let matches = if let Some(compare_fn) = compare_line {
-                    let this = JsValue::NULL;
-                    let line_num = JsValue::from_f64((to_pos + 1) as f64);
-                    let line_content =
-                        JsValue::from_str(lines.get(to_pos as usize).unwrap_or(&String::new()));
-                    let op = JsValue::from_str("-");
-                    let patch_content = JsValue::from_str(content);
-
-                    let args = js_sys::Array::new();
-                    args.push(&line_num);
-                    args.push(&line_content);
-                    args.push(&op);
-                    args.push(&patch_content);
-
-                    compare_fn
-                        .apply(&this, &args)
-                        .map(|result| result.as_bool().unwrap_or(false))
-                        .unwrap_or(false)
-                } else {
-                    lines
-                        .get(to_pos as usize)
-                        .map(|l| l == content)
-                        .unwrap_or(false)
+                let matches = match lines.get(to_pos as usize) {
+                    Some(line) => compare_line(options, to_pos + 1, line, '-', content),
+                    // Past the end of the source nothing matches by default, but a
+                    // custom comparator still sees the (empty) line, as in jsdiff.
+                    None => options
+                        .compare_line
+                        .as_ref()
+                        .is_some_and(|f| f((to_pos + 1) as usize, "", '-', content)),
                 };
 
                 if matches {
@@ -315,7 +263,7 @@ fn apply_hunk_rec(
                         false,
                         patched,
                         patched_len + 1,
-                        compare_line,
+                        options,
                     );
                 }
             }
@@ -333,26 +281,7 @@ fn apply_hunk_rec(
                 n_consecutive_ctx += 1;
                 let source_line = lines.get(to_pos as usize)?.clone();
 
-                let matches = if let Some(compare_fn) = compare_line {
-                    let this = JsValue::NULL;
-                    let line_num = JsValue::from_f64((to_pos + 1) as f64);
-                    let line_content = JsValue::from_str(&source_line);
-                    let op = JsValue::from_str(" ");
-                    let patch_content = JsValue::from_str(content);
-
-                    let args = js_sys::Array::new();
-                    args.push(&line_num);
-                    args.push(&line_content);
-                    args.push(&op);
-                    args.push(&patch_content);
-
-                    compare_fn
-                        .apply(&this, &args)
-                        .map(|result| result.as_bool().unwrap_or(false))
-                        .unwrap_or(false)
-                } else {
-                    source_line == content
-                };
+                let matches = compare_line(options, to_pos + 1, &source_line, ' ', content);
 
                 if matches {
                     patched.push(source_line);
@@ -379,7 +308,7 @@ fn apply_hunk_rec(
                             false,
                             patched_with_line.clone(),
                             patched_len + 1,
-                            compare_line,
+                            options,
                         )
                         .or_else(|| {
                             apply_hunk_rec(
@@ -391,7 +320,7 @@ fn apply_hunk_rec(
                                 false,
                                 patched_with_line,
                                 patched_len + 1,
-                                compare_line,
+                                options,
                             )
                         })
                     } else {
@@ -407,7 +336,7 @@ fn apply_hunk_rec(
                             false,
                             patched,
                             patched_len,
-                            compare_line,
+                            options,
                         )
                     });
                 }
@@ -426,29 +355,6 @@ fn apply_hunk_rec(
     })
 }
 
-#[wasm_bindgen(js_name = applyPatches)]
-pub fn apply_patches(uni_diff: &str, cb: &js_sys::Function) {
-    let list = match parse_patch_internal(uni_diff) {
-        Ok(v) => v,
-        Err(e) => {
-            let _ = cb.call1(
-                &JsValue::NULL,
-                &JsValue::from_str(&format!("parse error: {e}")),
-            );
-            return;
-        }
-    };
-
-    let this = JsValue::NULL;
-    for (idx, p) in list.into_iter().enumerate() {
-        let _ = cb.call2(
-            &this,
-            &JsValue::from_f64(idx as f64),
-            &serde_wasm_bindgen::to_value(&p).unwrap(),
-        );
-    }
-}
-
 #[cfg(test)]
 #[path = "apply_test.rs"]
 mod tests;
@@ -1,4 +1,4 @@
-use crate::patch::apply::{apply_patch_internal, ApplyOptions};
+use crate::patch::apply::{apply_patch, ApplyOptions};
 use crate::patch::types::{Hunk, Patch};
 use pretty_assertions::assert_eq;
 fn create_patch(old_file_name: &str, new_file_name: &str, hunks: Vec<Hunk>) -> Patch {
@@ -38,7 +38,7 @@ fn test_basic_patch_application() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline2-modified\nline3\n");
 }
 
@@ -58,7 +58,7 @@ fn test_patch_with_context() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline2\nline3-new\nline4\n");
 }
 
@@ -72,7 +72,7 @@ fn test_empty_source() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline2\n");
 }
 
@@ -94,7 +94,7 @@ fn test_patch_with_fuzz_1() {
         fuzz_factor: Some(1),
         ..Default::default()
     };
-    let result = apply_patch_internal(source, &patch, &options);
+    let result = apply_patch(source, &patch, &options);
     assert!(result.is_err());
 }
 
@@ -111,7 +111,7 @@ fn test_multiple_hunks() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline2-new\nline3\nline4\nline5-new\nline6\n");
 }
 
@@ -137,7 +137,7 @@ fn test_eof_add_newline() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline2\nline3\n");
 }
 
@@ -163,7 +163,7 @@ fn test_eof_remove_newline() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline2\nline3");
 }
 
@@ -185,7 +185,7 @@ fn test_line_ending_unix_to_win() {
         auto_convert_line_endings: Some(true),
         ..Default::default()
     };
-    let result = apply_patch_internal(source, &patch, &options);
+    let result = apply_patch(source, &patch, &options);
     assert!(result.is_err());
 }
 
@@ -208,7 +208,7 @@ fn test_patch_failure() {
         ..Default::default()
     };
 
-    let result = apply_patch_internal(source, &patch, &options);
+    let result = apply_patch(source, &patch, &options);
     assert!(result.is_err());
 }
 
@@ -222,7 +222,7 @@ fn test_apply_to_empty_file_adds_content() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline2\nline3\n");
 }
 
@@ -236,7 +236,7 @@ fn test_apply_deletion_to_file() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "");
 }
 
@@ -256,7 +256,7 @@ fn test_patch_with_trailing_context() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline2\nline3-modified\nline4\nline5\n");
 }
 
@@ -276,7 +276,7 @@ fn test_patch_with_leading_context() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline2\nline3-modified\nline4\nline5\n");
 }
 
@@ -298,7 +298,7 @@ fn test_fuzz_factor_allows_context_mismatch() {
         fuzz_factor: Some(1),
         ..Default::default()
     };
-    let result = apply_patch_internal(source, &patch, &options);
+    let result = apply_patch(source, &patch, &options);
     assert!(result.is_err());
 }
 
@@ -318,7 +318,7 @@ fn test_eof_newline_with_context() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline2-modified\n");
 }
 
@@ -341,7 +341,7 @@ fn test_patch_at_offset() {
         ..Default::default()
     };
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "header\nline1\nline2-new\nline3\nfooter\n");
 }
 
@@ -351,7 +351,7 @@ fn test_empty_patch() {
     let patch = create_patch("test.txt", "test.txt", vec![]);
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline2\nline3\n");
 }
 
@@ -371,7 +371,7 @@ fn test_windows_line_endings_preserved() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\r\nline2-modified\r\nline3\r\n");
 }
 
@@ -385,7 +385,7 @@ fn test_mixed_line_endings() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline2-modified\nline3\n");
 }
 
@@ -408,7 +408,7 @@ fn test_complex_multi_hunk_patch() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(
         result,
         "start\nline1\nline2-new\nline2.5\nline3\nmiddle\nline4-and-5\nline6\nend\n"
@@ -420,7 +420,7 @@ fn test_empty_hunk_lines() {
     let patch = create_patch("test.txt", "test.txt", vec![]);
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, source);
 }
 
@@ -445,7 +445,7 @@ fn test_eofnl_marker_detection_edge_cases() {
         )],
     );
     let options = ApplyOptions::default();
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline2-new");
     let source2 = "line1\nline2\n";
     let patch2 = create_patch(
@@ -459,7 +459,7 @@ fn test_eofnl_marker_detection_edge_cases() {
             vec!["-line2", "+line2-new", "\\ No newline at end of file"],
         )],
     );
-    let result2 = apply_patch_internal(source2, &patch2, &options).unwrap();
+    let result2 = apply_patch(source2, &patch2, &options).unwrap();
     assert_eq!(result2, "line1\nline2-new");
 }
 
@@ -482,7 +482,7 @@ fn test_context_line_processing_edge_cases() {
         ..Default::default()
     };
 
-    let result = apply_patch_internal(source, &patch, &options);
+    let result = apply_patch(source, &patch, &options);
     assert!(result.is_ok());
 }
 
@@ -496,7 +496,7 @@ fn test_deletion_line_processing() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline3\n");
     let patch_wrong = create_patch(
         "test.txt",
@@ -504,7 +504,7 @@ fn test_deletion_line_processing() {
         vec![create_hunk(2, 1, 2, 0, vec!["-wrong_line"])],
     );
 
-    let result_wrong = apply_patch_internal(source, &patch_wrong, &options);
+    let result_wrong = apply_patch(source, &patch_wrong, &options);
     assert!(result_wrong.is_err());
 }
 
@@ -524,7 +524,7 @@ fn test_insertion_context_requirements() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline2\ninserted\nline3\n");
     let patch_wrong = create_patch(
         "test.txt",
@@ -538,7 +538,7 @@ fn test_insertion_context_requirements() {
         )],
     );
 
-    let result_wrong = apply_patch_internal(source, &patch_wrong, &options);
+    let result_wrong = apply_patch(source, &patch_wrong, &options);
     assert!(result_wrong.is_err());
 }
 
@@ -552,7 +552,7 @@ fn test_distance_iterator_search() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "extra_line\nline1\nline2-modified\nline3\n");
 }
 
@@ -569,7 +569,7 @@ fn test_multiple_hunks_with_offsets() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(
         result,
         "line1\ninserted1\nline2\nline3-modified\nline4\nline5\n"
@@ -595,7 +595,7 @@ fn test_fuzz_factor_strategies() {
         ..Default::default()
     };
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
 
     assert!(result.contains("inserted"));
 }
@@ -616,7 +616,7 @@ fn test_trailing_context_trimming() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline2\ninserted\nline3\nline4\nline5\n");
 }
 
@@ -636,7 +636,7 @@ fn test_consecutive_context_tracking() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline2\ninserted\nline3\nline4\n");
 }
 
@@ -658,13 +658,13 @@ fn test_max_errors_boundary() {
         fuzz_factor: Some(1),
         ..Default::default()
     };
-    let result1 = apply_patch_internal(source, &patch, &options1);
+    let result1 = apply_patch(source, &patch, &options1);
     assert!(result1.is_ok());
     let options0 = ApplyOptions {
         fuzz_factor: Some(0),
         ..Default::default()
     };
-    let result0 = apply_patch_internal(source, &patch, &options0);
+    let result0 = apply_patch(source, &patch, &options0);
     assert!(result0.is_ok());
 }
 
@@ -678,7 +678,7 @@ fn test_empty_source_with_additions() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nline2\n");
 }
 
@@ -692,7 +692,7 @@ fn test_source_to_empty_with_deletions() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "");
 }
 
@@ -706,7 +706,7 @@ fn test_line_ending_preservation() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nmodified\n");
     assert!(result.ends_with('\n'));
 }
@@ -732,7 +732,7 @@ fn test_special_eofnl_combinations() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options).unwrap();
+    let result = apply_patch(source, &patch, &options).unwrap();
     assert_eq!(result, "line1\nchanged");
 }
 
@@ -746,7 +746,7 @@ fn test_hunk_bounds_checking() {
     );
     let options = ApplyOptions::default();
 
-    let result = apply_patch_internal(source, &patch, &options);
+    let result = apply_patch(source, &patch, &options);
     assert!(result.is_err());
 }
 
@@ -768,6 +768,56 @@ fn test_patch_preprocessing_logic() {
         fuzz_factor: Some(0),
         ..Default::default()
     };
-    let result = apply_patch_internal(source, &patch_remove_only, &options);
+    let result = apply_patch(source, &patch_remove_only, &options);
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_apply_patch_with_compare_line_closure() {
+    use crate::patch::apply_patch;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let source = "Line1\nLINE2\nline3\n";
+    let patch = create_patch(
+        "test.txt",
+        "test.txt",
+        vec![create_hunk(
+            1,
+            3,
+            1,
+            3,
+            vec![" line1", "-line2", "+changed", " line3"],
+        )],
+    );
+
+    assert!(apply_patch(source, &patch, &ApplyOptions::default()).is_err());
+
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let seen = Rc::clone(&calls);
+    let options =
+        ApplyOptions::default().with_compare_line(move |line_number, line, op, content| {
+            seen.borrow_mut().push((line_number, op));
+            line.eq_ignore_ascii_case(content)
+        });
+
+    let result = apply_patch(source, &patch, &options).unwrap();
+    assert_eq!(result, "Line1\nchanged\nline3\n");
+    assert_eq!(*calls.borrow(), vec![(1, ' '), (2, '-'), (3, ' ')]);
+}
+
+#[test]
+fn test_create_then_apply_round_trip() {
+    use crate::patch::{apply_patch, reverse_patch, structured_patch};
+
+    let old = "alpha\nbeta\ngamma\ndelta\n";
+    let new = "alpha\nBETA\ngamma\ndelta\nepsilon\n";
+    let patch = structured_patch("f", "f", old, new, None, None, &Default::default()).unwrap();
+
+    let options = ApplyOptions::default();
+    assert_eq!(apply_patch(old, &patch, &options).unwrap(), new);
+    assert_eq!(
+        apply_patch(new, &reverse_patch(&patch), &options).unwrap(),
+        old
+    );
+}
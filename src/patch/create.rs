@@ -1,53 +1,49 @@
 use serde::Deserialize;
-use wasm_bindgen::prelude::*;
 
 use crate::{
-    diff::line::diff_lines,
+    diff::{base::Change, line::diff_lines},
+    options::DiffLinesOptions,
     patch::types::{Hunk, Patch},
 };
 
-#[derive(Default, Deserialize)]
+#[derive(Default, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct StructuredOptions {
     pub context: Option<usize>,
-    pub newline_is_token: Option<bool>,
-}
-
-#[derive(Deserialize)]
-struct ChangeOwned {
-    value: String,
-    added: bool,
-    removed: bool,
+    #[serde(flatten)]
+    pub line_options: DiffLinesOptions,
 }
 
 pub(crate) fn prefix_space(lines: &[String]) -> Vec<String> {
     lines.iter().map(|l| format!(" {}", l)).collect()
 }
 
-#[wasm_bindgen(js_name = structuredPatch)]
 pub fn structured_patch(
     old_file_name: &str,
     new_file_name: &str,
     old_str: &str,
     new_str: &str,
-    old_header: Option<String>,
-    new_header: Option<String>,
-    opts: JsValue,
-) -> Result<JsValue, JsValue> {
-    let opt_rs: StructuredOptions =
-        serde_wasm_bindgen::from_value(opts.clone()).unwrap_or_default();
-    if opt_rs.newline_is_token.unwrap_or(false) {
+    old_header: Option<&str>,
+    new_header: Option<&str>,
+    opts: &StructuredOptions,
+) -> Result<Patch, String> {
+    if opts.line_options.newline_is_token {
         return Err("newlineIsToken may not be used with patch-generation functions".into());
     }
-    let context = opt_rs.context.unwrap_or(4);
+    let context = opts.context.unwrap_or(4);
 
-    let diff_val = diff_lines(old_str, new_str, opts)?;
-    let mut changes: Vec<ChangeOwned> = serde_wasm_bindgen::from_value(diff_val)?;
+    let line_options = DiffLinesOptions {
+        indent_heuristic: Some(opts.line_options.indent_heuristic.unwrap_or(true)),
+        ..opts.line_options.clone()
+    };
+    let mut changes = diff_lines(old_str, new_str, &line_options);
 
-    changes.push(ChangeOwned {
+    changes.push(Change {
         value: String::new(),
+        count: 0,
         added: false,
         removed: false,
+        position: None,
     });
 
     let mut hunks: Vec<Hunk> = Vec::new();
@@ -149,18 +145,17 @@ pub fn structured_patch(
         }
     }
 
-    let patch = Patch {
+    Ok(Patch {
         old_file_name: old_file_name.to_string(),
         new_file_name: new_file_name.to_string(),
-        old_header: old_header.unwrap_or_default(),
-        new_header: new_header.unwrap_or_default(),
+        old_header: old_header.unwrap_or_default().to_string(),
+        new_header: new_header.unwrap_or_default().to_string(),
         hunks,
         ..Default::default()
-    };
-    serde_wasm_bindgen::to_value(&patch).map_err(Into::into)
+    })
 }
 
-pub(crate) fn format_single(p: &Patch) -> String {
+pub fn format_patch(p: &Patch) -> String {
     let mut out = String::new();
     if p.old_file_name == p.new_file_name {
         out.push_str(&format!("Index: {}\n", p.old_file_name));
@@ -195,48 +190,48 @@ pub(crate) fn format_single(p: &Patch) -> String {
     out
 }
 
-#[cfg(test)]
-#[path = "create_test.rs"]
-mod tests;
-
-#[wasm_bindgen(js_name = formatPatch)]
-pub fn format_patch(val: JsValue) -> Result<String, JsValue> {
-    if val.is_string() {
-        return Ok(val.as_string().unwrap());
-    }
-    if let Ok(vec) = serde_wasm_bindgen::from_value::<Vec<Patch>>(val.clone()) {
-        return Ok(vec.iter().map(format_single).collect::<Vec<_>>().join("\n"));
-    }
-    let p: Patch = serde_wasm_bindgen::from_value(val)?;
-    Ok(format_single(&p))
+pub fn format_patches(patches: &[Patch]) -> String {
+    patches
+        .iter()
+        .map(format_patch)
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
-#[wasm_bindgen(js_name = createTwoFilesPatch)]
 pub fn create_two_files_patch(
-    old_file: &str,
-    new_file: &str,
+    old_file_name: &str,
+    new_file_name: &str,
     old_str: &str,
     new_str: &str,
-    old_header: Option<String>,
-    new_header: Option<String>,
-    opts: JsValue,
-) -> Result<String, JsValue> {
-    let patch_val = structured_patch(
-        old_file, new_file, old_str, new_str, old_header, new_header, opts,
+    old_header: Option<&str>,
+    new_header: Option<&str>,
+    opts: &StructuredOptions,
+) -> Result<String, String> {
+    let patch = structured_patch(
+        old_file_name,
+        new_file_name,
+        old_str,
+        new_str,
+        old_header,
+        new_header,
+        opts,
     )?;
-    format_patch(patch_val)
+    Ok(format_patch(&patch))
 }
 
-#[wasm_bindgen(js_name = createPatch)]
 pub fn create_patch(
     file_name: &str,
     old_str: &str,
     new_str: &str,
-    old_header: Option<String>,
-    new_header: Option<String>,
-    opts: JsValue,
-) -> Result<String, JsValue> {
+    old_header: Option<&str>,
+    new_header: Option<&str>,
+    opts: &StructuredOptions,
+) -> Result<String, String> {
     create_two_files_patch(
         file_name, file_name, old_str, new_str, old_header, new_header, opts,
     )
 }
+
+#[cfg(test)]
+#[path = "create_test.rs"]
+mod tests;
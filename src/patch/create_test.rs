@@ -1,4 +1,4 @@
-use crate::patch::create::{format_single, prefix_space};
+use crate::patch::create::{format_patch, prefix_space};
 use crate::patch::types::*;
 
 #[test]
@@ -63,7 +63,7 @@ fn test_format_single_header_tabs() {
         ..Default::default()
     };
 
-    let formatted = format_single(&patch);
+    let formatted = format_patch(&patch);
     let lines: Vec<&str> = formatted.lines().collect();
     assert!(lines[2].starts_with("--- test\t"));
     assert!(lines[3].starts_with("+++ test\t"));
@@ -398,3 +398,85 @@ fn test_range_reset_conditions() {
     let in_change_group = old_range_start != 0;
     assert!(!in_change_group);
 }
+
+#[test]
+fn test_structured_patch_native_api() {
+    use crate::patch::{create_patch, format_patch, structured_patch, StructuredOptions};
+
+    let opts = StructuredOptions {
+        context: Some(1),
+        ..Default::default()
+    };
+    let patch = structured_patch(
+        "a.txt",
+        "b.txt",
+        "one\ntwo\nthree\nfour\n",
+        "one\n2\nthree\nfour\n",
+        Some("old"),
+        None,
+        &opts,
+    )
+    .unwrap();
+
+    assert_eq!(patch.old_file_name, "a.txt");
+    assert_eq!(patch.new_file_name, "b.txt");
+    assert_eq!(patch.old_header, "old");
+    assert_eq!(patch.new_header, "");
+    assert_eq!(
+        patch.hunks,
+        vec![Hunk {
+            old_start: 1,
+            old_lines: 3,
+            new_start: 1,
+            new_lines: 3,
+            lines: vec![
+                " one".to_string(),
+                "-two".to_string(),
+                "+2".to_string(),
+                " three".to_string(),
+            ],
+        }]
+    );
+
+    let text = create_patch("a.txt", "x\n", "y\n", None, None, &Default::default()).unwrap();
+    assert_eq!(
+        text,
+        "Index: a.txt\n\
+         ===================================================================\n\
+         --- a.txt\t\n\
+         +++ a.txt\t\n\
+         @@ -1,1 +1,1 @@\n\
+         -x\n\
+         +y\n"
+    );
+    assert_eq!(
+        format_patch(
+            &structured_patch(
+                "a.txt",
+                "a.txt",
+                "x\n",
+                "y\n",
+                None,
+                None,
+                &Default::default()
+            )
+            .unwrap()
+        ),
+        text
+    );
+}
+
+#[test]
+fn test_structured_patch_rejects_newline_is_token() {
+    use crate::options::DiffLinesOptions;
+    use crate::patch::{structured_patch, StructuredOptions};
+
+    let opts = StructuredOptions {
+        context: None,
+        line_options: DiffLinesOptions {
+            newline_is_token: true,
+            ..Default::default()
+        },
+    };
+    assert!(structured_patch("a", "b", "x\n", "y\n", None, None, &opts).is_err());
+}
@@ -1,8 +1,6 @@
-use wasm_bindgen::prelude::*;
-
 use crate::patch::types::Patch;
 
-pub(crate) fn is_unix_internal(patch: &Patch) -> bool {
+pub fn is_unix_internal(patch: &Patch) -> bool {
     patch.hunks.iter().all(|h| {
         h.lines
             .iter()
@@ -10,7 +8,7 @@ pub(crate) fn is_unix_internal(patch: &Patch) -> bool {
     })
 }
 
-pub(crate) fn is_win_internal(patch: &Patch) -> bool {
+pub fn is_win_internal(patch: &Patch) -> bool {
     patch
         .hunks
         .iter()
@@ -26,7 +24,7 @@ pub(crate) fn is_win_internal(patch: &Patch) -> bool {
         })
 }
 
-pub(crate) fn win_to_unix_internal(p: &Patch) -> Patch {
+pub fn win_to_unix_internal(p: &Patch) -> Patch {
     let mut out = p.clone();
     for h in &mut out.hunks {
         for l in &mut h.lines {
@@ -38,7 +36,7 @@ pub(crate) fn win_to_unix_internal(p: &Patch) -> Patch {
     out
 }
 
-pub(crate) fn unix_to_win_internal(p: &Patch) -> Patch {
+pub fn unix_to_win_internal(p: &Patch) -> Patch {
     let mut out = p.clone();
     for h in &mut out.hunks {
         let mut nl = Vec::with_capacity(h.lines.len());
@@ -57,46 +55,15 @@ pub(crate) fn unix_to_win_internal(p: &Patch) -> Patch {
     out
 }
 
-#[wasm_bindgen(js_name = isUnix)]
-pub fn is_unix(v: JsValue) -> Result<bool, JsValue> {
-    if let Ok(vec) = serde_wasm_bindgen::from_value::<Vec<Patch>>(v.clone()) {
-        return Ok(vec.iter().all(is_unix_internal));
-    }
-    let p: Patch = serde_wasm_bindgen::from_value(v)?;
-    Ok(is_unix_internal(&p))
-}
-
-#[wasm_bindgen(js_name = isWin)]
-pub fn is_win(v: JsValue) -> Result<bool, JsValue> {
-    if let Ok(vec) = serde_wasm_bindgen::from_value::<Vec<Patch>>(v.clone()) {
-        let some = vec.iter().any(|p| {
-            p.hunks
-                .iter()
-                .any(|h| h.lines.iter().any(|l| l.ends_with('\r')))
-        });
-        let all = vec.iter().all(is_win_internal);
-        return Ok(some && all);
-    }
-    let p: Patch = serde_wasm_bindgen::from_value(v)?;
-    Ok(is_win_internal(&p))
-}
-
-#[wasm_bindgen(js_name = winToUnix)]
-pub fn win_to_unix(v: JsValue) -> Result<JsValue, JsValue> {
-    if let Ok(vec) = serde_wasm_bindgen::from_value::<Vec<Patch>>(v.clone()) {
-        let conv: Vec<Patch> = vec.iter().map(win_to_unix_internal).collect();
-        return serde_wasm_bindgen::to_value(&conv).map_err(Into::into);
-    }
-    let p: Patch = serde_wasm_bindgen::from_value(v)?;
-    serde_wasm_bindgen::to_value(&win_to_unix_internal(&p)).map_err(Into::into)
+pub fn all_unix_internal(patches: &[Patch]) -> bool {
+    patches.iter().all(is_unix_internal)
 }
 
-#[wasm_bindgen(js_name = unixToWin)]
-pub fn unix_to_win(v: JsValue) -> Result<JsValue, JsValue> {
-    if let Ok(vec) = serde_wasm_bindgen::from_value::<Vec<Patch>>(v.clone()) {
-        let conv: Vec<Patch> = vec.iter().map(unix_to_win_internal).collect();
-        return serde_wasm_bindgen::to_value(&conv).map_err(Into::into);
-    }
-    let p: Patch = serde_wasm_bindgen::from_value(v)?;
-    serde_wasm_bindgen::to_value(&unix_to_win_internal(&p)).map_err(Into::into)
+pub fn all_win_internal(patches: &[Patch]) -> bool {
+    let some = patches.iter().any(|p| {
+        p.hunks
+            .iter()
+            .any(|h| h.lines.iter().any(|l| l.ends_with('\r')))
+    });
+    some && patches.iter().all(is_win_internal)
 }
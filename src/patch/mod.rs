@@ -12,8 +12,10 @@ mod parse_test;
 #[cfg(test)]
 mod reverse_test;
 
-pub use apply::{apply_patch, apply_patches, ApplyOptions};
+pub use apply::{apply_patch, ApplyOptions, CompareLine};
 pub use create::{
-    create_patch, create_two_files_patch, format_patch, structured_patch, StructuredOptions,
+    create_patch, create_two_files_patch, format_patch, format_patches, structured_patch,
+    StructuredOptions,
 };
-pub use reverse::reverse_patch;
+pub use parse::parse_patch;
+pub use reverse::{reverse_patch, reverse_patches};
@@ -1,9 +1,8 @@
 use regex::Regex;
-use wasm_bindgen::prelude::*;
 
 use crate::patch::types::{Hunk, Patch};
 
-pub fn parse_patch_internal(uni_diff: &str) -> Result<Vec<Patch>, String> {
+pub fn parse_patch(uni_diff: &str) -> Result<Vec<Patch>, String> {
     if uni_diff.is_empty() {
         return Ok(vec![]);
     }
@@ -201,9 +200,3 @@ pub fn parse_patch_internal(uni_diff: &str) -> Result<Vec<Patch>, String> {
 
     Ok(out)
 }
-
-#[wasm_bindgen(js_name = parsePatch)]
-pub fn parse_patch(uni_diff: &str) -> Result<JsValue, JsValue> {
-    let parsed = parse_patch_internal(uni_diff).map_err(|e| JsValue::from_str(&e))?;
-    serde_wasm_bindgen::to_value(&parsed).map_err(Into::into)
-}
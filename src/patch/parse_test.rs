@@ -1,4 +1,4 @@
-use crate::patch::parse::parse_patch_internal;
+use crate::patch::parse::parse_patch;
 use crate::patch::types::{Hunk, Patch};
 use pretty_assertions::assert_eq;
 fn create_patch(hunks: Vec<Hunk>) -> Patch {
@@ -62,7 +62,7 @@ fn test_basic_patch() {
         vec![" line2", " line3", "+line4", " line5"],
     )])];
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result, expected);
 }
 
@@ -80,7 +80,7 @@ fn test_single_line_hunks() {
         vec!["-line3", "+line4"],
     )])];
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result, expected);
 }
 
@@ -102,7 +102,7 @@ fn test_multiple_hunks() {
         create_hunk(4, 4, 1, 3, vec![" line2", " line3", "-line4", " line5"]),
     ])];
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result, expected);
 }
 
@@ -133,7 +133,7 @@ fn test_single_index() {
         )],
     )];
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result, expected);
 }
 
@@ -189,7 +189,7 @@ Index: test2
         ),
     ];
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result, expected);
 }
 
@@ -241,7 +241,7 @@ fn test_multiple_files_no_index() {
         ),
     ];
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result, expected);
 }
 
@@ -272,7 +272,7 @@ fn test_filenames_with_quotes() {
         )],
     )];
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result, expected);
 }
 
@@ -290,7 +290,7 @@ fn test_added_eofnl() {
         vec!["-line5", "\\ No newline at end of file"],
     )])];
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result, expected);
 }
 
@@ -308,7 +308,7 @@ fn test_removed_eofnl() {
         vec!["+line5", "\\ No newline at end of file"],
     )])];
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result, expected);
 }
 
@@ -327,7 +327,7 @@ fn test_context_no_eofnl() {
         vec!["+line4", " line5", "\\ No newline at end of file"],
     )])];
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result, expected);
 }
 
@@ -337,7 +337,7 @@ fn test_empty_patch() {
 
     let expected = vec![create_patch(vec![create_hunk(1, 0, 1, 0, vec![])])];
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result, expected);
 }
 
@@ -375,7 +375,7 @@ fn test_vertical_tabs() {
         )],
     )];
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result, expected);
 }
 
@@ -414,7 +414,7 @@ fn test_trailing_newlines() {
         )],
     )];
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result, expected);
 }
 
@@ -440,7 +440,7 @@ fn test_add_remove_prefixed_lines() {
         ],
     )];
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result, expected);
 }
 
@@ -456,7 +456,7 @@ line3
 +line4
  line5";
 
-    let result = parse_patch_internal(input);
+    let result = parse_patch(input);
     assert!(result.is_err());
     assert_eq!(
         result.err().unwrap(),
@@ -491,7 +491,7 @@ fn test_oom_case() {
         )],
     )];
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result, expected);
 }
 
@@ -500,7 +500,7 @@ fn test_sanity_check_line_count() {
     let input = "@@ -1 +1,4 @@
  line2
 +line3";
-    let result = parse_patch_internal(input);
+    let result = parse_patch(input);
     assert!(result.is_err());
 }
 
@@ -517,7 +517,7 @@ fn test_invalid_input() {
         vec![],
     )];
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result, expected);
 }
 
@@ -526,7 +526,7 @@ fn test_empty_input() {
     let input = "";
     let expected: Vec<Patch> = vec![];
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result, expected);
 }
 
@@ -539,7 +539,7 @@ fn test_parse_header_edge_cases() {
 @@ -1 +1 @@
  unchanged";
 
-    let result1 = parse_patch_internal(input1).unwrap();
+    let result1 = parse_patch(input1).unwrap();
     assert_eq!(result1[0].index, Some("simple_file.txt".to_string()));
     let input2 = "diff -r abc123 file.txt
 --- old_file.txt\theader
@@ -547,12 +547,12 @@ fn test_parse_header_edge_cases() {
 @@ -1 +1 @@
  unchanged";
 
-    let result2 = parse_patch_internal(input2).unwrap();
+    let result2 = parse_patch(input2).unwrap();
     assert_eq!(result2[0].index, Some("file.txt".to_string()));
     let input3 = "@@ -1 +1 @@
  unchanged";
 
-    let result3 = parse_patch_internal(input3).unwrap();
+    let result3 = parse_patch(input3).unwrap();
     assert_eq!(result3[0].index, None);
     assert_eq!(result3[0].old_file_name, "");
     assert_eq!(result3[0].new_file_name, "");
@@ -565,7 +565,7 @@ fn test_file_header_parsing_edge_cases() {
 @@ -1 +1 @@
  unchanged";
 
-    let result1 = parse_patch_internal(input1).unwrap();
+    let result1 = parse_patch(input1).unwrap();
     assert_eq!(result1[0].old_file_name, "/path/to/file.txt");
     assert_eq!(result1[0].new_file_name, "/path/to/new_file.txt");
     assert_eq!(result1[0].old_header, "2023-01-01 12:00:00.000000000 +0000");
@@ -575,7 +575,7 @@ fn test_file_header_parsing_edge_cases() {
 @@ -1 +1 @@
  unchanged";
 
-    let result2 = parse_patch_internal(input2).unwrap();
+    let result2 = parse_patch(input2).unwrap();
     assert_eq!(result2[0].old_file_name, "file_a");
     assert_eq!(result2[0].new_file_name, "file_b");
     assert_eq!(result2[0].old_header, "");
@@ -585,7 +585,7 @@ fn test_file_header_parsing_edge_cases() {
 @@ -1 +1 @@
  unchanged";
 
-    let result3 = parse_patch_internal(input3).unwrap();
+    let result3 = parse_patch(input3).unwrap();
     assert_eq!(result3[0].old_file_name, "file\\with\\backslashes.txt");
     assert_eq!(result3[0].new_file_name, "file\\with\\backslashes.txt");
 }
@@ -597,7 +597,7 @@ fn test_hunk_header_variations() {
 +added1
 +added2";
 
-    let result1 = parse_patch_internal(input1).unwrap();
+    let result1 = parse_patch(input1).unwrap();
     let hunk1 = &result1[0].hunks[0];
     assert_eq!(hunk1.old_start, 5);
     assert_eq!(hunk1.old_lines, 1);
@@ -607,7 +607,7 @@ fn test_hunk_header_variations() {
 -old
 +new";
 
-    let result2 = parse_patch_internal(input2).unwrap();
+    let result2 = parse_patch(input2).unwrap();
     let hunk2 = &result2[0].hunks[0];
     assert_eq!(hunk2.old_start, 3);
     assert_eq!(hunk2.old_lines, 1);
@@ -617,7 +617,7 @@ fn test_hunk_header_variations() {
 +line1
 +line2";
 
-    let result3 = parse_patch_internal(input3).unwrap();
+    let result3 = parse_patch(input3).unwrap();
     let hunk3 = &result3[0].hunks[0];
     assert_eq!(hunk3.old_start, 1);
     assert_eq!(hunk3.old_lines, 0);
@@ -632,7 +632,7 @@ fn test_quoted_filename_edge_cases() {
 @@ -1 +1 @@
  unchanged";
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(
         result[0].old_file_name,
         "file with spaces and \"quotes\".txt"
@@ -643,7 +643,7 @@ fn test_quoted_filename_edge_cases() {
 @@ -1 +1 @@
  unchanged";
 
-    let result2 = parse_patch_internal(input2).unwrap();
+    let result2 = parse_patch(input2).unwrap();
     assert_eq!(result2[0].old_file_name, "\"unbalanced_quote.txt");
     assert_eq!(result2[0].new_file_name, "normal_file.txt");
 }
@@ -653,14 +653,14 @@ fn test_hunk_line_counting_edge_cases() {
     let input1 = "@@ -1,1 +1,0 @@
 -removed_line";
 
-    let result1 = parse_patch_internal(input1).unwrap();
+    let result1 = parse_patch(input1).unwrap();
     let hunk1 = &result1[0].hunks[0];
     assert_eq!(hunk1.old_lines, 1);
     assert_eq!(hunk1.new_lines, 0);
     let input2 = "@@ -1,0 +1,1 @@
 +added_line";
 
-    let result2 = parse_patch_internal(input2).unwrap();
+    let result2 = parse_patch(input2).unwrap();
     let hunk2 = &result2[0].hunks[0];
     assert_eq!(hunk2.old_lines, 0);
     assert_eq!(hunk2.new_lines, 1);
@@ -673,7 +673,7 @@ fn test_line_operation_detection() {
 -removed_line
 +added_line";
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     let lines = &result[0].hunks[0].lines;
 
     assert_eq!(lines[0], " context_line");
@@ -687,7 +687,7 @@ fn test_line_count_mismatch_errors() {
 -line1
 +new_line";
 
-    let result1 = parse_patch_internal(input1);
+    let result1 = parse_patch(input1);
     assert!(result1.is_err());
     assert!(result1
         .unwrap_err()
@@ -696,7 +696,7 @@ fn test_line_count_mismatch_errors() {
 -old_line
 +new_line";
 
-    let result2 = parse_patch_internal(input2);
+    let result2 = parse_patch(input2);
     assert!(result2.is_err());
     assert!(result2
         .unwrap_err()
@@ -708,7 +708,7 @@ fn test_empty_line_handling() {
     let input =
         "Index: test\n\n--- old\theader\n+++ new\theader\n\n@@ -1,3 +1,3 @@\n line1\n \n line2";
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result[0].index, Some("test".to_string()));
     assert_eq!(result[0].hunks[0].lines.len(), 3);
     assert_eq!(result[0].hunks[0].lines[1], " ");
@@ -720,7 +720,7 @@ fn test_backslash_continuation_lines() {
  line1
 \\ No newline at end of file";
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     let lines = &result[0].hunks[0].lines;
 
     assert_eq!(lines.len(), 2);
@@ -741,7 +741,7 @@ Index: file2
 @@ -1 +1 @@
  line2";
 
-    let result = parse_patch_internal(input).unwrap();
+    let result = parse_patch(input).unwrap();
     assert_eq!(result.len(), 2);
     assert_eq!(result[0].old_file_name, "file1");
     assert_eq!(result[1].index, Some("file2".to_string()));
@@ -775,11 +775,11 @@ fn test_regex_patterns() {
 fn test_error_recovery_behavior() {
     let input1 = "@@ -1,1 +1,1 @@
 invalid_line_without_prefix";
-    let result1 = parse_patch_internal(input1);
+    let result1 = parse_patch(input1);
     assert!(result1.is_err());
     let input2 =
         "random text\nmore random text\nIndex: test\n--- old\n+++ new\n@@ -1 +1 @@\n line1";
-    let result2 = parse_patch_internal(input2);
+    let result2 = parse_patch(input2);
 
     assert!(result2.is_ok());
 }
@@ -1,8 +1,6 @@
-use wasm_bindgen::prelude::*;
-
 use crate::patch::types::{Hunk, Patch};
 
-pub fn reverse_single(p: &Patch) -> Patch {
+pub fn reverse_patch(p: &Patch) -> Patch {
     let mut out = p.clone();
     std::mem::swap(&mut out.old_file_name, &mut out.new_file_name);
     std::mem::swap(&mut out.old_header, &mut out.new_header);
@@ -33,13 +31,6 @@ pub fn reverse_single(p: &Patch) -> Patch {
     out
 }
 
-#[wasm_bindgen(js_name = reversePatch)]
-pub fn reverse_patch(val: JsValue) -> Result<JsValue, JsValue> {
-    if let Ok(vec) = serde_wasm_bindgen::from_value::<Vec<Patch>>(val.clone()) {
-        let mut rev: Vec<Patch> = vec.iter().map(reverse_single).collect();
-        rev.reverse();
-        return serde_wasm_bindgen::to_value(&rev).map_err(Into::into);
-    }
-    let patch: Patch = serde_wasm_bindgen::from_value(val)?;
-    serde_wasm_bindgen::to_value(&reverse_single(&patch)).map_err(Into::into)
+pub fn reverse_patches(patches: &[Patch]) -> Vec<Patch> {
+    patches.iter().rev().map(reverse_patch).collect()
 }
@@ -1,4 +1,4 @@
-use crate::patch::reverse::reverse_single;
+use crate::patch::reverse::reverse_patch;
 use crate::patch::types::{Hunk, Patch};
 use pretty_assertions::assert_eq;
 fn create_patch(
@@ -50,7 +50,7 @@ fn test_reverse_single_patch_basic() {
         )],
     );
 
-    let reversed = reverse_single(&original_patch);
+    let reversed = reverse_patch(&original_patch);
     assert_eq!(reversed.old_file_name, "file2");
     assert_eq!(reversed.new_file_name, "file1");
     assert_eq!(reversed.old_header, "");
@@ -89,7 +89,7 @@ fn test_reverse_single_patch_with_headers() {
         )],
     };
 
-    let reversed = reverse_single(&original_patch);
+    let reversed = reverse_patch(&original_patch);
 
     assert_eq!(reversed.old_file_name, "b/CONTRIBUTING.md");
     assert_eq!(reversed.new_file_name, "a/CONTRIBUTING.md");
@@ -141,7 +141,7 @@ fn test_reverse_single_patch_complex_changes() {
         )],
     );
 
-    let reversed = reverse_single(&original_patch);
+    let reversed = reverse_patch(&original_patch);
 
     assert_eq!(reversed.old_file_name, "new.txt");
     assert_eq!(reversed.new_file_name, "old.txt");
@@ -174,7 +174,7 @@ fn test_reverse_single_patch_empty_lines() {
         vec![create_hunk(1, 3, 1, 3, vec!["+", " normal line", "-"])],
     );
 
-    let reversed = reverse_single(&original_patch);
+    let reversed = reverse_patch(&original_patch);
 
     let expected_lines = vec!["-", " normal line", "+"];
     assert_eq!(reversed.hunks[0].lines, expected_lines);
@@ -227,7 +227,7 @@ fn test_reverse_patch_array_behavior() {
     );
 
     let original_patches = [patch1.clone(), patch2.clone()];
-    let mut reversed: Vec<Patch> = original_patches.iter().map(reverse_single).collect();
+    let mut reversed: Vec<Patch> = original_patches.iter().map(reverse_patch).collect();
     reversed.reverse();
     assert_eq!(reversed.len(), 2);
     assert_eq!(reversed[0].old_file_name, "b/README.md");
@@ -258,7 +258,7 @@ fn test_reverse_patch_multiple_hunks() {
         ],
     );
 
-    let reversed = reverse_single(&original_patch);
+    let reversed = reverse_patch(&original_patch);
 
     assert_eq!(reversed.hunks.len(), 2);
     let hunk1 = &reversed.hunks[0];
@@ -287,7 +287,7 @@ fn test_reverse_patch_context_only() {
         vec![create_hunk(1, 3, 1, 3, vec![" line1", " line2", " line3"])],
     );
 
-    let reversed = reverse_single(&original_patch);
+    let reversed = reverse_patch(&original_patch);
 
     assert_eq!(reversed.old_file_name, "same.txt");
     assert_eq!(reversed.new_file_name, "same.txt");
@@ -305,7 +305,7 @@ fn test_reverse_patch_context_only() {
 fn test_reverse_patch_edge_case_empty_patch() {
     let original_patch = create_patch("empty1", "empty2", "", "", vec![]);
 
-    let reversed = reverse_single(&original_patch);
+    let reversed = reverse_patch(&original_patch);
 
     assert_eq!(reversed.old_file_name, "empty2");
     assert_eq!(reversed.new_file_name, "empty1");
@@ -328,7 +328,7 @@ fn test_reverse_patch_only_additions() {
         )],
     );
 
-    let reversed = reverse_single(&original_patch);
+    let reversed = reverse_patch(&original_patch);
 
     let hunk = &reversed.hunks[0];
     assert_eq!(hunk.old_start, 1);
@@ -356,7 +356,7 @@ fn test_reverse_patch_only_deletions() {
         )],
     );
 
-    let reversed = reverse_single(&original_patch);
+    let reversed = reverse_patch(&original_patch);
 
     let hunk = &reversed.hunks[0];
     assert_eq!(hunk.old_start, 1);
@@ -384,7 +384,7 @@ fn test_reverse_patch_compatibility() {
         )],
     );
 
-    let reversed = reverse_single(&original_patch);
+    let reversed = reverse_patch(&original_patch);
     assert_eq!(reversed.old_file_name, "file2");
     assert_eq!(reversed.new_file_name, "file1");
     assert_eq!(reversed.old_header, "");
@@ -398,3 +398,23 @@ fn test_reverse_patch_compatibility() {
     let expected_lines = vec![" line1", " line2", "+line3", "-line5", " line4"];
     assert_eq!(hunk.lines, expected_lines);
 }
+
+#[test]
+fn test_reverse_patches_native_api() {
+    use crate::patch::{reverse_patch, reverse_patches};
+
+    let first = create_patch(
+        "a",
+        "a",
+        "",
+        "",
+        vec![create_hunk(1, 1, 1, 1, vec!["-x", "+y"])],
+    );
+    let second = create_patch("b", "b", "", "", vec![create_hunk(1, 0, 1, 1, vec!["+z"])]);
+
+    let reversed = reverse_patches(&[first.clone(), second.clone()]);
+    assert_eq!(reversed.len(), 2);
+    assert_eq!(reversed[0], reverse_patch(&second));
+    assert_eq!(reversed[1], reverse_patch(&first));
+    assert_eq!(reverse_patch(&reverse_patch(&first)), first);
+}
@@ -0,0 +1,37 @@
+/// Wall-clock deadline for bounding diff time. Uses `Date.now()` in wasm
+/// builds, where `std::time::Instant` is unavailable.
+#[derive(Clone, Copy, Debug)]
+pub struct Deadline {
+    #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+    at: f64,
+    #[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+    at: std::time::Instant,
+}
+
+impl Deadline {
+    #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+    pub fn after_ms(ms: u64) -> Self {
+        Self {
+            at: js_sys::Date::now() + ms as f64,
+        }
+    }
+
+    #[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+    pub fn after_ms(ms: u64) -> Self {
+        Self {
+            at: std::time::Instant::now() + std::time::Duration::from_millis(ms),
+        }
+    }
+
+    #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+    #[inline]
+    pub fn expired(&self) -> bool {
+        js_sys::Date::now() >= self.at
+    }
+
+    #[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+    #[inline]
+    pub fn expired(&self) -> bool {
+        std::time::Instant::now() >= self.at
+    }
+}
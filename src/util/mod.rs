@@ -1,4 +1,5 @@
 pub mod array;
+pub mod deadline;
 pub mod distance_iterator;
 pub mod params;
 pub mod string;
@@ -0,0 +1,353 @@
+use js_sys::{Function, Promise, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::AbortSignal;
+
+use super::{comparator, from_js, normalizers, ops_to_js, ops_unit, to_js, words_options, Thrown};
+use crate::{
+    diff::{
+        base::{Change, Options, Tokeniser},
+        indent::indent_heuristic,
+        ops::restore_cr,
+        word, CharTokenizer, CodeTokenizer, CodeWordTokenizer, CssTokenizer, Granularity,
+        GraphemeTokenizer, LineTokenizer, OpUnit, PooledDiff, SentenceBoundaries,
+        SentenceTokenizer, UnicodeSentenceTokenizer, UnicodeWordTokenizer, WordBoundaries,
+        WordTokenizer, WordWithSpaceTokenizer,
+    },
+    options::{
+        DiffCodeOptions, DiffLinesOptions, DiffOptions, DiffSentencesOptions, DiffWordsOptions,
+    },
+    util::deadline::Deadline,
+};
+
+/// Time spent diffing before yielding back to the event loop.
+const SLICE_MS: u64 = 16;
+/// Search rounds run between clock checks inside a slice.
+const ROUNDS_PER_CHECK: usize = 32;
+
+/// `onProgress` callback, `signal` and `output: 'ops'` unit taken from the
+/// options object, and the exceptions of its `comparator`.
+struct Control {
+    on_progress: Option<Function>,
+    signal: Option<AbortSignal>,
+    ops: Option<OpUnit>,
+    thrown: Thrown,
+}
+
+impl Control {
+    fn from_opts(opts: &JsValue, thrown: Thrown) -> Self {
+        if !opts.is_object() {
+            return Self {
+                on_progress: None,
+                signal: None,
+                ops: None,
+                thrown,
+            };
+        }
+        let get = |key: &str| {
+            Reflect::get(opts, &JsValue::from_str(key))
+                .ok()
+                .filter(|v| !v.is_undefined() && !v.is_null())
+        };
+        Self {
+            on_progress: get("onProgress").and_then(|v| v.dyn_into().ok()),
+            signal: get("signal").map(|v| v.unchecked_into()),
+            ops: ops_unit(opts),
+            thrown,
+        }
+    }
+
+    fn check_aborted(&self) -> Result<(), JsValue> {
+        match &self.signal {
+            Some(signal) if signal.aborted() => {
+                let reason = signal.reason();
+                if reason.is_undefined() {
+                    let err = js_sys::Error::new("The operation was aborted");
+                    err.set_name("AbortError");
+                    Err(err.into())
+                } else {
+                    Err(reason)
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn progress(&self, edit_length: usize) -> Result<(), JsValue> {
+        if let Some(cb) = &self.on_progress {
+            cb.call1(&JsValue::NULL, &JsValue::from_f64(edit_length as f64))?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolves on a later macrotask so the host can render and handle input.
+async fn yield_now() -> Result<(), JsValue> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        let set_timeout = Reflect::get(&js_sys::global(), &JsValue::from_str("setTimeout"))
+            .ok()
+            .and_then(|f| f.dyn_into::<Function>().ok());
+        let scheduled = match set_timeout {
+            Some(f) => f.call2(&JsValue::NULL, &resolve, &JsValue::from_f64(0.0)),
+            None => resolve.call0(&JsValue::NULL),
+        };
+        if let Err(e) = scheduled {
+            let _ = reject.call1(&JsValue::NULL, &e);
+        }
+    });
+    JsFuture::from(promise).await.map(|_| ())
+}
+
+/// Runs `diff` in time slices, yielding between them, until it finishes or
+/// the signal aborts.
+async fn run<'a, T: Tokeniser<'a>>(
+    mut diff: PooledDiff<'a, T>,
+    old: &'a str,
+    new_: &'a str,
+    control: &Control,
+) -> Result<JsValue, JsValue> {
+    match control.ops {
+        Some(unit) => {
+            let step = |diff: &mut PooledDiff<'a, T>| diff.resume_ops(ROUNDS_PER_CHECK, unit);
+            Ok(ops_to_js(
+                &slices(&mut diff, old, new_, control, step).await?,
+            ))
+        }
+        None => to_js(&changes(diff, old, new_, control).await?),
+    }
+}
+
+async fn changes<'a, T: Tokeniser<'a>>(
+    mut diff: PooledDiff<'a, T>,
+    old: &'a str,
+    new_: &'a str,
+    control: &Control,
+) -> Result<Vec<Change>, JsValue> {
+    let step = |diff: &mut PooledDiff<'a, T>| diff.resume(ROUNDS_PER_CHECK);
+    slices(&mut diff, old, new_, control, step).await
+}
+
+async fn slices<'a, T: Tokeniser<'a>, R>(
+    diff: &mut PooledDiff<'a, T>,
+    old: &'a str,
+    new_: &'a str,
+    control: &Control,
+    step: impl Fn(&mut PooledDiff<'a, T>) -> Option<R>,
+) -> Result<R, JsValue> {
+    control.check_aborted()?;
+    diff.start(old, new_);
+    loop {
+        let slice = Deadline::after_ms(SLICE_MS);
+        while !slice.expired() {
+            let result = step(diff);
+            control.thrown.check(Ok(()))?;
+            if let Some(result) = result {
+                return Ok(result);
+            }
+        }
+        control.progress(diff.edit_length())?;
+        yield_now().await?;
+        control.check_aborted()?;
+    }
+}
+
+#[wasm_bindgen(js_name = diffCharsAsync)]
+pub async fn diff_chars_async(
+    old_str: String,
+    new_str: String,
+    opts: JsValue,
+) -> Result<JsValue, JsValue> {
+    let (comparator, thrown) = comparator(&opts);
+    let o = DiffOptions {
+        comparator,
+        normalizers: normalizers(&opts)?,
+        ..from_js(opts.clone())
+    };
+    let control = Control::from_opts(&opts, thrown);
+    match o.granularity {
+        Granularity::Codepoint => {
+            let diff = PooledDiff::new(CharTokenizer, (&o).into());
+            run(diff, &old_str, &new_str, &control).await
+        }
+        Granularity::Grapheme => {
+            let diff = PooledDiff::new(GraphemeTokenizer, (&o).into());
+            run(diff, &old_str, &new_str, &control).await
+        }
+    }
+}
+
+#[wasm_bindgen(js_name = diffWordsAsync)]
+pub async fn diff_words_async(
+    old: String,
+    new_: String,
+    opts: JsValue,
+) -> Result<JsValue, JsValue> {
+    let (comparator, thrown) = comparator(&opts);
+    let wo = words_options(opts.clone(), comparator)?;
+    let base: Options = (&wo).into();
+    let control = Control::from_opts(&opts, thrown);
+    if control.ops.is_none() && word::inputs_equal(&old, &new_, &base) {
+        return to_js(&word::diff_words(&old, &new_, &wo));
+    }
+    if wo.ignore_whitespace == Some(false) {
+        let diff = PooledDiff::new(WordWithSpaceTokenizer, base);
+        return run(diff, &old, &new_, &control).await;
+    }
+    match wo.word_boundaries {
+        WordBoundaries::Jsdiff => {
+            run(PooledDiff::new(WordTokenizer, base), &old, &new_, &control).await
+        }
+        WordBoundaries::Unicode => {
+            let diff = PooledDiff::new(UnicodeWordTokenizer, base);
+            run(diff, &old, &new_, &control).await
+        }
+        WordBoundaries::Code => {
+            run(
+                PooledDiff::new(CodeWordTokenizer, base),
+                &old,
+                &new_,
+                &control,
+            )
+            .await
+        }
+    }
+}
+
+#[wasm_bindgen(js_name = diffWordsWithSpaceAsync)]
+pub async fn diff_words_with_space_async(
+    old: String,
+    new_: String,
+    opts: JsValue,
+) -> Result<JsValue, JsValue> {
+    let (comparator, thrown) = comparator(&opts);
+    let wo = DiffWordsOptions {
+        comparator,
+        normalizers: normalizers(&opts)?,
+        ..from_js(opts.clone())
+    };
+    let base: Options = (&wo).into();
+    let control = Control::from_opts(&opts, thrown);
+    if control.ops.is_none() && word::inputs_equal(&old, &new_, &base) {
+        return to_js(&word::diff_words_with_space(&old, &new_, &wo));
+    }
+    let diff = PooledDiff::new(WordWithSpaceTokenizer, base);
+    run(diff, &old, &new_, &control).await
+}
+
+#[wasm_bindgen(js_name = diffLinesAsync)]
+pub async fn diff_lines_async(
+    old_str: String,
+    new_str: String,
+    opts: JsValue,
+) -> Result<JsValue, JsValue> {
+    let (comparator, thrown) = comparator(&opts);
+    let lo = DiffLinesOptions {
+        comparator,
+        normalizers: normalizers(&opts)?,
+        ..from_js(opts.clone())
+    };
+    lines(old_str, new_str, lo, &Control::from_opts(&opts, thrown)).await
+}
+
+#[wasm_bindgen(js_name = diffTrimmedLinesAsync)]
+pub async fn diff_trimmed_lines_async(
+    old_str: String,
+    new_str: String,
+    opts: JsValue,
+) -> Result<JsValue, JsValue> {
+    let (comparator, thrown) = comparator(&opts);
+    let lo = DiffLinesOptions {
+        ignore_whitespace: true,
+        comparator,
+        normalizers: normalizers(&opts)?,
+        ..from_js(opts.clone())
+    };
+    lines(old_str, new_str, lo, &Control::from_opts(&opts, thrown)).await
+}
+
+async fn lines(
+    old_str: String,
+    new_str: String,
+    lo: DiffLinesOptions,
+    control: &Control,
+) -> Result<JsValue, JsValue> {
+    let (old, new_) = if lo.strip_trailing_cr {
+        (old_str.replace("\r\n", "\n"), new_str.replace("\r\n", "\n"))
+    } else {
+        (old_str.clone(), new_str.clone())
+    };
+    let mut diff = PooledDiff::new(LineTokenizer::new(&lo), (&lo).into());
+    if let Some(unit) = control.ops {
+        let step =
+            |diff: &mut PooledDiff<'_, LineTokenizer>| diff.resume_ops(ROUNDS_PER_CHECK, unit);
+        let mut ops = slices(&mut diff, &old, &new_, control, step).await?;
+        if lo.strip_trailing_cr {
+            restore_cr(&mut ops, &old_str, &new_str, unit);
+        }
+        return Ok(ops_to_js(&ops));
+    }
+    let changes = changes(diff, &old, &new_, control).await?;
+    if lo.indent_heuristic == Some(true) {
+        let changes = indent_heuristic(&old, &new_, changes, &lo);
+        return control.thrown.check(to_js(&changes));
+    }
+    to_js(&changes)
+}
+
+#[wasm_bindgen(js_name = diffSentencesAsync)]
+pub async fn diff_sentences_async(
+    old_str: String,
+    new_str: String,
+    opts: JsValue,
+) -> Result<JsValue, JsValue> {
+    let (comparator, thrown) = comparator(&opts);
+    let so = DiffSentencesOptions {
+        comparator,
+        normalizers: normalizers(&opts)?,
+        ..from_js(opts.clone())
+    };
+    let control = Control::from_opts(&opts, thrown);
+    match so.sentence_boundaries {
+        SentenceBoundaries::Jsdiff => {
+            let diff = PooledDiff::new(SentenceTokenizer, (&so).into());
+            run(diff, &old_str, &new_str, &control).await
+        }
+        SentenceBoundaries::Unicode => {
+            let diff = PooledDiff::new(UnicodeSentenceTokenizer::new(&so), (&so).into());
+            run(diff, &old_str, &new_str, &control).await
+        }
+    }
+}
+
+#[wasm_bindgen(js_name = diffCssAsync)]
+pub async fn diff_css_async(
+    old_str: String,
+    new_str: String,
+    opts: JsValue,
+) -> Result<JsValue, JsValue> {
+    let (comparator, thrown) = comparator(&opts);
+    let o = DiffOptions {
+        comparator,
+        normalizers: normalizers(&opts)?,
+        ..from_js(opts.clone())
+    };
+    let diff = PooledDiff::new(CssTokenizer, (&o).into());
+    run(diff, &old_str, &new_str, &Control::from_opts(&opts, thrown)).await
+}
+
+#[wasm_bindgen(js_name = diffCodeAsync)]
+pub async fn diff_code_async(
+    old_str: String,
+    new_str: String,
+    opts: JsValue,
+) -> Result<JsValue, JsValue> {
+    let (comparator, thrown) = comparator(&opts);
+    let co = DiffCodeOptions {
+        comparator,
+        normalizers: normalizers(&opts)?,
+        ..from_js(opts.clone())
+    };
+    let diff = PooledDiff::new(CodeTokenizer::new(co.language), (&co).into());
+    run(diff, &old_str, &new_str, &Control::from_opts(&opts, thrown)).await
+}
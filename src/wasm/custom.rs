@@ -0,0 +1,138 @@
+use js_sys::{Array, Function, Object, Reflect};
+use std::borrow::Cow;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::{from_js, js_diff_spans, normalizers};
+use crate::{
+    diff::{self, base::Options, case::fold_case, normalize::normalize},
+    options::DiffOptions,
+};
+
+/// Differ built by `createDiff` from JS hooks, like a jsdiff `Diff`
+/// subclass. Tokens come from the JS `tokenize` and are diffed by the Myers
+/// engine.
+#[wasm_bindgen(js_name = CustomDiff)]
+pub struct CustomDiff {
+    tokenize: Function,
+    equals: Option<Function>,
+    join: Option<Function>,
+    post_process: Option<Function>,
+}
+
+fn hook(spec: &JsValue, name: &str) -> Result<Option<Function>, JsValue> {
+    let value = Reflect::get(spec, &JsValue::from_str(name))?;
+    if value.is_undefined() || value.is_null() {
+        return Ok(None);
+    }
+    value
+        .dyn_into::<Function>()
+        .map(Some)
+        .map_err(|_| JsValue::from_str(&format!("{name} must be a function")))
+}
+
+#[wasm_bindgen(js_name = createDiff)]
+pub fn create_diff(spec: JsValue) -> Result<CustomDiff, JsValue> {
+    if !spec.is_object() {
+        return Err(JsValue::from_str("createDiff expects an object of hooks"));
+    }
+    Ok(CustomDiff {
+        tokenize: hook(&spec, "tokenize")?
+            .ok_or_else(|| JsValue::from_str("tokenize is required"))?,
+        equals: hook(&spec, "equals")?,
+        join: hook(&spec, "join")?,
+        post_process: hook(&spec, "postProcess")?,
+    })
+}
+
+#[wasm_bindgen(js_class = CustomDiff)]
+impl CustomDiff {
+    /// Diffs `old` against `new`, passing `opts` to every hook. Without an
+    /// `equals` hook, string tokens honour `ignoreCase` and `normalizers` and
+    /// other tokens compare with `===`.
+    pub fn diff(&self, old: JsValue, new_: JsValue, opts: JsValue) -> Result<JsValue, JsValue> {
+        let o = DiffOptions {
+            normalizers: normalizers(&opts)?,
+            ..from_js(opts.clone())
+        };
+        let options: Options = (&o).into();
+        let a = self.tokens(&old, &opts)?;
+        let b = self.tokens(&new_, &opts)?;
+
+        let spans = match &self.equals {
+            Some(f) => js_diff_spans(
+                a.len(),
+                b.len(),
+                |i, j| f.call3(&JsValue::NULL, &a[i], &b[j], &opts),
+                &options,
+            )?,
+            None => {
+                let key = |v: &JsValue| {
+                    let s = normalize(Cow::Owned(v.as_string()?), &options.normalizers);
+                    Some(if options.ignore_case {
+                        fold_case(&s, options.case_locale).into_owned()
+                    } else {
+                        s.into_owned()
+                    })
+                };
+                let ka: Vec<Option<String>> = a.iter().map(key).collect();
+                let kb: Vec<Option<String>> = b.iter().map(key).collect();
+                let eq = |i: usize, j: usize| match (&ka[i], &kb[j]) {
+                    (Some(x), Some(y)) => x == y,
+                    _ => a[i] == b[j],
+                };
+                diff::array::diff_spans(a.len(), b.len(), eq, &options)
+            }
+        };
+
+        let out = Array::new();
+        let (mut old_pos, mut new_pos) = (0usize, 0usize);
+        for (count, added, removed) in spans.unwrap_or_default() {
+            let n = count as usize;
+            let runs = if o.one_change_per_token { n } else { 1 };
+            let per_run = n / runs;
+            for _ in 0..runs {
+                let tokens = if removed {
+                    old_pos += per_run;
+                    &a[old_pos - per_run..old_pos]
+                } else {
+                    new_pos += per_run;
+                    if !added {
+                        old_pos += per_run;
+                    }
+                    &b[new_pos - per_run..new_pos]
+                };
+                let change = Object::new();
+                Reflect::set(&change, &"value".into(), &self.join(tokens, &opts)?)?;
+                Reflect::set(&change, &"count".into(), &JsValue::from_f64(per_run as f64))?;
+                Reflect::set(&change, &"added".into(), &JsValue::from_bool(added))?;
+                Reflect::set(&change, &"removed".into(), &JsValue::from_bool(removed))?;
+                out.push(&change);
+            }
+        }
+
+        match &self.post_process {
+            Some(f) => f.call2(&JsValue::NULL, &out, &opts),
+            None => Ok(out.into()),
+        }
+    }
+}
+
+impl CustomDiff {
+    fn tokens(&self, value: &JsValue, opts: &JsValue) -> Result<Vec<JsValue>, JsValue> {
+        let tokens = self.tokenize.call2(&JsValue::NULL, value, opts)?;
+        let tokens: Array = tokens
+            .dyn_into()
+            .map_err(|_| JsValue::from_str("tokenize must return an array"))?;
+        // Falsy tokens are dropped, like jsdiff's `removeEmpty`.
+        Ok(tokens.iter().filter(JsValue::is_truthy).collect())
+    }
+
+    fn join(&self, tokens: &[JsValue], opts: &JsValue) -> Result<JsValue, JsValue> {
+        let tokens: Array = tokens.iter().collect();
+        match &self.join {
+            Some(f) => f.call2(&JsValue::NULL, &tokens, opts),
+            None => Ok(tokens.join("").into()),
+        }
+    }
+}
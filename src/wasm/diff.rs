@@ -0,0 +1,529 @@
+use js_sys::{Array, Function, Object, Reflect, JSON};
+use serde_wasm_bindgen as swb;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::{
+    comparator, from_js, js_diff_spans, normalizers, ops_to_js, ops_unit, pattern, to_js,
+    words_options,
+};
+use crate::{
+    change::Change,
+    diff::{
+        self,
+        base::{Options, Tokeniser},
+        cleanup::Cleanup,
+        json::canonicalize_value,
+        ops::restore_cr,
+        CharTokenizer, CodeTokenizer, CodeWordTokenizer, CssTokenizer, Granularity,
+        GraphemeTokenizer, LineTokenizer, OpUnit, PatternTokenizer, PooledDiff, SentenceBoundaries,
+        SentenceTokenizer, Similarity, UnicodeSentenceTokenizer, UnicodeWordTokenizer,
+        WordBoundaries, WordTokenizer, WordWithSpaceTokenizer,
+    },
+    options::{
+        DiffCodeOptions, DiffJsonOptions, DiffLinesOptions, DiffOptions, DiffPatternOptions,
+        DiffSentencesOptions, DiffWordsOptions,
+    },
+};
+
+/// Edit script for `output: 'ops'`.
+fn ops<'a, T: Tokeniser<'a>>(
+    tokenizer: T,
+    opts: Options,
+    old: &'a str,
+    new_: &'a str,
+    unit: OpUnit,
+) -> Result<JsValue, JsValue> {
+    Ok(ops_to_js(
+        &PooledDiff::new(tokenizer, opts).diff_ops(old, new_, unit),
+    ))
+}
+
+#[wasm_bindgen(js_name = diffChars)]
+pub fn diff_chars(old_str: &str, new_str: &str, opts: JsValue) -> Result<JsValue, JsValue> {
+    let unit = ops_unit(&opts);
+    let (comparator, thrown) = comparator(&opts);
+    let o = DiffOptions {
+        comparator,
+        normalizers: normalizers(&opts)?,
+        ..from_js(opts)
+    };
+    thrown.check(match (unit, o.granularity) {
+        (Some(unit), Granularity::Codepoint) => {
+            ops(CharTokenizer, (&o).into(), old_str, new_str, unit)
+        }
+        (Some(unit), Granularity::Grapheme) => {
+            ops(GraphemeTokenizer, (&o).into(), old_str, new_str, unit)
+        }
+        (None, _) => to_js(&diff::character::diff_chars(old_str, new_str, &o)),
+    })
+}
+
+#[wasm_bindgen(js_name = diffWords)]
+pub fn diff_words(old: &str, new_: &str, opts: JsValue) -> Result<JsValue, JsValue> {
+    let unit = ops_unit(&opts);
+    let (comparator, thrown) = comparator(&opts);
+    thrown.check(words(old, new_, words_options(opts, comparator)?, unit))
+}
+
+#[wasm_bindgen(js_name = diffCodeWords)]
+pub fn diff_code_words(old: &str, new_: &str, opts: JsValue) -> Result<JsValue, JsValue> {
+    let unit = ops_unit(&opts);
+    let (comparator, thrown) = comparator(&opts);
+    let wo = DiffWordsOptions {
+        word_boundaries: WordBoundaries::Code,
+        ..words_options(opts, comparator)?
+    };
+    thrown.check(words(old, new_, wo, unit))
+}
+
+fn words(
+    old: &str,
+    new_: &str,
+    wo: DiffWordsOptions,
+    unit: Option<OpUnit>,
+) -> Result<JsValue, JsValue> {
+    match unit {
+        Some(unit) if wo.ignore_whitespace == Some(false) => {
+            ops(WordWithSpaceTokenizer, (&wo).into(), old, new_, unit)
+        }
+        Some(unit) => match wo.word_boundaries {
+            WordBoundaries::Jsdiff => ops(WordTokenizer, (&wo).into(), old, new_, unit),
+            WordBoundaries::Unicode => ops(UnicodeWordTokenizer, (&wo).into(), old, new_, unit),
+            WordBoundaries::Code => ops(CodeWordTokenizer, (&wo).into(), old, new_, unit),
+        },
+        None => to_js(&diff::word::diff_words(old, new_, &wo)),
+    }
+}
+
+#[wasm_bindgen(js_name = diffWordsWithSpace)]
+pub fn diff_words_with_space(old: &str, new_: &str, opts: JsValue) -> Result<JsValue, JsValue> {
+    let unit = ops_unit(&opts);
+    let (comparator, thrown) = comparator(&opts);
+    let wo = DiffWordsOptions {
+        comparator,
+        normalizers: normalizers(&opts)?,
+        ..from_js(opts)
+    };
+    thrown.check(match unit {
+        Some(unit) => ops(WordWithSpaceTokenizer, (&wo).into(), old, new_, unit),
+        None => to_js(&diff::word::diff_words_with_space(old, new_, &wo)),
+    })
+}
+
+#[wasm_bindgen(js_name = diffLines)]
+pub fn diff_lines(old_str: &str, new_str: &str, opts: JsValue) -> Result<JsValue, JsValue> {
+    let unit = ops_unit(&opts);
+    let (comparator, thrown) = comparator(&opts);
+    let lo = DiffLinesOptions {
+        comparator,
+        normalizers: normalizers(&opts)?,
+        ..from_js(opts)
+    };
+    thrown.check(match unit {
+        Some(unit) => line_ops(old_str, new_str, &lo, unit),
+        None => to_js(&diff::line::diff_lines(old_str, new_str, &lo)),
+    })
+}
+
+#[wasm_bindgen(js_name = diffTrimmedLines)]
+pub fn diff_trimmed_lines(old_str: &str, new_str: &str, opts: JsValue) -> Result<JsValue, JsValue> {
+    let unit = ops_unit(&opts);
+    let (comparator, thrown) = comparator(&opts);
+    let lo = DiffLinesOptions {
+        comparator,
+        normalizers: normalizers(&opts)?,
+        ..from_js(opts)
+    };
+    thrown.check(match unit {
+        Some(unit) => {
+            let lo = DiffLinesOptions {
+                ignore_whitespace: true,
+                ..lo
+            };
+            line_ops(old_str, new_str, &lo, unit)
+        }
+        None => to_js(&diff::line::diff_trimmed_lines(old_str, new_str, &lo)),
+    })
+}
+
+/// Line edit script. With `strip_trailing_cr` the diff runs on the inputs
+/// with `\r\n` replaced, and the ranges are moved back onto the originals.
+fn line_ops(
+    old_str: &str,
+    new_str: &str,
+    lo: &DiffLinesOptions,
+    unit: OpUnit,
+) -> Result<JsValue, JsValue> {
+    let tokenizer = LineTokenizer::new(lo);
+    if lo.strip_trailing_cr {
+        let (old, new_) = (old_str.replace("\r\n", "\n"), new_str.replace("\r\n", "\n"));
+        let mut ops = PooledDiff::new(tokenizer, lo.into()).diff_ops(&old, &new_, unit);
+        restore_cr(&mut ops, old_str, new_str, unit);
+        return Ok(ops_to_js(&ops));
+    }
+    ops(tokenizer, lo.into(), old_str, new_str, unit)
+}
+
+#[wasm_bindgen(js_name = diffSentences)]
+pub fn diff_sentences(old_str: &str, new_str: &str, opts: JsValue) -> Result<JsValue, JsValue> {
+    let unit = ops_unit(&opts);
+    let (comparator, thrown) = comparator(&opts);
+    let so = DiffSentencesOptions {
+        comparator,
+        normalizers: normalizers(&opts)?,
+        ..from_js(opts)
+    };
+    thrown.check(match (unit, so.sentence_boundaries) {
+        (Some(unit), SentenceBoundaries::Jsdiff) => {
+            ops(SentenceTokenizer, (&so).into(), old_str, new_str, unit)
+        }
+        (Some(unit), SentenceBoundaries::Unicode) => ops(
+            UnicodeSentenceTokenizer::new(&so),
+            (&so).into(),
+            old_str,
+            new_str,
+            unit,
+        ),
+        (None, _) => to_js(&diff::sentences::diff_sentences(old_str, new_str, &so)),
+    })
+}
+
+#[wasm_bindgen(js_name = diffCss)]
+pub fn diff_css(old_str: &str, new_str: &str, opts: JsValue) -> Result<JsValue, JsValue> {
+    let unit = ops_unit(&opts);
+    let (comparator, thrown) = comparator(&opts);
+    let o = DiffOptions {
+        comparator,
+        normalizers: normalizers(&opts)?,
+        ..from_js(opts)
+    };
+    thrown.check(match unit {
+        Some(unit) => ops(CssTokenizer, (&o).into(), old_str, new_str, unit),
+        None => to_js(&diff::css::diff_css(old_str, new_str, &o)),
+    })
+}
+
+#[wasm_bindgen(js_name = diffCode)]
+pub fn diff_code(old_str: &str, new_str: &str, opts: JsValue) -> Result<JsValue, JsValue> {
+    let unit = ops_unit(&opts);
+    let (comparator, thrown) = comparator(&opts);
+    let co = DiffCodeOptions {
+        comparator,
+        normalizers: normalizers(&opts)?,
+        ..from_js(opts)
+    };
+    thrown.check(match unit {
+        Some(unit) => ops(
+            CodeTokenizer::new(co.language),
+            (&co).into(),
+            old_str,
+            new_str,
+            unit,
+        ),
+        None => to_js(&diff::code::diff_code(old_str, new_str, &co)),
+    })
+}
+
+#[wasm_bindgen(js_name = diffWithPattern)]
+pub fn diff_with_pattern(old_str: &str, new_str: &str, opts: JsValue) -> Result<JsValue, JsValue> {
+    let unit = ops_unit(&opts);
+    let token_pattern = pattern(&opts, "tokenPattern")
+        .ok_or_else(|| JsValue::from_str("tokenPattern is required"))?;
+    let (comparator, thrown) = comparator(&opts);
+    let po = DiffPatternOptions {
+        token_pattern,
+        ignore_pattern: pattern(&opts, "ignorePattern"),
+        comparator,
+        normalizers: normalizers(&opts)?,
+        ..from_js(opts)
+    };
+    if let Some(unit) = unit {
+        let tokenizer = PatternTokenizer::new(&po.token_pattern, po.ignore_pattern.as_deref())
+            .map_err(|e| JsValue::from_str(&e))?;
+        return thrown.check(ops(tokenizer, (&po).into(), old_str, new_str, unit));
+    }
+    let changes = diff::pattern::diff_with_pattern(old_str, new_str, &po)
+        .map_err(|e| JsValue::from_str(&e))?;
+    thrown.check(to_js(&changes))
+}
+
+#[wasm_bindgen(js_name = diffJson)]
+pub fn diff_json(old_val: JsValue, new_val: JsValue, opts: JsValue) -> Result<JsValue, JsValue> {
+    let jo: DiffJsonOptions = from_js(opts);
+    let old_v: serde_json::Value = swb::from_value(old_val)?;
+    let new_v: serde_json::Value = swb::from_value(new_val)?;
+    to_js(&diff::json::diff_json(&old_v, &new_v, &jo))
+}
+
+#[wasm_bindgen(js_name = diffArrays)]
+pub fn diff_arrays(old: Array, new_: Array, opts: JsValue) -> Result<JsValue, JsValue> {
+    let o: DiffOptions = from_js(opts.clone());
+    let comparator = if opts.is_object() {
+        Reflect::get(&opts, &JsValue::from_str("comparator"))?
+            .dyn_into::<Function>()
+            .ok()
+    } else {
+        None
+    };
+    let a: Vec<JsValue> = old.iter().collect();
+    let b: Vec<JsValue> = new_.iter().collect();
+
+    let spans = match comparator {
+        Some(f) => js_diff_spans(
+            a.len(),
+            b.len(),
+            |i, j| f.call2(&JsValue::NULL, &a[i], &b[j]),
+            &(&o).into(),
+        )?,
+        None => {
+            let (ka, kb) = (structural_keys(&a), structural_keys(&b));
+            let eq = |i: usize, j: usize| match (&ka[i], &kb[j]) {
+                (Some(x), Some(y)) => x == y,
+                _ => a[i] == b[j],
+            };
+            diff::array::diff_spans(a.len(), b.len(), eq, &(&o).into())
+        }
+    };
+
+    let out = Array::new();
+    let (mut old_pos, mut new_pos) = (0usize, 0usize);
+    for (count, added, removed) in spans.unwrap_or_default() {
+        let n = count as usize;
+        let value: Array = if removed {
+            old_pos += n;
+            a[old_pos - n..old_pos].iter().collect()
+        } else {
+            new_pos += n;
+            if !added {
+                old_pos += n;
+            }
+            b[new_pos - n..new_pos].iter().collect()
+        };
+        let change = Object::new();
+        Reflect::set(&change, &"value".into(), &value)?;
+        Reflect::set(&change, &"count".into(), &JsValue::from_f64(count as f64))?;
+        Reflect::set(&change, &"added".into(), &JsValue::from_bool(added))?;
+        Reflect::set(&change, &"removed".into(), &JsValue::from_bool(removed))?;
+        out.push(&change);
+    }
+    Ok(out.into())
+}
+
+/// JSON form of each plain object or array, used to compare them by
+/// structure. Other values (primitives, class instances) get `None` and are
+/// compared with `===`.
+fn structural_keys(values: &[JsValue]) -> Vec<Option<serde_json::Value>> {
+    let object_proto = Object::get_prototype_of(&Object::new());
+    values
+        .iter()
+        .map(|v| {
+            if !v.is_object() {
+                return None;
+            }
+            let proto = Object::get_prototype_of(v);
+            if !Array::is_array(v) && !proto.is_null() && proto != object_proto {
+                return None;
+            }
+            swb::from_value(v.clone()).ok()
+        })
+        .collect()
+}
+
+#[wasm_bindgen(js_name = canonicalize)]
+pub fn canonicalize(js_val: JsValue) -> Result<JsValue, JsValue> {
+    let val: serde_json::Value = swb::from_value(js_val)?;
+    let canon = canonicalize_value(&val);
+    let as_string = serde_json::to_string(&canon).unwrap();
+    JSON::parse(&as_string)
+}
+
+#[wasm_bindgen(js_name = convertChangesToXML)]
+pub fn convert_changes_to_xml(changes_js: JsValue) -> Result<String, JsValue> {
+    let changes: Vec<Change> = swb::from_value(changes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to deserialize changes: {}", e)))?;
+    Ok(crate::convert::xml::convert_changes_to_xml(&changes))
+}
+
+#[wasm_bindgen(js_name = cleanupChanges)]
+pub fn cleanup_changes(changes_js: JsValue, mode: JsValue) -> Result<JsValue, JsValue> {
+    let changes: Vec<Change> = swb::from_value(changes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to deserialize changes: {}", e)))?;
+    let mode: Cleanup = if mode.is_undefined() {
+        Cleanup::Semantic
+    } else {
+        swb::from_value(mode)?
+    };
+    to_js(&diff::cleanup_changes(changes, mode))
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SimilarityJs {
+    #[serde(flatten)]
+    similarity: Similarity,
+    edit_distance: usize,
+    ratio: f64,
+}
+
+#[wasm_bindgen(js_name = similarity)]
+pub fn similarity(old_str: &str, new_str: &str, opts: JsValue) -> Result<JsValue, JsValue> {
+    let tokenizer = if opts.is_object() {
+        Reflect::get(&opts, &JsValue::from_str("tokenizer"))?.as_string()
+    } else {
+        None
+    };
+    let (comparator, thrown) = comparator(&opts);
+    let normalizers = normalizers(&opts)?;
+    let result = match tokenizer.as_deref().unwrap_or("chars") {
+        "chars" => {
+            let o = DiffOptions {
+                comparator,
+                normalizers,
+                ..from_js(opts)
+            };
+            match o.granularity {
+                Granularity::Codepoint => {
+                    diff::similarity(CharTokenizer, old_str, new_str, &(&o).into())
+                }
+                Granularity::Grapheme => {
+                    diff::similarity(GraphemeTokenizer, old_str, new_str, &(&o).into())
+                }
+            }
+        }
+        "words" => {
+            let wo = words_options(opts, comparator)?;
+            let base = (&wo).into();
+            match (wo.ignore_whitespace, wo.word_boundaries) {
+                (Some(false), _) => {
+                    diff::similarity(WordWithSpaceTokenizer, old_str, new_str, &base)
+                }
+                (_, WordBoundaries::Jsdiff) => {
+                    diff::similarity(WordTokenizer, old_str, new_str, &base)
+                }
+                (_, WordBoundaries::Unicode) => {
+                    diff::similarity(UnicodeWordTokenizer, old_str, new_str, &base)
+                }
+                (_, WordBoundaries::Code) => {
+                    diff::similarity(CodeWordTokenizer, old_str, new_str, &base)
+                }
+            }
+        }
+        "wordsWithSpace" => {
+            let wo = DiffWordsOptions {
+                comparator,
+                normalizers,
+                ..from_js(opts)
+            };
+            diff::similarity(WordWithSpaceTokenizer, old_str, new_str, &(&wo).into())
+        }
+        kind @ ("lines" | "trimmedLines") => {
+            let lo: DiffLinesOptions = from_js(opts);
+            let lo = DiffLinesOptions {
+                ignore_whitespace: lo.ignore_whitespace || kind == "trimmedLines",
+                comparator,
+                normalizers,
+                ..lo
+            };
+            let (old_str, new_str) = if lo.strip_trailing_cr {
+                (old_str.replace("\r\n", "\n"), new_str.replace("\r\n", "\n"))
+            } else {
+                (old_str.to_string(), new_str.to_string())
+            };
+            diff::similarity(LineTokenizer::new(&lo), &old_str, &new_str, &(&lo).into())
+        }
+        "sentences" => {
+            let so = DiffSentencesOptions {
+                comparator,
+                normalizers,
+                ..from_js(opts)
+            };
+            match so.sentence_boundaries {
+                SentenceBoundaries::Jsdiff => {
+                    diff::similarity(SentenceTokenizer, old_str, new_str, &(&so).into())
+                }
+                SentenceBoundaries::Unicode => diff::similarity(
+                    UnicodeSentenceTokenizer::new(&so),
+                    old_str,
+                    new_str,
+                    &(&so).into(),
+                ),
+            }
+        }
+        "css" => {
+            let o = DiffOptions {
+                comparator,
+                normalizers,
+                ..from_js(opts)
+            };
+            diff::similarity(CssTokenizer, old_str, new_str, &(&o).into())
+        }
+        "code" => {
+            let co = DiffCodeOptions {
+                comparator,
+                normalizers,
+                ..from_js(opts)
+            };
+            let tokenizer = CodeTokenizer::new(co.language);
+            diff::similarity(tokenizer, old_str, new_str, &(&co).into())
+        }
+        other => return Err(JsValue::from_str(&format!("Unknown tokenizer: {other}"))),
+    };
+    thrown.check(match result {
+        Some(similarity) => to_js(&SimilarityJs {
+            similarity,
+            edit_distance: similarity.edit_distance(),
+            ratio: similarity.ratio(),
+        }),
+        None => Ok(JsValue::NULL),
+    })
+}
+
+#[wasm_bindgen(js_name = lineDiff)]
+pub struct LineDiff;
+
+#[wasm_bindgen(js_class = lineDiff)]
+impl LineDiff {
+    #[wasm_bindgen(js_name = tokenize)]
+    pub fn tokenize_js(value: &str, opts: JsValue) -> Result<JsValue, JsValue> {
+        let lo: DiffLinesOptions = from_js(opts);
+        to_js(&diff::line::tokenize_lines(value, &lo))
+    }
+}
+
+#[wasm_bindgen(js_name = wordDiff)]
+pub struct WordDiff;
+
+#[wasm_bindgen(js_class = wordDiff)]
+impl WordDiff {
+    #[wasm_bindgen(js_name = tokenize)]
+    pub fn tokenize_js(text: &str, opts: JsValue) -> Result<JsValue, JsValue> {
+        let mut arena = Vec::new();
+        let toks = match words_options(opts, None)?.word_boundaries {
+            WordBoundaries::Jsdiff => WordTokenizer.tokenize(text, &mut arena),
+            WordBoundaries::Unicode => UnicodeWordTokenizer.tokenize(text, &mut arena),
+            WordBoundaries::Code => CodeWordTokenizer.tokenize(text, &mut arena),
+        };
+        let vec: Vec<&str> = toks.iter().map(|t| t.text).collect();
+        to_js(&vec)
+    }
+}
+
+#[wasm_bindgen(js_name = sentenceDiff)]
+pub struct SentenceDiff;
+
+#[wasm_bindgen(js_class = sentenceDiff)]
+impl SentenceDiff {
+    #[wasm_bindgen(js_name = tokenize)]
+    pub fn tokenize_js(text: &str, opts: JsValue) -> Result<JsValue, JsValue> {
+        let so: DiffSentencesOptions = from_js(opts);
+        let mut arena = Vec::new();
+        let unicode = UnicodeSentenceTokenizer::new(&so);
+        let toks = match so.sentence_boundaries {
+            SentenceBoundaries::Jsdiff => SentenceTokenizer.tokenize(text, &mut arena),
+            SentenceBoundaries::Unicode => unicode.tokenize(text, &mut arena),
+        };
+        let vec: Vec<&str> = toks.iter().map(|t| t.text).collect();
+        to_js(&vec)
+    }
+}
@@ -0,0 +1,207 @@
+use serde::Serialize;
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::diff::{
+    array::diff_spans,
+    base::{Comparator, Options},
+    memory_pool::Span,
+    normalize::Normalizer,
+    OpUnit, WordBoundaries,
+};
+use crate::options::DiffWordsOptions;
+
+pub mod async_diff;
+pub mod custom;
+pub mod diff;
+pub mod patch;
+
+pub use async_diff::{
+    diff_chars_async, diff_css_async, diff_lines_async, diff_sentences_async,
+    diff_trimmed_lines_async, diff_words_async, diff_words_with_space_async,
+};
+pub use diff::{
+    canonicalize, convert_changes_to_xml, diff_arrays, diff_chars, diff_css, diff_json, diff_lines,
+    diff_sentences, diff_trimmed_lines, diff_words, diff_words_with_space,
+};
+pub use patch::{
+    apply_patch, apply_patches, create_patch, create_two_files_patch, format_patch, is_unix,
+    is_win, parse_patch, reverse_patch, structured_patch, unix_to_win, win_to_unix,
+};
+
+#[wasm_bindgen]
+pub fn set_panic_hook() {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+}
+
+/// First exception thrown by a JS callback during a diff. The engine cannot
+/// fail mid-search, so once a call throws the callback is not called again,
+/// every later comparison is unequal, and the diff fails with the exception.
+#[derive(Clone, Default)]
+pub(crate) struct Thrown(Rc<RefCell<Option<JsValue>>>);
+
+impl Thrown {
+    /// Whether `call` returns a truthy value. Not called after a throw.
+    pub(crate) fn truthy(&self, call: impl FnOnce() -> Result<JsValue, JsValue>) -> bool {
+        if self.0.borrow().is_some() {
+            return false;
+        }
+        match call() {
+            Ok(v) => v.is_truthy(),
+            Err(e) => {
+                *self.0.borrow_mut() = Some(e);
+                false
+            }
+        }
+    }
+
+    /// `result`, or the recorded exception in its place.
+    pub(crate) fn check<R>(&self, result: Result<R, JsValue>) -> Result<R, JsValue> {
+        match self.0.borrow_mut().take() {
+            Some(e) => Err(e),
+            None => result,
+        }
+    }
+}
+
+/// `comparator` function from a JS options object, called with the old and
+/// the new token, and the `Thrown` the diff must be checked against.
+pub(crate) fn comparator(opts: &JsValue) -> (Option<Comparator>, Thrown) {
+    let thrown = Thrown::default();
+    let f = opts
+        .is_object()
+        .then(|| js_sys::Reflect::get(opts, &JsValue::from_str("comparator")).ok())
+        .flatten()
+        .and_then(|f| f.dyn_into::<js_sys::Function>().ok());
+    let comparator = f.map(|f| {
+        let thrown = thrown.clone();
+        Comparator::new(move |l, r| {
+            thrown.truthy(|| f.call2(&JsValue::NULL, &JsValue::from_str(l), &JsValue::from_str(r)))
+        })
+    });
+    (comparator, thrown)
+}
+
+/// `diff::array::diff_spans` with token equality from a JS call returning a
+/// truthy value, failing with the first exception the call throws.
+pub(crate) fn js_diff_spans(
+    old_len: usize,
+    new_len: usize,
+    eq: impl Fn(usize, usize) -> Result<JsValue, JsValue>,
+    opts: &Options,
+) -> Result<Option<Vec<Span>>, JsValue> {
+    let thrown = Thrown::default();
+    let spans = diff_spans(old_len, new_len, |i, j| thrown.truthy(|| eq(i, j)), opts);
+    thrown.check(Ok(spans))
+}
+
+/// Regex source from a JS options property holding a string or a `RegExp`.
+/// The `i`, `m` and `s` flags of a `RegExp` become inline flags.
+pub(crate) fn pattern(opts: &JsValue, key: &str) -> Option<String> {
+    if !opts.is_object() {
+        return None;
+    }
+    let value = js_sys::Reflect::get(opts, &JsValue::from_str(key)).ok()?;
+    if let Some(source) = value.as_string() {
+        return Some(source);
+    }
+    let re: js_sys::RegExp = value.dyn_into().ok()?;
+    let source = String::from(re.source());
+    let flags: String = String::from(re.flags())
+        .chars()
+        .filter(|c| matches!(c, 'i' | 'm' | 's'))
+        .collect();
+    if flags.is_empty() {
+        Some(source)
+    } else {
+        Some(format!("(?{flags}){source}"))
+    }
+}
+
+/// `normalizers` from a JS options object: names, or `{ mask, placeholder }`
+/// objects whose `mask` is a string or a `RegExp`. Fails on an unknown name
+/// or a mask that does not compile.
+pub(crate) fn normalizers(opts: &JsValue) -> Result<Vec<Normalizer>, JsValue> {
+    if !opts.is_object() {
+        return Ok(Vec::new());
+    }
+    let value = js_sys::Reflect::get(opts, &JsValue::from_str("normalizers"))?;
+    if value.is_undefined() || value.is_null() {
+        return Ok(Vec::new());
+    }
+    let list: js_sys::Array = value
+        .dyn_into()
+        .map_err(|_| JsValue::from_str("normalizers must be an array"))?;
+    list.iter()
+        .map(|entry| {
+            let normalizer = match entry.as_string() {
+                Some(name) => name.parse(),
+                None => match pattern(&entry, "mask") {
+                    Some(mask) => {
+                        let placeholder = js_sys::Reflect::get(&entry, &"placeholder".into())?
+                            .as_string()
+                            .unwrap_or_default();
+                        Normalizer::mask(&mask, &placeholder)
+                    }
+                    None => Err("A normalizer must be a name or { mask }".to_string()),
+                },
+            };
+            normalizer.map_err(|e| JsValue::from_str(&e))
+        })
+        .collect()
+}
+
+/// `diffWords` options from a JS options object, with `comparator` built by
+/// the caller. jsdiff's `intlSegmenter` selects Unicode word boundaries; the
+/// segmenter itself is not called.
+pub(crate) fn words_options(
+    opts: JsValue,
+    comparator: Option<Comparator>,
+) -> Result<DiffWordsOptions, JsValue> {
+    let segmenter = opts.is_object()
+        && js_sys::Reflect::get(&opts, &JsValue::from_str("intlSegmenter"))
+            .is_ok_and(|v| v.is_object());
+    let wo = DiffWordsOptions {
+        comparator,
+        normalizers: normalizers(&opts)?,
+        ..from_js(opts)
+    };
+    if segmenter {
+        Ok(DiffWordsOptions {
+            word_boundaries: WordBoundaries::Unicode,
+            ..wo
+        })
+    } else {
+        Ok(wo)
+    }
+}
+
+/// `opsUnit` from a JS options object that asks for `output: 'ops'`.
+pub(crate) fn ops_unit(opts: &JsValue) -> Option<OpUnit> {
+    if !opts.is_object() {
+        return None;
+    }
+    let get = |key: &str| js_sys::Reflect::get(opts, &JsValue::from_str(key)).ok();
+    if get("output")?.as_string()? != "ops" {
+        return None;
+    }
+    Some(get("opsUnit").map(from_js).unwrap_or_default())
+}
+
+/// Edit script from `PooledDiff::diff_ops` as a `Uint32Array`.
+#[inline]
+pub(crate) fn ops_to_js(ops: &[u32]) -> JsValue {
+    js_sys::Uint32Array::from(ops).into()
+}
+
+#[inline]
+pub(crate) fn to_js<T: Serialize + ?Sized>(value: &T) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(value).map_err(|e| JsValue::from(e.to_string()))
+}
+
+#[inline]
+pub(crate) fn from_js<T: for<'de> serde::Deserialize<'de> + Default>(value: JsValue) -> T {
+    serde_wasm_bindgen::from_value(value).unwrap_or_default()
+}
@@ -0,0 +1,258 @@
+use js_sys::{Array, Reflect};
+use serde_wasm_bindgen as swb;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+use super::{comparator, from_js, normalizers, to_js, Thrown};
+use crate::patch::{
+    self,
+    apply::ApplyOptions,
+    create::StructuredOptions,
+    line_endings::{
+        all_unix_internal, all_win_internal, is_unix_internal, is_win_internal,
+        unix_to_win_internal, win_to_unix_internal,
+    },
+    types::Patch,
+};
+
+#[wasm_bindgen(js_name = parsePatch)]
+pub fn parse_patch(uni_diff: &str) -> Result<JsValue, JsValue> {
+    let parsed = patch::parse_patch(uni_diff).map_err(|e| JsValue::from_str(&e))?;
+    swb::to_value(&parsed).map_err(Into::into)
+}
+
+#[wasm_bindgen(js_name = structuredPatch)]
+pub fn structured_patch(
+    old_file_name: &str,
+    new_file_name: &str,
+    old_str: &str,
+    new_str: &str,
+    old_header: Option<String>,
+    new_header: Option<String>,
+    opts: JsValue,
+) -> Result<JsValue, JsValue> {
+    let (so, thrown) = structured_options(opts)?;
+    let patch = thrown.check(
+        patch::structured_patch(
+            old_file_name,
+            new_file_name,
+            old_str,
+            new_str,
+            old_header.as_deref(),
+            new_header.as_deref(),
+            &so,
+        )
+        .map_err(Into::into),
+    )?;
+    swb::to_value(&patch).map_err(Into::into)
+}
+
+fn structured_options(opts: JsValue) -> Result<(StructuredOptions, Thrown), JsValue> {
+    let mut so: StructuredOptions = from_js(opts.clone());
+    let (comparator, thrown) = comparator(&opts);
+    so.line_options.comparator = comparator;
+    so.line_options.normalizers = normalizers(&opts)?;
+    Ok((so, thrown))
+}
+
+#[wasm_bindgen(js_name = formatPatch)]
+pub fn format_patch(val: JsValue) -> Result<String, JsValue> {
+    if val.is_string() {
+        return Ok(val.as_string().unwrap());
+    }
+    if let Ok(vec) = swb::from_value::<Vec<Patch>>(val.clone()) {
+        return Ok(patch::format_patches(&vec));
+    }
+    let p: Patch = swb::from_value(val)?;
+    Ok(patch::format_patch(&p))
+}
+
+#[wasm_bindgen(js_name = createTwoFilesPatch)]
+pub fn create_two_files_patch(
+    old_file: &str,
+    new_file: &str,
+    old_str: &str,
+    new_str: &str,
+    old_header: Option<String>,
+    new_header: Option<String>,
+    opts: JsValue,
+) -> Result<String, JsValue> {
+    let (so, thrown) = structured_options(opts)?;
+    thrown.check(
+        patch::create_two_files_patch(
+            old_file,
+            new_file,
+            old_str,
+            new_str,
+            old_header.as_deref(),
+            new_header.as_deref(),
+            &so,
+        )
+        .map_err(Into::into),
+    )
+}
+
+#[wasm_bindgen(js_name = createPatch)]
+pub fn create_patch(
+    file_name: &str,
+    old_str: &str,
+    new_str: &str,
+    old_header: Option<String>,
+    new_header: Option<String>,
+    opts: JsValue,
+) -> Result<String, JsValue> {
+    create_two_files_patch(
+        file_name, file_name, old_str, new_str, old_header, new_header, opts,
+    )
+}
+
+#[wasm_bindgen(js_name = applyPatch)]
+pub fn apply_patch(source: &str, uni_diff: JsValue, opts: JsValue) -> Result<JsValue, JsValue> {
+    let mut patch_val = uni_diff.clone();
+
+    if Array::is_array(&uni_diff) {
+        let arr = Array::from(&uni_diff);
+        if arr.length() != 1 {
+            return Err(JsValue::from_str(
+                "applyPatch only works with a single input.",
+            ));
+        }
+        patch_val = arr.get(0);
+    }
+
+    let patch: Patch = if patch_val.is_string() {
+        let s = patch_val.as_string().unwrap();
+        patch::parse_patch(&s)
+            .map_err(|e| JsValue::from_str(&e))?
+            .into_iter()
+            .next()
+            .ok_or_else(|| JsValue::from_str("empty patch"))?
+    } else {
+        swb::from_value(patch_val)?
+    };
+
+    if !opts.is_undefined() && !opts.is_null() {
+        let ff =
+            Reflect::get(&opts, &JsValue::from_str("fuzzFactor")).unwrap_or(JsValue::UNDEFINED);
+        if !ff.is_undefined() && !ff.is_null() {
+            if let Some(n) = ff.as_f64() {
+                if n < 0.0 || n.fract() != 0.0 {
+                    return Err(JsValue::from_str(
+                        "fuzzFactor must be a non-negative integer",
+                    ));
+                }
+            } else {
+                return Err(JsValue::from_str(
+                    "fuzzFactor must be a non-negative integer",
+                ));
+            }
+        }
+    }
+
+    let mut options: ApplyOptions = from_js(opts.clone());
+
+    if !opts.is_undefined() && !opts.is_null() {
+        let compare_line =
+            Reflect::get(&opts, &JsValue::from_str("compareLine")).unwrap_or(JsValue::UNDEFINED);
+        if !compare_line.is_undefined() && !compare_line.is_null() && compare_line.is_function() {
+            let compare_fn = js_sys::Function::from(compare_line);
+            options.compare_line = Some(Rc::new(move |line_number, line, operation, content| {
+                call_compare_line(&compare_fn, line_number, line, operation, content)
+            }));
+        }
+    }
+
+    match patch::apply_patch(source, &patch, &options) {
+        Ok(result) => Ok(JsValue::from_str(&result)),
+        Err(_) => Ok(JsValue::from_bool(false)),
+    }
+}
+
+fn call_compare_line(
+    compare_fn: &js_sys::Function,
+    line_number: usize,
+    line: &str,
+    operation: char,
+    patch_content: &str,
+) -> bool {
+    let args = Array::new();
+    args.push(&JsValue::from_f64(line_number as f64));
+    args.push(&JsValue::from_str(line));
+    args.push(&JsValue::from_str(operation.encode_utf8(&mut [0; 4])));
+    args.push(&JsValue::from_str(patch_content));
+
+    compare_fn
+        .apply(&JsValue::NULL, &args)
+        .map(|result| result.as_bool().unwrap_or(false))
+        .unwrap_or(false)
+}
+
+#[wasm_bindgen(js_name = applyPatches)]
+pub fn apply_patches(uni_diff: &str, cb: &js_sys::Function) {
+    let list = match patch::parse_patch(uni_diff) {
+        Ok(v) => v,
+        Err(e) => {
+            let _ = cb.call1(
+                &JsValue::NULL,
+                &JsValue::from_str(&format!("parse error: {e}")),
+            );
+            return;
+        }
+    };
+
+    let this = JsValue::NULL;
+    for (idx, p) in list.into_iter().enumerate() {
+        let _ = cb.call2(
+            &this,
+            &JsValue::from_f64(idx as f64),
+            &swb::to_value(&p).unwrap(),
+        );
+    }
+}
+
+#[wasm_bindgen(js_name = reversePatch)]
+pub fn reverse_patch(val: JsValue) -> Result<JsValue, JsValue> {
+    if let Ok(vec) = swb::from_value::<Vec<Patch>>(val.clone()) {
+        return to_js(&patch::reverse_patches(&vec));
+    }
+    let p: Patch = swb::from_value(val)?;
+    to_js(&patch::reverse_patch(&p))
+}
+
+#[wasm_bindgen(js_name = isUnix)]
+pub fn is_unix(v: JsValue) -> Result<bool, JsValue> {
+    if let Ok(vec) = swb::from_value::<Vec<Patch>>(v.clone()) {
+        return Ok(all_unix_internal(&vec));
+    }
+    let p: Patch = swb::from_value(v)?;
+    Ok(is_unix_internal(&p))
+}
+
+#[wasm_bindgen(js_name = isWin)]
+pub fn is_win(v: JsValue) -> Result<bool, JsValue> {
+    if let Ok(vec) = swb::from_value::<Vec<Patch>>(v.clone()) {
+        return Ok(all_win_internal(&vec));
+    }
+    let p: Patch = swb::from_value(v)?;
+    Ok(is_win_internal(&p))
+}
+
+#[wasm_bindgen(js_name = winToUnix)]
+pub fn win_to_unix(v: JsValue) -> Result<JsValue, JsValue> {
+    if let Ok(vec) = swb::from_value::<Vec<Patch>>(v.clone()) {
+        let conv: Vec<Patch> = vec.iter().map(win_to_unix_internal).collect();
+        return to_js(&conv);
+    }
+    let p: Patch = swb::from_value(v)?;
+    to_js(&win_to_unix_internal(&p))
+}
+
+#[wasm_bindgen(js_name = unixToWin)]
+pub fn unix_to_win(v: JsValue) -> Result<JsValue, JsValue> {
+    if let Ok(vec) = swb::from_value::<Vec<Patch>>(v.clone()) {
+        let conv: Vec<Patch> = vec.iter().map(unix_to_win_internal).collect();
+        return to_js(&conv);
+    }
+    let p: Patch = swb::from_value(v)?;
+    to_js(&unix_to_win_internal(&p))
+}
@@ -155,4 +155,18 @@ describe('diffChars', () => {
       expect(result.length).toBe(3);
     });
   });
+
+  describe('comparator', () => {
+    it('fails with the first exception it throws', async () => {
+      let calls = 0;
+      const comparator = () => {
+        calls += 1;
+        throw new Error(`boom ${calls}`);
+      };
+      expect(() => wasm.diffChars('abc', 'abd', { comparator })).toThrow('boom 1');
+      expect(calls).toBe(1);
+      calls = 0;
+      await expect(wasm.diffCharsAsync('abc', 'abd', { comparator })).rejects.toThrow('boom 1');
+    });
+  });
 });
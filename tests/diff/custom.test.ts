@@ -0,0 +1,55 @@
+import { beforeAll, describe, expect, it } from 'bun:test';
+import type * as DiffNative from 'diff-native';
+import { getWasmModule } from '../setup';
+
+let wasm: typeof DiffNative;
+
+beforeAll(async () => {
+  wasm = await getWasmModule();
+});
+
+describe('createDiff', () => {
+  it('should diff the tokens from tokenize', () => {
+    const differ = wasm.createDiff({ tokenize: (value: string) => value.split(/(,)/) });
+    const result = differ.diff('a,b,c', 'a,x,c');
+    expect(wasm.convertChangesToXML(result)).toBe('a,<del>b</del><ins>x</ins>,c');
+    expect(differ.diff('a', 'a')).toEqual([{ value: 'a', count: 1, added: false, removed: false }]);
+  });
+
+  it('should drop empty tokens like jsdiff', () => {
+    const differ = wasm.createDiff({ tokenize: (value: string) => value.split(/(,)/) });
+    expect(differ.diff('a,', 'a,')).toEqual([
+      { value: 'a,', count: 2, added: false, removed: false },
+    ]);
+  });
+
+  it('should use the equals, join and postProcess hooks', () => {
+    const differ = wasm.createDiff<string, number, number[]>({
+      tokenize: (value) => value.split(' ').map(Number),
+      equals: (left, right) => Math.abs(left - right) < 1,
+      join: (tokens) => tokens,
+      postProcess: (changes) => changes.filter((change) => !change.added),
+    });
+    expect(differ.diff('1 2 3', '1.5 2 9')).toEqual([
+      { value: [1.5, 2], count: 2, added: false, removed: false },
+      { value: [3], count: 1, added: false, removed: true },
+    ]);
+  });
+
+  it('should honour ignoreCase and oneChangePerToken', () => {
+    const differ = wasm.createDiff({ tokenize: (value: string) => value.split('') });
+    expect(differ.diff('ab', 'AB', { ignoreCase: true })).toHaveLength(1);
+    expect(differ.diff('ab', 'ab', { oneChangePerToken: true })).toHaveLength(2);
+  });
+
+  it('should reject missing or failing hooks', () => {
+    expect(() => wasm.createDiff({} as never)).toThrow();
+    const differ = wasm.createDiff({
+      tokenize: (value: string) => value.split(''),
+      equals: () => {
+        throw new Error('boom');
+      },
+    });
+    expect(() => differ.diff('a', 'b')).toThrow('boom');
+  });
+});
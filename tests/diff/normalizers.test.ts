@@ -0,0 +1,42 @@
+import { beforeAll, describe, expect, it } from 'bun:test';
+import type * as DiffNative from 'diff-native';
+import { getWasmModule } from '../setup';
+
+let wasm: typeof DiffNative;
+
+beforeAll(async () => {
+  wasm = await getWasmModule();
+});
+
+describe('normalizers', () => {
+  it('should compare normalized tokens but return the original text', () => {
+    const result = wasm.diffLines('id 17  ok\nend\n', 'id 42 ok\nend\n', {
+      normalizers: ['collapseWhitespace', { mask: '\\d+', placeholder: '#' }],
+    });
+    expect(result).toEqual([{ value: 'id 42 ok\nend\n', count: 2, added: false, removed: false }]);
+  });
+
+  it('should accept a RegExp mask and throw on invalid normalizers', () => {
+    const result = wasm.diffLines('Ticket ABC-1\n', 'ticket XYZ-22\n', {
+      ignoreCase: true,
+      normalizers: [{ mask: /[A-Z]{3}-\d+/, placeholder: 'ID' }],
+    });
+    expect(result).toHaveLength(1);
+    expect(() => wasm.diffLines('a', 'b', { normalizers: ['nfd'] } as never)).toThrow();
+    expect(() => wasm.diffLines('a', 'b', { normalizers: [{ mask: '(' }] })).toThrow();
+  });
+
+  it('should apply to every tokenizer', () => {
+    const unchanged = (changes: DiffNative.Change[]) =>
+      changes.every((c) => !c.added && !c.removed);
+    const words = wasm.diffWords('café crème', 'cafe creme', { normalizers: ['stripDiacritics'] });
+    expect(unchanged(words)).toBe(true);
+    const graphemes = wasm.diffChars('cafe\u0301', 'caf\u00e9', {
+      granularity: 'grapheme',
+      normalizers: ['nfc'],
+    });
+    expect(unchanged(graphemes)).toBe(true);
+    const differ = wasm.createDiff({ tokenize: (value: string) => value.split(/(,)/) });
+    expect(unchanged(differ.diff('\uff21,b', 'A,b', { normalizers: ['nfkc'] }))).toBe(true);
+  });
+});
@@ -29,30 +29,225 @@ declare module 'diff-native' {
      * For line diffs, this is the number of lines.
      */
     count?: number;
+    /** Where this segment starts in both inputs; only set with `positions: true`. */
+    position?: ChangePosition;
   }
 
+  /**
+   * Start of a change in the old and in the new input. A change missing from
+   * one side starts where it would be inserted there.
+   */
+  export interface ChangePosition {
+    old: ChangeLocation;
+    new: ChangeLocation;
+  }
+
+  export interface ChangeLocation {
+    /** Index of the first token. */
+    token: number;
+    /** Offset in UTF-8 bytes. */
+    byte: number;
+    /** Offset in UTF-16 code units, usable with `String.prototype.slice`. */
+    utf16: number;
+    /** Zero-based line. */
+    line: number;
+    /** Zero-based column in UTF-16 code units. */
+    column: number;
+  }
+
+  /**
+   * Comparison normalizer: `'nfc'` and `'nfkc'` apply Unicode normalization,
+   * `'collapseWhitespace'` turns inner whitespace runs into one space,
+   * `'stripDiacritics'` drops accents, and `{ mask, placeholder }` replaces
+   * matches of `mask` with `placeholder` (default `''`). An unknown name or a
+   * mask that does not compile throws.
+   */
+  export type Normalizer =
+    | 'nfc'
+    | 'nfkc'
+    | 'collapseWhitespace'
+    | 'stripDiacritics'
+    | { mask: string | RegExp; placeholder?: string };
+
   /**
    * Common options for most diff algorithms.
    */
   export interface DiffOptions {
     /**
-     * When true, character case is ignored when comparing strings.
+     * When true, character case is ignored when comparing strings, using
+     * full Unicode case folding (`'Straße'` equals `'STRASSE'`).
      * @default false
      */
     ignoreCase?: boolean;
+    /**
+     * Language tag tailoring `ignoreCase`. `'tr'` and `'az'` pair `I` with
+     * dotless `ı` and `İ` with `i`.
+     */
+    locale?: string;
     /**
      * When true, returns a separate change object for each token.
      * When false (default), consecutive changes of the same type (add/remove) are merged.
      * @default false
      */
     oneChangePerToken?: boolean;
+    /**
+     * Largest edit distance, in tokens, worth computing. A diff that needs
+     * more edits returns an empty array instead.
+     */
+    maxEditLength?: number;
     /**
      * When true, whitespace is ignored when comparing strings.
      * @default false
      */
     ignoreWhitespace?: boolean;
+    /**
+     * Combined token count of both inputs above which the diff runs in
+     * linear memory (Myers' divide-and-conquer variant). Smaller inputs use
+     * the standard engine.
+     * @default 100000
+     */
+    linearSpaceThreshold?: number;
+    /**
+     * Milliseconds after which the search stops early. The remaining input is
+     * then diffed coarsely, so the result is still a valid diff but may not be
+     * minimal.
+     */
+    timeout?: number;
+    /**
+     * Custom token equality, called with a token from the old and the new
+     * input. Replaces the built-in comparison, including `ignoreCase`; with
+     * `algorithm: 'patience' | 'histogram'` the diff falls back to Myers. The
+     * first exception it throws stops the diff and is rethrown, or rejects
+     * the promise of the async differs.
+     */
+    comparator?: (left: string, right: string) => boolean;
+    /**
+     * Rewrites applied, in order, to tokens before they are compared. They
+     * run before `ignoreCase` folding, so masks see the original case. The
+     * changes still carry the original text. Ignored when `comparator` is set.
+     */
+    normalizers?: Normalizer[];
+    /**
+     * Cleanup pass for `diffChars`, `diffWords` and `diffWordsWithSpace`,
+     * modelled on diff-match-patch. `'semantic'` folds short coincidental
+     * equalities into the surrounding edits and aligns edits to word and line
+     * boundaries; `'efficiency'` folds equalities that are cheaper to re-type.
+     * Has no effect with `oneChangePerToken` or `granularity: 'grapheme'`, nor
+     * when tokens can match without being identical, since folding an
+     * equality into an edit would report the new text as removed: with
+     * `ignoreCase`, `normalizers` or `comparator`, and in `diffWords` unless
+     * `ignoreWhitespace` is `false`, as its words match whatever whitespace
+     * surrounds them.
+     * @default 'none'
+     */
+    cleanup?: DiffCleanup;
+    /**
+     * What `diffChars` treats as one character: a code point, or an extended
+     * grapheme cluster so that emoji sequences, flags and combining accents
+     * are never split. Counts are in the same unit. `'grapheme'` skips
+     * `cleanup`, which moves edit boundaries by code point and could split a
+     * cluster again. Only `diffChars`, `diffCharsAsync` and `similarity` with
+     * `tokenizer: 'chars'` read it; the other diffs ignore it.
+     * @default 'codepoint'
+     */
+    granularity?: 'codepoint' | 'grapheme';
+    /**
+     * When true, every change gets a `position` with its token index, byte
+     * and UTF-16 offsets and line/column in both inputs. Offsets index each
+     * input's own text, even where unchanged tokens only match loosely, e.g.
+     * under `ignoreWhitespace` or `ignoreCase`.
+     * @default false
+     */
+    positions?: boolean;
   }
 
+  /**
+   * Asks a text diff for a compact edit script instead of change objects: a
+   * `Uint32Array` of `(op, oldStart, oldLen, newStart, newLen)` quintuples,
+   * one per run of equal (`op` 0), inserted (1) or deleted (2) tokens. No
+   * strings are copied out of the diff, and word whitespace handling and
+   * `cleanup` are skipped. With `stripTrailingCr` the ranges still index the
+   * original inputs, and a line's range includes its `\r`.
+   */
+  export interface OpsOptions {
+    output: 'ops';
+    /**
+     * Unit of the ranges: UTF-16 code units for `String.prototype.slice`,
+     * UTF-8 bytes, or token indices.
+     * @default 'utf16'
+     */
+    opsUnit?: 'utf16' | 'byte' | 'token';
+  }
+
+  /**
+   * Options for `diffWords`.
+   */
+  export interface DiffWordsOptions extends DiffOptions {
+    /**
+     * `'unicode'` splits words on Unicode word boundaries (UAX #29), so that
+     * Chinese, Japanese and Thai text is diffed word by word rather than in
+     * long runs. Passing jsdiff's `intlSegmenter` selects it too; the
+     * segmenter itself is not called. `'code'` additionally splits
+     * camelCase, PascalCase, snake_case and kebab-case identifiers and
+     * digit runs, so renaming `getUserById` to `getUserByEmail` only
+     * changes `Id`.
+     * @default 'jsdiff'
+     */
+    wordBoundaries?: 'jsdiff' | 'unicode' | 'code';
+    /** jsdiff compatibility: any `Intl.Segmenter` selects `wordBoundaries: 'unicode'`. */
+    intlSegmenter?: object;
+  }
+
+  /**
+   * Options for `diffSentences`.
+   */
+  export interface DiffSentencesOptions extends DiffOptions {
+    /**
+     * `'unicode'` splits sentences on Unicode sentence boundaries (UAX #29):
+     * `'3.14'` and `'e.g. this'` stay whole, `。！？` end a sentence, and so
+     * does an ellipsis followed by a capitalised word. A sentence never ends
+     * on a known abbreviation such as `'Dr.'`.
+     * @default 'jsdiff'
+     */
+    sentenceBoundaries?: 'jsdiff' | 'unicode';
+    /**
+     * Abbreviations added to the built-in list for `sentenceBoundaries:
+     * 'unicode'`, with or without the final period, e.g. `['ca.', 'Gen.']`.
+     */
+    abbreviations?: string[];
+  }
+
+  /**
+   * Options for `diffCode`.
+   */
+  export interface DiffCodeOptions extends DiffOptions {
+    /**
+     * Lexical rules to tokenize by. `'c'` covers C, C++, Java, C#, Go, Rust,
+     * JavaScript and TypeScript; `'hash'` covers Python, Ruby, shell and
+     * YAML (`#` comments, triple-quoted strings); `'sql'` has `--` comments
+     * and quotes escaped by doubling.
+     * @default 'c'
+     */
+    language?: 'c' | 'hash' | 'sql';
+  }
+
+  /**
+   * Options for `diffWithPattern`. Patterns use Rust `regex` syntax, which
+   * has no lookaround or backreferences; a `RegExp` passes its source and its
+   * `i`, `m` and `s` flags. Compiled patterns are cached between calls.
+   */
+  export interface DiffPatternOptions extends DiffOptions {
+    /** Each match is a token, and so is the text between matches. */
+    tokenPattern: string | RegExp;
+    /** Text, such as timestamps, left out when tokens are compared. */
+    ignorePattern?: string | RegExp;
+  }
+
+  /**
+   * Cleanup pass run over character and word diffs.
+   */
+  export type DiffCleanup = 'semantic' | 'efficiency' | 'none';
+
   /**
    * Options specific to line-based diff algorithms.
    */
@@ -69,6 +264,36 @@ declare module 'diff-native' {
      * @default false
      */
     stripTrailingCr?: boolean;
+    /**
+     * Diff algorithm used to align lines. `'patience'` anchors on lines that
+     * are unique to both inputs, which keeps moved blocks and braces readable;
+     * `'histogram'` matches `git diff --histogram`.
+     * @default 'myers'
+     */
+    algorithm?: DiffAlgorithm;
+    /**
+     * When true, insertions and deletions that could sit at several places
+     * are moved to where `git diff --indent-heuristic` puts them, so e.g. an
+     * added function keeps its own closing brace.
+     * @default false for diffLines, true for patch creation
+     */
+    indentHeuristic?: boolean;
+  }
+
+  /**
+   * Line alignment algorithm.
+   */
+  export type DiffAlgorithm = 'myers' | 'patience' | 'histogram';
+
+  /**
+   * Options for patch creation functions.
+   */
+  export interface PatchOptions extends DiffLinesOptions {
+    /**
+     * Number of context lines to include around each hunk.
+     * @default 4
+     */
+    context?: number;
   }
 
   /**
@@ -149,6 +374,23 @@ declare module 'diff-native' {
     hunks: Hunk[];
   }
 
+  /**
+   * Extra options accepted by the `*Async` diff functions.
+   */
+  export interface AsyncDiffOptions {
+    /**
+     * Called between slices with the edit distance explored so far. For
+     * `patience`, `histogram` and inputs above `linearSpaceThreshold`, which
+     * yield between the segments they split the inputs into, it is the number
+     * of tokens found changed so far.
+     */
+    onProgress?: (editLength: number) => void;
+    /**
+     * Cancels the diff; the returned promise rejects with the signal's reason.
+     */
+    signal?: AbortSignal;
+  }
+
   /**
    * Diffs two blocks of text, treating each word, punctuation mark, newline, or run of whitespace as a token.
    *
@@ -161,6 +403,11 @@ declare module 'diff-native' {
    * @param options Optional configuration options.
    * @returns An array of change objects.
    */
+  export function diffWordsWithSpace(
+    oldStr: string,
+    newStr: string,
+    options: DiffOptions & OpsOptions
+  ): Uint32Array;
   export function diffWordsWithSpace(
     oldStr: string,
     newStr: string,
@@ -177,7 +424,33 @@ declare module 'diff-native' {
    * @param options Optional configuration options.
    * @returns An array of change objects.
    */
-  export function diffWords(oldStr: string, newStr: string, options?: DiffOptions): Change[];
+  export function diffWords(
+    oldStr: string,
+    newStr: string,
+    options: DiffWordsOptions & OpsOptions
+  ): Uint32Array;
+  export function diffWords(oldStr: string, newStr: string, options?: DiffWordsOptions): Change[];
+
+  /**
+   * `diffWords` with `wordBoundaries: 'code'`: identifiers are split into
+   * their camelCase, PascalCase, snake_case and kebab-case parts and digit
+   * runs.
+   *
+   * @param oldStr The original source text.
+   * @param newStr The new source text to compare against.
+   * @param options Optional configuration options.
+   * @returns An array of change objects.
+   */
+  export function diffCodeWords(
+    oldStr: string,
+    newStr: string,
+    options: DiffWordsOptions & OpsOptions
+  ): Uint32Array;
+  export function diffCodeWords(
+    oldStr: string,
+    newStr: string,
+    options?: DiffWordsOptions
+  ): Change[];
 
   /**
    * Diffs two blocks of text, treating each Unicode character as a token.
@@ -188,6 +461,11 @@ declare module 'diff-native' {
    * @param options Optional configuration options.
    * @returns An array of change objects.
    */
+  export function diffChars(
+    oldStr: string,
+    newStr: string,
+    options: DiffOptions & OpsOptions
+  ): Uint32Array;
   export function diffChars(oldStr: string, newStr: string, options?: DiffOptions): Change[];
 
   /**
@@ -199,6 +477,11 @@ declare module 'diff-native' {
    * @param options Optional configuration options specific to line diffing.
    * @returns An array of change objects.
    */
+  export function diffLines(
+    oldStr: string,
+    newStr: string,
+    options: DiffLinesOptions & OpsOptions
+  ): Uint32Array;
   export function diffLines(oldStr: string, newStr: string, options?: DiffLinesOptions): Change[];
 
   /**
@@ -210,6 +493,11 @@ declare module 'diff-native' {
    * @param options Optional configuration options specific to line diffing.
    * @returns An array of change objects.
    */
+  export function diffTrimmedLines(
+    oldStr: string,
+    newStr: string,
+    options: DiffLinesOptions & OpsOptions
+  ): Uint32Array;
   export function diffTrimmedLines(
     oldStr: string,
     newStr: string,
@@ -219,14 +507,24 @@ declare module 'diff-native' {
   /**
    * Diffs two blocks of text, treating each sentence as a token.
    * Sentences are delimited by periods, question marks, and exclamation marks
-   * followed by whitespace.
+   * followed by whitespace, or by Unicode sentence boundaries with
+   * `sentenceBoundaries: 'unicode'`.
    *
    * @param oldStr The original string containing sentences.
    * @param newStr The new string containing sentences to compare against.
    * @param options Optional configuration options.
    * @returns An array of change objects.
    */
-  export function diffSentences(oldStr: string, newStr: string, options?: DiffOptions): Change[];
+  export function diffSentences(
+    oldStr: string,
+    newStr: string,
+    options: DiffSentencesOptions & OpsOptions
+  ): Uint32Array;
+  export function diffSentences(
+    oldStr: string,
+    newStr: string,
+    options?: DiffSentencesOptions
+  ): Change[];
 
   /**
    * Diffs two blocks of CSS text, with specialized handling for CSS tokens.
@@ -237,8 +535,51 @@ declare module 'diff-native' {
    * @param options Optional configuration options.
    * @returns An array of change objects.
    */
+  export function diffCss(
+    oldStr: string,
+    newStr: string,
+    options: DiffOptions & OpsOptions
+  ): Uint32Array;
   export function diffCss(oldStr: string, newStr: string, options?: DiffOptions): Change[];
 
+  /**
+   * Diffs two blocks of source code token by token. String literals,
+   * comments, numbers, identifiers and operators are each one token, and so
+   * is every run of whitespace.
+   *
+   * @param oldStr The original source text.
+   * @param newStr The new source text to compare against.
+   * @param options Optional configuration options.
+   * @returns An array of change objects.
+   */
+  export function diffCode(
+    oldStr: string,
+    newStr: string,
+    options: DiffCodeOptions & OpsOptions
+  ): Uint32Array;
+  export function diffCode(oldStr: string, newStr: string, options?: DiffCodeOptions): Change[];
+
+  /**
+   * Diffs two blocks of text split into tokens by a caller-supplied regex.
+   * Joining the tokens gives back the original text.
+   *
+   * @param oldStr The original string.
+   * @param newStr The new string to compare against.
+   * @param options `tokenPattern` and optional `ignorePattern`.
+   * @returns An array of change objects.
+   * @throws When a pattern does not compile.
+   */
+  export function diffWithPattern(
+    oldStr: string,
+    newStr: string,
+    options: DiffPatternOptions & OpsOptions
+  ): Uint32Array;
+  export function diffWithPattern(
+    oldStr: string,
+    newStr: string,
+    options: DiffPatternOptions
+  ): Change[];
+
   /**
    * Diffs two JSON-serializable objects by first serializing them to prettily-formatted JSON
    * and then treating each line of the JSON as a token.
@@ -254,6 +595,92 @@ declare module 'diff-native' {
    */
   export function diffJson(oldVal: any, newVal: any, options?: JsonOptions | null): Change[];
 
+  /**
+   * A change produced by `diffArrays`; `value` is the slice of elements in this segment.
+   */
+  export interface ArrayChange<T> {
+    /** The elements of this change segment */
+    value: T[];
+    /** True if these elements were added in the new array */
+    added: boolean;
+    /** True if these elements were removed from the old array */
+    removed: boolean;
+    /** Number of elements in this segment */
+    count: number;
+  }
+
+  /**
+   * Options for `diffArrays`.
+   */
+  export interface DiffArraysOptions<T> extends Omit<DiffOptions, 'ignoreCase' | 'normalizers'> {
+    /**
+     * Decides whether an old and a new element are equal. Without it, plain
+     * objects and arrays are compared structurally and everything else with `===`.
+     */
+    comparator?: (left: T, right: T) => boolean;
+  }
+
+  /**
+   * Diffs two arrays of arbitrary values, element by element.
+   *
+   * @param oldArr The original array.
+   * @param newArr The new array to compare against.
+   * @param options Optional configuration options.
+   * @returns An array of change objects whose values are slices of the inputs.
+   */
+  export function diffArrays<T>(
+    oldArr: T[],
+    newArr: T[],
+    options?: DiffArraysOptions<T>
+  ): ArrayChange<T>[];
+
+  /**
+   * Hooks for `createDiff`, named after the jsdiff `Diff` methods they
+   * replace. Each receives the options passed to `diff`.
+   */
+  export interface DiffHooks<Input = string, Token = string, Value = string> {
+    /** Splits an input into tokens. */
+    tokenize(value: Input, options: DiffOptions): Token[];
+    /**
+     * Token equality. By default string tokens are compared honouring
+     * `ignoreCase` and `normalizers`, and other tokens with `===`.
+     */
+    equals?(left: Token, right: Token, options: DiffOptions): boolean;
+    /** Builds a change value from its tokens; by default `tokens.join('')`. */
+    join?(tokens: Token[], options: DiffOptions): Value;
+    /** Rewrites the finished changes. */
+    postProcess?(changes: CustomChange<Value>[], options: DiffOptions): CustomChange<Value>[];
+  }
+
+  export interface CustomChange<Value = string> {
+    value: Value;
+    count: number;
+    added: boolean;
+    removed: boolean;
+  }
+
+  /** Reusable differ returned by `createDiff`. */
+  export interface CustomDiff<Input = string, Token = string, Value = string> {
+    /**
+     * Diffs `oldValue` against `newValue`. Returns an empty array when
+     * `maxEditLength` is exceeded.
+     */
+    diff(oldValue: Input, newValue: Input, options?: DiffOptions): CustomChange<Value>[];
+    /** Releases the differ's WebAssembly memory. */
+    free(): void;
+  }
+
+  /**
+   * Builds a differ from JS hooks, the way jsdiff users subclass `Diff`.
+   * The tokens from `tokenize` are diffed by the native Myers engine.
+   *
+   * @param hooks `tokenize` and the optional `equals`, `join` and `postProcess`.
+   * @returns A differ that can be reused across calls.
+   */
+  export function createDiff<Input = string, Token = string, Value = string>(
+    hooks: DiffHooks<Input, Token, Value>
+  ): CustomDiff<Input, Token, Value>;
+
   /**
    * Converts an array of change objects to an XML string.
    *
@@ -265,6 +692,65 @@ declare module 'diff-native' {
    */
   export function convertChangesToXML(changes: Change[]): string;
 
+  /**
+   * Runs a cleanup pass over existing changes, e.g. from `diffChars`.
+   * Changes whose text is rewritten get their `count` in characters.
+   *
+   * @param changes Array of change objects to clean up.
+   * @param mode Cleanup to run.
+   * @default mode 'semantic'
+   * @returns The cleaned-up changes.
+   */
+  export function cleanupChanges(changes: Change[], mode?: DiffCleanup): Change[];
+
+  /**
+   * How much two texts have in common, computed without building a diff.
+   */
+  export interface Similarity {
+    /** Token count of the old input. */
+    oldLen: number;
+    /** Token count of the new input. */
+    newLen: number;
+    /** Length of the longest common subsequence of tokens. */
+    lcs: number;
+    /** Tokens inserted plus tokens deleted. */
+    editDistance: number;
+    /** `2 * lcs / (oldLen + newLen)`, like Python's `difflib` ratio; 1 for two empty inputs. */
+    ratio: number;
+  }
+
+  export interface SimilarityOptions
+    extends DiffLinesOptions,
+      DiffWordsOptions,
+      DiffSentencesOptions,
+      DiffCodeOptions {
+    /**
+     * Which diff's tokens to compare.
+     * @default 'chars'
+     */
+    tokenizer?:
+      | 'chars'
+      | 'words'
+      | 'wordsWithSpace'
+      | 'lines'
+      | 'trimmedLines'
+      | 'sentences'
+      | 'css'
+      | 'code';
+  }
+
+  /**
+   * LCS length, edit distance and similarity ratio of two texts, e.g. for
+   * deduplication or rename detection.
+   *
+   * @returns `null` when the edit distance exceeds `maxEditLength`.
+   */
+  export function similarity(
+    oldStr: string,
+    newStr: string,
+    options?: SimilarityOptions
+  ): Similarity | null;
+
   /**
    * Parses a unified diff patch string into structured patch objects.
    *
@@ -293,7 +779,7 @@ declare module 'diff-native' {
     newStr: string,
     oldHeader?: string,
     newHeader?: string,
-    options?: { context?: number }
+    options?: PatchOptions
   ): string;
 
   /**
@@ -316,7 +802,7 @@ declare module 'diff-native' {
     newStr: string,
     oldHeader?: string,
     newHeader?: string,
-    options?: { context?: number }
+    options?: PatchOptions
   ): string;
 
   /**
@@ -342,7 +828,7 @@ declare module 'diff-native' {
     newStr: string,
     oldHeader?: string,
     newHeader?: string,
-    options?: { context?: number }
+    options?: PatchOptions
   ): StructuredPatch;
 
   /**
@@ -437,6 +923,92 @@ declare module 'diff-native' {
     patch: string | StructuredPatch | StructuredPatch[]
   ): string | StructuredPatch | StructuredPatch[];
 
+  /**
+   * Asynchronous variants of the diff functions. The diff runs in short slices
+   * and yields to the event loop between them, so large inputs do not block
+   * the main thread.
+   */
+  export function diffCharsAsync(
+    oldStr: string,
+    newStr: string,
+    options: DiffOptions & AsyncDiffOptions & OpsOptions
+  ): Promise<Uint32Array>;
+  export function diffCharsAsync(
+    oldStr: string,
+    newStr: string,
+    options?: DiffOptions & AsyncDiffOptions
+  ): Promise<Change[]>;
+  export function diffWordsAsync(
+    oldStr: string,
+    newStr: string,
+    options: DiffWordsOptions & AsyncDiffOptions & OpsOptions
+  ): Promise<Uint32Array>;
+  export function diffWordsAsync(
+    oldStr: string,
+    newStr: string,
+    options?: DiffWordsOptions & AsyncDiffOptions
+  ): Promise<Change[]>;
+  export function diffWordsWithSpaceAsync(
+    oldStr: string,
+    newStr: string,
+    options: DiffOptions & AsyncDiffOptions & OpsOptions
+  ): Promise<Uint32Array>;
+  export function diffWordsWithSpaceAsync(
+    oldStr: string,
+    newStr: string,
+    options?: DiffOptions & AsyncDiffOptions
+  ): Promise<Change[]>;
+  export function diffLinesAsync(
+    oldStr: string,
+    newStr: string,
+    options: DiffLinesOptions & AsyncDiffOptions & OpsOptions
+  ): Promise<Uint32Array>;
+  export function diffLinesAsync(
+    oldStr: string,
+    newStr: string,
+    options?: DiffLinesOptions & AsyncDiffOptions
+  ): Promise<Change[]>;
+  export function diffTrimmedLinesAsync(
+    oldStr: string,
+    newStr: string,
+    options: DiffLinesOptions & AsyncDiffOptions & OpsOptions
+  ): Promise<Uint32Array>;
+  export function diffTrimmedLinesAsync(
+    oldStr: string,
+    newStr: string,
+    options?: DiffLinesOptions & AsyncDiffOptions
+  ): Promise<Change[]>;
+  export function diffSentencesAsync(
+    oldStr: string,
+    newStr: string,
+    options: DiffSentencesOptions & AsyncDiffOptions & OpsOptions
+  ): Promise<Uint32Array>;
+  export function diffSentencesAsync(
+    oldStr: string,
+    newStr: string,
+    options?: DiffSentencesOptions & AsyncDiffOptions
+  ): Promise<Change[]>;
+  export function diffCssAsync(
+    oldStr: string,
+    newStr: string,
+    options: DiffOptions & AsyncDiffOptions & OpsOptions
+  ): Promise<Uint32Array>;
+  export function diffCssAsync(
+    oldStr: string,
+    newStr: string,
+    options?: DiffOptions & AsyncDiffOptions
+  ): Promise<Change[]>;
+  export function diffCodeAsync(
+    oldStr: string,
+    newStr: string,
+    options: DiffCodeOptions & AsyncDiffOptions & OpsOptions
+  ): Promise<Uint32Array>;
+  export function diffCodeAsync(
+    oldStr: string,
+    newStr: string,
+    options?: DiffCodeOptions & AsyncDiffOptions
+  ): Promise<Change[]>;
+
   /**
    * Utility object for working with line-level diffs.
    */
@@ -463,9 +1035,10 @@ declare module 'diff-native' {
      * This can be useful for custom implementations or for debugging.
      *
      * @param text The text to tokenize.
+     * @param options `wordBoundaries` (or `intlSegmenter`) as for `diffWords`.
      * @returns An array of word tokens.
      */
-    tokenize(text: string): string[];
+    tokenize(text: string, options?: DiffWordsOptions): string[];
   };
 
   /**
@@ -479,9 +1052,10 @@ declare module 'diff-native' {
      * This can be useful for custom implementations or for debugging.
      *
      * @param text The text to tokenize.
+     * @param options `sentenceBoundaries` and `abbreviations` as for `diffSentences`.
      * @returns An array of sentence tokens.
      */
-    tokenize(text: string): string[];
+    tokenize(text: string, options?: DiffSentencesOptions): string[];
   };
 
   /**